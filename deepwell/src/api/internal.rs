@@ -24,8 +24,10 @@
 
 use crate::api::ApiServer;
 use crate::methods::{
-    category::*, file::*, file_revision::*, link::*, locales::*, misc::*, page::*,
-    page_revision::*, parent::*, site::*, text::*, user::*, vote::*,
+    audit::*, auth::*, block::*, category::*, file::*, file_revision::*, forum::*,
+    link::*, locales::*, membership::*, message::*, mfa::*, misc::*, page::*,
+    page_revision::*, parent::*, redirect::*, role::*, search::*, site::*, tag::*,
+    text::*, token::*, user::*, vote::*,
 };
 use crate::web::utils::error_response;
 use tide::StatusCode;
@@ -36,6 +38,7 @@ pub fn build(mut app: ApiServer) -> ApiServer {
     app.at("/version").get(version);
     app.at("/version/full").get(full_version);
     app.at("/ratelimit-exempt").all(ratelimit_exempt);
+    app.at("/render-cache").get(render_cache_get);
     app.at("/teapot")
         .get(|_| async { error_response(StatusCode::ImATeapot, "🫖") });
 
@@ -54,6 +57,10 @@ pub fn build(mut app: ApiServer) -> ApiServer {
         .head(site_head)
         .get(site_get);
 
+    app.at("/site/:type/:id_or_slug/settings")
+        .get(site_settings_get)
+        .put(site_settings_put);
+
     // Category
     app.at("/category/:site_id").get(category_all_get);
 
@@ -63,7 +70,8 @@ pub fn build(mut app: ApiServer) -> ApiServer {
 
     app.at("/category/:site_id/:type/:id_or_slug")
         .head(category_head)
-        .get(category_get);
+        .get(category_get)
+        .put(category_put);
 
     // Page
     app.at("/page/direct/:page_id")
@@ -83,6 +91,10 @@ pub fn build(mut app: ApiServer) -> ApiServer {
     app.at("/page/:site_id/:page_id/rerender")
         .post(page_rerender);
 
+    app.at("/page/rerender/sweep")
+        .get(page_rerender_sweep_get)
+        .post(page_rerender_sweep_post);
+
     app.at("/page/:site_id/:page_id/restore").post(page_restore);
 
     // Page revisions
@@ -100,6 +112,19 @@ pub fn build(mut app: ApiServer) -> ApiServer {
     app.at("/page/:site_id/:type/:id_or_slug/revision/:revision_number/:direction")
         .get(page_revision_range_get);
 
+    // Page redirects
+    app.at("/page/:site_id/:type/:id_or_slug/redirects")
+        .get(page_redirects_get)
+        .post(page_alias_create);
+
+    app.at("/page/:site_id/redirect/:slug")
+        .delete(page_redirect_delete);
+
+    // Page discussions
+    app.at("/page/:site_id/:type/:id_or_slug/comments")
+        .get(page_comments_get)
+        .post(page_comment_create);
+
     // Page links
     app.at("/page/:site_id/:type/:id_or_slug/links/from")
         .get(page_links_from_get);
@@ -128,12 +153,76 @@ pub fn build(mut app: ApiServer) -> ApiServer {
     app.at("/page/:site_id/:relationship_type/:type/:id_or_slug")
         .get(parent_relationships_get);
 
+    app.at("/page/:site_id/ancestors/:type/:id_or_slug")
+        .get(parent_ancestors_get);
+
+    app.at("/page/:site_id/descendants/:type/:id_or_slug")
+        .get(parent_descendants_get);
+
+    app.at("/page/:site_id/siblings/:type/:id_or_slug")
+        .get(parent_siblings_get);
+
     // Page (invalid routes)
     app.at("/page").all(page_invalid);
     app.at("/page/:type/:id_or_slug").all(page_invalid);
     app.at("/page/:site_id/id/:page_slug/links/to/missing")
         .all(page_invalid);
 
+    // Link reports
+    app.at("/report/:site_id/wanted")
+        .get(link_report_wanted_get);
+    app.at("/report/:site_id/orphaned")
+        .get(link_report_orphaned_get);
+    app.at("/report/:site_id/dead-ends")
+        .get(link_report_dead_ends_get);
+
+    // Search
+    app.at("/search/:site_id").get(search_get);
+
+    // Tags
+    app.at("/tag/:site_id").get(tag_all_get);
+    app.at("/tag/:site_id/pages").get(tag_pages_get);
+    app.at("/tag/:site_id/rename").post(tag_rename);
+    app.at("/tag/:site_id/allowed")
+        .get(tag_allowed_get)
+        .put(tag_allowed_put);
+
+    // Messages
+    app.at("/message").post(message_send);
+    app.at("/message/:user_id/folder/:folder")
+        .get(message_folder_get);
+    app.at("/message/:user_id/:message_id")
+        .get(message_get)
+        .put(message_put)
+        .delete(message_delete);
+
+    // Forum
+    app.at("/forum/:site_id").get(forum_get);
+    app.at("/forum/:site_id/group").post(forum_group_create);
+    app.at("/forum/:site_id/category")
+        .post(forum_category_create);
+    app.at("/forum/:site_id/category/:category_id")
+        .get(forum_category_get)
+        .put(forum_category_put);
+    app.at("/forum/:site_id/category/:category_id/threads")
+        .get(forum_threads_get);
+
+    app.at("/forum/:site_id/thread").post(forum_thread_create);
+    app.at("/forum/:site_id/thread/:thread_id")
+        .get(forum_thread_get)
+        .put(forum_thread_put)
+        .delete(forum_thread_delete);
+    app.at("/forum/:site_id/thread/:thread_id/posts")
+        .get(forum_posts_get);
+
+    app.at("/forum/:site_id/post").post(forum_post_create);
+    app.at("/forum/:site_id/post/:post_id")
+        .get(forum_post_get)
+        .put(forum_post_edit)
+        .delete(forum_post_delete);
+    app.at("/forum/:site_id/post/:post_id/revisions")
+        .get(forum_post_revisions_get);
+
     // Files
     app.at("/file/direct/:file_id")
         .head(file_head_direct)
@@ -170,6 +259,28 @@ pub fn build(mut app: ApiServer) -> ApiServer {
     app.at("/text").put(text_put);
     app.at("/text/:hash").get(text_get).head(text_head);
 
+    // Authentication
+    app.at("/auth/login").post(auth_login);
+
+    // Multi-factor authentication
+    app.at("/mfa/:user_id").post(mfa_enable).delete(mfa_disable);
+
+    app.at("/mfa/:user_id/setup").get(mfa_setup);
+    app.at("/mfa/:user_id/verify").post(mfa_verify);
+    app.at("/mfa/:user_id/recovery").post(mfa_recovery_reset);
+
+    // Email tokens
+    app.at("/token/:user_id/email-verification")
+        .post(token_email_verification_create);
+    app.at("/token/:user_id/email-change")
+        .post(token_email_change_create);
+    app.at("/token/password-reset")
+        .post(token_password_reset_create);
+    app.at("/token/verify-email").post(token_verify_email);
+    app.at("/token/confirm-email-change")
+        .post(token_confirm_email_change);
+    app.at("/token/reset-password").post(token_reset_password);
+
     // User
     app.at("/user").post(user_create);
     app.at("/user/:type/:id_or_slug")
@@ -177,6 +288,55 @@ pub fn build(mut app: ApiServer) -> ApiServer {
         .get(user_get)
         .put(user_put)
         .delete(user_delete);
+    app.at("/user/:type/:id_or_slug/aliases")
+        .get(user_aliases_get);
+
+    // Roles and permissions
+    app.at("/role/:site_id").get(roles_get).post(role_create);
+    app.at("/role/:site_id/:role_id")
+        .get(role_get)
+        .put(role_put)
+        .delete(role_delete);
+
+    app.at("/role/:site_id/user/:user_id")
+        .get(user_role_get)
+        .put(user_role_put)
+        .delete(user_role_delete);
+
+    app.at("/role/:site_id/category/:category_id")
+        .get(category_permissions_get)
+        .put(category_permissions_put);
+
+    // Membership
+    app.at("/membership/:site_id").get(members_get);
+    app.at("/membership/:site_id/join").post(membership_join);
+    app.at("/membership/:site_id/join/:user_id")
+        .get(join_options_get);
+    app.at("/membership/:site_id/leave").post(membership_leave);
+    app.at("/membership/:site_id/application")
+        .get(applications_get)
+        .post(application_create);
+    app.at("/membership/:site_id/application/:application_id")
+        .put(application_put);
+    app.at("/membership/:site_id/invitation")
+        .post(invitation_create);
+
+    app.at("/invitation/:invitation_id").put(invitation_put);
+    app.at("/invitation/user/:user_id").get(invitations_get);
+
+    // Bans and blocks
+    app.at("/ban/:site_id")
+        .get(site_bans_get)
+        .post(site_ban_create)
+        .delete(site_ban_delete);
+
+    app.at("/block").post(user_block_create);
+    app.at("/block/:user_id").get(user_blocks_get);
+    app.at("/block/:user_id/:blocked_user_id")
+        .delete(user_block_delete);
+
+    // Audit log
+    app.at("/audit").get(audit_get);
 
     // Votes
     app.at("/vote")
@@ -192,6 +352,14 @@ pub fn build(mut app: ApiServer) -> ApiServer {
     app.at("/vote/action").put(vote_action);
     app.at("/vote/list").get(vote_list_get);
     app.at("/vote/count").get(vote_count_get);
+    app.at("/vote/analytics").get(vote_analytics_get);
+
+    app.at("/vote/analysis/:site_id")
+        .get(vote_analysis_list_get)
+        .post(vote_analysis_create);
+
+    app.at("/vote/analysis/report/:report_id")
+        .get(vote_analysis_get);
 
     app
 }
@@ -26,11 +26,13 @@
 //! This module should only contain definitions for the web server such as its routes, and
 //! not any of the implementations themselves. Those should be in the `methods` module.
 
+use crate::cache::RenderCache;
 use crate::config::Config;
 use crate::database;
 use crate::locales::Localizations;
+use crate::mail::{build_mailer, Mailer};
 use crate::services::blob::spawn_magic_thread;
-use crate::services::job::JobRunner;
+use crate::services::job::{JobRunner, RerenderSweep};
 use crate::web::ratelimit::GovernorMiddleware;
 use anyhow::Result;
 use s3::bucket::Bucket;
@@ -52,6 +54,8 @@ pub struct ServerState {
     pub database: DatabaseConnection,
     pub localizations: Localizations,
     pub s3_bucket: Bucket,
+    pub render_cache: RenderCache,
+    pub mailer: Box<dyn Mailer>,
 }
 
 pub async fn build_server(config: Config) -> Result<ApiServer> {
@@ -75,12 +79,20 @@ pub async fn build_server(config: Config) -> Result<ApiServer> {
         config.aws_credentials.clone(),
     )?;
 
+    // Create compiled HTML cache
+    let render_cache = RenderCache::new(config.render_cache_size);
+
+    // Create mail sender
+    let mailer = build_mailer(&config);
+
     // Create server state
     let state = Arc::new(ServerState {
         config,
         database,
         localizations,
         s3_bucket,
+        render_cache,
+        mailer,
     });
 
     macro_rules! new {
@@ -92,6 +104,11 @@ pub async fn build_server(config: Config) -> Result<ApiServer> {
     // Start job executor task
     JobRunner::spawn(&state);
 
+    // Rerender pages from older ftml versions
+    if state.config.rerender_sweep_on_startup {
+        RerenderSweep::start(&state);
+    }
+
     // Start MIME evaluator thread
     spawn_magic_thread();
 
@@ -20,21 +20,87 @@
 
 //! Routes for version 1 of the API.
 //!
-//! Because it is not yet stabilized, it is a stub.
+//! This version is stable, and is a read-only API for third-party tools.
+//! Every route here must be described in the OpenAPI document, which is
+//! checked by the tests below.
 
 use crate::api::ApiServer;
-use crate::web::utils::error_response;
-use tide::StatusCode;
-
-pub fn build(mut app: ApiServer) -> ApiServer {
-    let not_implemented = |_| async {
-        error_response(
-            StatusCode::NotImplemented,
-            "API v1 has not yet been stablized",
-        )
+use crate::methods::v1::*;
+
+macro_rules! routes {
+    ($($path:literal => $method:ident),+ $(,)?) => {
+        pub fn build(mut app: ApiServer) -> ApiServer {
+            $(
+                app.at($path).get($method);
+            )+
+
+            app
+        }
+
+        /// All the paths in this version of the API, which only use `GET`.
+        #[cfg(test)]
+        const PATHS: &[&str] = &[$($path),+];
     };
+}
+
+routes! {
+    // Meta
+    "/openapi.json" => openapi_get,
+
+    // Sites
+    "/site/:site_slug" => site_get,
+    "/site/:site_slug/tag" => tags_get,
+
+    // Pages
+    "/site/:site_slug/page" => pages_get,
+    "/site/:site_slug/page/:page_slug" => page_get,
+    "/site/:site_slug/page/:page_slug/revision" => revisions_get,
+    "/site/:site_slug/page/:page_slug/revision/:revision_number" => revision_get,
+    "/site/:site_slug/page/:page_slug/vote" => votes_get,
+
+    // Files
+    "/site/:site_slug/page/:page_slug/file" => files_get,
+    "/site/:site_slug/page/:page_slug/file/:filename" => file_get,
+
+    // Users
+    "/user/:user_slug" => user_get,
+}
+
+#[test]
+fn openapi_paths() {
+    use serde_json::Value as JsonValue;
+    use std::collections::BTreeSet;
+
+    let document: JsonValue = serde_json::from_str(OPENAPI_DOCUMENT)
+        .expect("OpenAPI document is not valid JSON");
+
+    // Convert from tide's ":name" parameters to OpenAPI's "{name}"
+    let expected = PATHS
+        .iter()
+        .map(|path| {
+            path.split('/')
+                .map(|part| match part.strip_prefix(':') {
+                    Some(name) => format!("{{{name}}}"),
+                    None => str!(part),
+                })
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect::<BTreeSet<_>>();
+
+    let paths = document["paths"]
+        .as_object()
+        .expect("OpenAPI document has no paths");
+
+    let actual = paths.keys().cloned().collect::<BTreeSet<_>>();
+    assert_eq!(actual, expected, "Routes differ from OpenAPI document");
 
-    app.at("/").all(not_implemented);
-    app.at("/*").all(not_implemented);
-    app
+    for (path, item) in paths {
+        let operations = item.as_object().expect("Path item is not an object");
+        assert!(
+            operations.contains_key("get"),
+            "No GET operation for {path}"
+        );
+        assert_eq!(operations.len(), 1, "Unexpected operations for {path}");
+    }
 }
@@ -0,0 +1,200 @@
+/*
+ * cache.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! An in-memory cache of compiled page HTML.
+//!
+//! Entries are keyed by the hash of their contents, so they never go stale:
+//! rerendering a page produces a new hash rather than modifying an existing entry.
+//! Once the cache grows past its size limit, the least recently used entries
+//! are evicted.
+
+use crate::hash::Hash;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+#[derive(Debug)]
+pub struct RenderCache {
+    inner: Mutex<CacheInner>,
+    capacity: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl RenderCache {
+    /// Creates a new cache which holds at most `capacity` bytes of text.
+    pub fn new(capacity: usize) -> Self {
+        RenderCache {
+            inner: Mutex::new(CacheInner::default()),
+            capacity,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, hash: &Hash) -> Option<String> {
+        let contents = self.lock().get(hash);
+        let counter = match contents {
+            Some(_) => &self.hits,
+            None => &self.misses,
+        };
+
+        counter.fetch_add(1, Ordering::Relaxed);
+        contents
+    }
+
+    pub fn insert(&self, hash: Hash, contents: String) {
+        // Don't flush the whole cache for one oversized entry
+        if contents.len() > self.capacity {
+            return;
+        }
+
+        let mut inner = self.lock();
+        inner.insert(hash, contents);
+        inner.evict(self.capacity);
+    }
+
+    pub fn stats(&self) -> RenderCacheStats {
+        let inner = self.lock();
+
+        RenderCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: inner.entries.len(),
+            size: inner.size,
+            capacity: self.capacity,
+        }
+    }
+
+    #[inline]
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheInner> {
+        self.inner.lock().expect("Render cache lock poisoned")
+    }
+}
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub size: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    entries: HashMap<Hash, CacheEntry>,
+
+    /// The keys of all entries, ordered from least to most recently used.
+    recency: BTreeMap<u64, Hash>,
+
+    /// The total number of bytes of text being held.
+    size: usize,
+
+    /// A counter incremented on every access, used to order entries.
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    contents: String,
+    last_used: u64,
+}
+
+impl CacheInner {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Marks an entry as the most recently used, if present.
+    fn touch(&mut self, hash: &Hash) -> Option<&CacheEntry> {
+        let tick = self.next_tick();
+        let entry = self.entries.get_mut(hash)?;
+
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(tick, *hash);
+        entry.last_used = tick;
+
+        Some(entry)
+    }
+
+    fn get(&mut self, hash: &Hash) -> Option<String> {
+        self.touch(hash).map(|entry| entry.contents.clone())
+    }
+
+    fn insert(&mut self, hash: Hash, contents: String) {
+        if self.touch(&hash).is_some() {
+            return;
+        }
+
+        let tick = self.next_tick();
+        self.size += contents.len();
+        self.recency.insert(tick, hash);
+        self.entries.insert(
+            hash,
+            CacheEntry {
+                contents,
+                last_used: tick,
+            },
+        );
+    }
+
+    fn evict(&mut self, capacity: usize) {
+        while self.size > capacity {
+            let oldest = match self.recency.keys().next() {
+                Some(&tick) => tick,
+                None => break,
+            };
+
+            let hash = self.recency.remove(&oldest).unwrap();
+            if let Some(entry) = self.entries.remove(&hash) {
+                self.size -= entry.contents.len();
+            }
+        }
+    }
+}
+
+#[test]
+fn lru() {
+    let cache = RenderCache::new(10);
+    let key = |n: u8| [n; 64];
+
+    cache.insert(key(1), str!("aaaa"));
+    cache.insert(key(2), str!("bbbb"));
+    assert_eq!(cache.get(&key(1)).as_deref(), Some("aaaa"));
+
+    // Entry 2 is now the least recently used, so it gets evicted
+    cache.insert(key(3), str!("cccc"));
+    assert_eq!(cache.get(&key(2)), None);
+    assert_eq!(cache.get(&key(1)).as_deref(), Some("aaaa"));
+    assert_eq!(cache.get(&key(3)).as_deref(), Some("cccc"));
+
+    // Too large to ever be cached
+    cache.insert(key(4), str!("dddddddddddd"));
+    assert_eq!(cache.get(&key(4)), None);
+
+    let stats = cache.stats();
+    assert_eq!(stats.hits, 3);
+    assert_eq!(stats.misses, 2);
+    assert_eq!(stats.entries, 2);
+    assert_eq!(stats.size, 8);
+}
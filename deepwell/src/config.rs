@@ -21,6 +21,7 @@
 use crate::info;
 use clap::{Arg, Command};
 use dotenv::dotenv;
+use rand_core::{OsRng, RngCore};
 use ref_map::*;
 use s3::{creds::Credentials, region::Region};
 use std::env;
@@ -103,6 +104,71 @@ pub struct Config {
     ///
     /// Set using environment variable `RATE_LIMIT_SECRET`.
     pub rate_limit_secret: String,
+
+    /// Whether to rerender pages compiled by an older version of ftml on startup.
+    ///
+    /// Can be set using environment variable `RERENDER_SWEEP_ON_STARTUP`.
+    pub rerender_sweep_on_startup: bool,
+
+    /// The number of pages per second a rerender sweep will queue.
+    ///
+    /// Can be set using environment variable `RERENDER_SWEEP_RATE`.
+    pub rerender_sweep_rate: NonZeroU32,
+
+    /// The maximum number of bytes of compiled HTML to keep cached in memory.
+    ///
+    /// Can be set using environment variable `RENDER_CACHE_SIZE`.
+    pub render_cache_size: usize,
+
+    /// How many times a user may change their username within each period.
+    ///
+    /// Can be set using environment variable `MAXIMUM_NAME_CHANGES`.
+    pub maximum_name_changes: i16,
+
+    /// The length of the period, in days, in which username changes are limited.
+    ///
+    /// Can be set using environment variable `NAME_CHANGE_PERIOD_DAYS`.
+    pub name_change_period_days: u32,
+
+    /// How many days a released username is reserved for its former owner.
+    ///
+    /// Can be set using environment variable `NAME_RESERVATION_DAYS`.
+    pub name_reservation_days: u32,
+
+    /// The memory cost, in KiB, used when hashing passwords with Argon2id.
+    ///
+    /// Can be set using environment variable `PASSWORD_MEMORY_COST`.
+    pub password_memory_cost: u32,
+
+    /// The number of iterations used when hashing passwords with Argon2id.
+    ///
+    /// Can be set using environment variable `PASSWORD_TIME_COST`.
+    pub password_time_cost: u32,
+
+    /// The degree of parallelism used when hashing passwords with Argon2id.
+    ///
+    /// Can be set using environment variable `PASSWORD_PARALLELISM`.
+    pub password_parallelism: u32,
+
+    /// The secret used to sign email verification and password reset tokens.
+    /// If not specified, a random secret is generated on startup, which means
+    /// tokens issued before a restart will no longer be accepted.
+    /// If a value is specified, the secret must be at least 64 bytes long.
+    ///
+    /// Set using environment variable `TOKEN_SECRET`.
+    pub token_secret: String,
+
+    /// The directory to write outgoing emails to.
+    ///
+    /// Can be set using environment variable `MAIL_DIRECTORY`.
+    pub mail_directory: Option<PathBuf>,
+
+    /// Whether to only log the recipient and subject of outgoing emails,
+    /// if no mail directory is set. This is for local development only.
+    /// Otherwise, sending mail without a backend configured fails.
+    ///
+    /// Can be set using environment variable `MAIL_LOG`.
+    pub mail_log: bool,
 }
 
 impl Default for Config {
@@ -127,6 +193,18 @@ impl Default for Config {
             localization_path: PathBuf::from("../locales"),
             rate_limit_per_minute: NonZeroU32::new(20).unwrap(),
             rate_limit_secret: String::new(),
+            rerender_sweep_on_startup: true,
+            rerender_sweep_rate: NonZeroU32::new(10).unwrap(),
+            render_cache_size: 64 * 1024 * 1024,
+            maximum_name_changes: 3,
+            name_change_period_days: 365,
+            name_reservation_days: 90,
+            password_memory_cost: 19 * 1024,
+            password_time_cost: 2,
+            password_parallelism: 1,
+            token_secret: String::new(),
+            mail_directory: None,
+            mail_log: false,
         }
     }
 }
@@ -251,6 +329,131 @@ fn read_env(config: &mut Config) {
 
         config.rate_limit_secret = value;
     }
+
+    if let Ok(value) = env::var("RERENDER_SWEEP_ON_STARTUP") {
+        match value.parse() {
+            Ok(sweep) => config.rerender_sweep_on_startup = sweep,
+            Err(_) => {
+                eprintln!("RERENDER_SWEEP_ON_STARTUP variable is not a valid boolean");
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Ok(value) = env::var("RERENDER_SWEEP_RATE") {
+        match value.parse() {
+            Ok(rate) => config.rerender_sweep_rate = rate,
+            Err(_) => {
+                eprintln!("RERENDER_SWEEP_RATE variable is not a valid integer");
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Ok(value) = env::var("RENDER_CACHE_SIZE") {
+        match value.parse() {
+            Ok(size) => config.render_cache_size = size,
+            Err(_) => {
+                eprintln!("RENDER_CACHE_SIZE variable is not a valid integer");
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Ok(value) = env::var("MAXIMUM_NAME_CHANGES") {
+        match value.parse() {
+            Ok(changes) => config.maximum_name_changes = changes,
+            Err(_) => {
+                eprintln!("MAXIMUM_NAME_CHANGES variable is not a valid integer");
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Ok(value) = env::var("NAME_CHANGE_PERIOD_DAYS") {
+        match value.parse() {
+            Ok(days) => config.name_change_period_days = days,
+            Err(_) => {
+                eprintln!("NAME_CHANGE_PERIOD_DAYS variable is not a valid integer");
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Ok(value) = env::var("NAME_RESERVATION_DAYS") {
+        match value.parse() {
+            Ok(days) => config.name_reservation_days = days,
+            Err(_) => {
+                eprintln!("NAME_RESERVATION_DAYS variable is not a valid integer");
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Ok(value) = env::var("PASSWORD_MEMORY_COST") {
+        match value.parse() {
+            Ok(cost) => config.password_memory_cost = cost,
+            Err(_) => {
+                eprintln!("PASSWORD_MEMORY_COST variable is not a valid integer");
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Ok(value) = env::var("PASSWORD_TIME_COST") {
+        match value.parse() {
+            Ok(cost) => config.password_time_cost = cost,
+            Err(_) => {
+                eprintln!("PASSWORD_TIME_COST variable is not a valid integer");
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Ok(value) = env::var("PASSWORD_PARALLELISM") {
+        match value.parse() {
+            Ok(lanes) => config.password_parallelism = lanes,
+            Err(_) => {
+                eprintln!("PASSWORD_PARALLELISM variable is not a valid integer");
+                process::exit(1);
+            }
+        }
+    }
+
+    match env::var("TOKEN_SECRET") {
+        Ok(value) if !value.is_empty() => {
+            if value.len() < MIN_SECRET_LENGTH {
+                eprintln!(
+                    "TOKEN_SECRET value too short (must be at least {MIN_SECRET_LENGTH} bytes long)",
+                );
+                process::exit(1);
+            }
+
+            config.token_secret = value;
+        }
+        _ => {
+            let mut secret = [0; MIN_SECRET_LENGTH / 2];
+            OsRng.fill_bytes(&mut secret);
+            config.token_secret = hex::encode(secret);
+        }
+    }
+
+    if let Some(value) = env::var_os("MAIL_DIRECTORY") {
+        if !value.is_empty() {
+            config.mail_directory = Some(PathBuf::from(value));
+        }
+    }
+
+    if let Ok(value) = env::var("MAIL_LOG") {
+        if value.eq_ignore_ascii_case("true") {
+            config.mail_log = true;
+        } else if value.eq_ignore_ascii_case("false") {
+            config.mail_log = false;
+        } else {
+            eprintln!("MAIL_LOG variable is not a valid boolean value");
+            process::exit(1);
+        }
+    }
 }
 
 fn parse_args(config: &mut Config) {
@@ -454,6 +657,33 @@ impl Config {
             "Rate limit bypass: {}",
             bool_str(!self.rate_limit_secret.is_empty()),
         );
+        tide::log::info!(
+            "Rerender sweep on startup: {} ({} pages per second)",
+            bool_str(self.rerender_sweep_on_startup),
+            self.rerender_sweep_rate,
+        );
+        tide::log::info!("Render cache size: {} bytes", self.render_cache_size);
+        tide::log::info!(
+            "Username changes: {} per {} days (old names reserved for {} days)",
+            self.maximum_name_changes,
+            self.name_change_period_days,
+            self.name_reservation_days,
+        );
+        tide::log::info!(
+            "Password hashing: Argon2id (m={} KiB, t={}, p={})",
+            self.password_memory_cost,
+            self.password_time_cost,
+            self.password_parallelism,
+        );
+        match self.mail_directory {
+            Some(ref directory) => {
+                tide::log::info!("Mail: writing to {}", directory.display());
+            }
+            None if self.mail_log => {
+                tide::log::info!("Mail: logging recipients only (development)");
+            }
+            None => tide::log::warn!("Mail: no backend configured, sending will fail"),
+        }
     }
 }
 
@@ -48,3 +48,12 @@ macro_rules! str_writeln {
         writeln!($dest, $($arg)*).expect("Writing to string failed");
     }};
 }
+
+/// Borrows a string-like value as a `Cow<str>`.
+///
+/// This is used to build the borrowed `PageInfo` structures ftml takes.
+macro_rules! cow {
+    ($s:expr) => {
+        std::borrow::Cow::Borrowed($s.as_ref())
+    };
+}
@@ -0,0 +1,172 @@
+/*
+ * mail.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Sending of emails to users.
+//!
+//! Mail delivery is abstracted behind the `Mailer` trait, so that different
+//! backends can be used depending on the deployment. For local development
+//! and testing, messages can be written to a directory. Logging only the
+//! recipient and subject must be explicitly enabled, and otherwise sending
+//! fails, so that a missing mail backend does not go unnoticed.
+
+use crate::config::Config;
+use anyhow::{anyhow, bail};
+use async_std::fs;
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A plain text email to be sent to a single recipient.
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Email {
+    /// Formats the email as an RFC 5322 message.
+    ///
+    /// Fails if the recipient or subject could inject additional headers.
+    pub fn to_message(&self) -> anyhow::Result<String> {
+        if !is_valid_address(&self.to) {
+            bail!("Invalid recipient address");
+        }
+
+        if self.subject.contains(['\r', '\n']) {
+            bail!("Subject contains a line break");
+        }
+
+        Ok(format!(
+            "To: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            self.to, self.subject, self.body,
+        ))
+    }
+}
+
+/// Checks that an email address is plausible and safe to put in a header.
+///
+/// This only rejects obviously malformed addresses, whether the
+/// address actually exists is determined by sending to it.
+pub fn is_valid_address(address: &str) -> bool {
+    let (local, domain) = match address.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    !local.is_empty()
+        && !domain.is_empty()
+        && !domain.contains('@')
+        && !address
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || c == '<' || c == '>')
+}
+
+#[async_trait]
+pub trait Mailer: Debug + Send + Sync {
+    async fn send(&self, email: &Email) -> anyhow::Result<()>;
+}
+
+/// Creates the mailer specified by the configuration.
+pub fn build_mailer(config: &Config) -> Box<dyn Mailer> {
+    match config.mail_directory {
+        Some(ref directory) => Box::new(FileMailer::new(directory.clone())),
+        None if config.mail_log => Box::new(LogMailer),
+        None => Box::new(DisabledMailer),
+    }
+}
+
+/// Mailer which refuses to send anything, used when no backend is configured.
+#[derive(Debug)]
+pub struct DisabledMailer;
+
+#[async_trait]
+impl Mailer for DisabledMailer {
+    async fn send(&self, _email: &Email) -> anyhow::Result<()> {
+        Err(anyhow!("No mail backend is configured"))
+    }
+}
+
+/// Mailer which notes each email in the server log, for development only.
+///
+/// The body is never logged, since it may contain tokens or other secrets.
+#[derive(Debug)]
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: &Email) -> anyhow::Result<()> {
+        if !is_valid_address(&email.to) {
+            bail!("Invalid recipient address");
+        }
+
+        tide::log::info!(
+            "Not sending email to {} (subject '{}'), mail is only logged",
+            email.to,
+            email.subject,
+        );
+
+        Ok(())
+    }
+}
+
+/// Mailer which writes each email as a file in a directory.
+#[derive(Debug)]
+pub struct FileMailer {
+    directory: PathBuf,
+}
+
+impl FileMailer {
+    #[inline]
+    pub fn new(directory: PathBuf) -> Self {
+        FileMailer { directory }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: &Email) -> anyhow::Result<()> {
+        let message = email.to_message()?;
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+        let path = self.directory.join(format!("{timestamp}.eml"));
+
+        tide::log::info!("Writing email to {} to {}", email.to, path.display());
+        fs::create_dir_all(&self.directory).await?;
+        fs::write(&path, message).await?;
+        Ok(())
+    }
+}
+
+#[test]
+fn addresses() {
+    assert!(is_valid_address("user@example.com"));
+    assert!(is_valid_address("first.last+tag@sub.example.org"));
+
+    assert!(!is_valid_address(""));
+    assert!(!is_valid_address("example.com"));
+    assert!(!is_valid_address("@example.com"));
+    assert!(!is_valid_address("user@"));
+    assert!(!is_valid_address("user@a@example.com"));
+    assert!(!is_valid_address(
+        "user@example.com\r\nBcc: other@example.com"
+    ));
+    assert!(!is_valid_address("User <user@example.com>"));
+}
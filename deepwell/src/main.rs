@@ -39,12 +39,14 @@ extern crate str_macro;
 mod macros;
 
 mod api;
+mod cache;
 mod config;
 mod database;
 mod hash;
 mod info;
 mod json_utils;
 mod locales;
+mod mail;
 mod methods;
 mod models;
 mod services;
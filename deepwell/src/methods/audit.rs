@@ -0,0 +1,43 @@
+/*
+ * methods/audit.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::audit::AuditQuery;
+use crate::web::{CursorPage, CursorQuery};
+
+pub async fn audit_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let query: AuditQuery = req.query()?;
+    let cursor: CursorQuery = req.query()?;
+    tide::log::info!("Getting audit log entries for {query:?}");
+
+    let before = cursor.key()?;
+    let entries = AuditService::get_entries(&ctx, query, before, cursor.fetch_limit())
+        .await
+        .to_api()?;
+
+    let output = CursorPage::new(entries, &cursor, |entry| entry.audit_id);
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
@@ -0,0 +1,125 @@
+/*
+ * methods/block.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::block::{CreateSiteBan, CreateUserBlock, RemoveSiteBan};
+
+pub async fn site_bans_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting active bans in site ID {site_id}");
+
+    let bans = BlockService::get_site_bans(&ctx, site_id).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&bans)?;
+    Ok(body.into())
+}
+
+pub async fn site_ban_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: CreateSiteBan = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!(
+        "Banning user ID {} from site ID {site_id} (by user ID {})",
+        input.user_id,
+        input.created_by,
+    );
+
+    let ban = BlockService::ban(&ctx, site_id, input).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&ban)?;
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn site_ban_delete(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: RemoveSiteBan = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!(
+        "Lifting ban on user ID {} from site ID {site_id} (by user ID {})",
+        input.user_id,
+        input.revoked_by,
+    );
+
+    let ban = BlockService::unban(&ctx, site_id, input).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&ban)?;
+    Ok(body.into())
+}
+
+pub async fn user_blocks_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Getting users blocked by user ID {user_id}");
+
+    let blocks = BlockService::get_user_blocks(&ctx, user_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&blocks)?;
+    Ok(body.into())
+}
+
+pub async fn user_block_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: CreateUserBlock = req.body_json().await?;
+    tide::log::info!(
+        "User ID {} is blocking user ID {}",
+        input.user_id,
+        input.blocked_user_id,
+    );
+
+    let block = BlockService::block_user(&ctx, input).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&block)?;
+    Ok(body.into())
+}
+
+pub async fn user_block_delete(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let user_id = req.param("user_id")?.parse()?;
+    let blocked_user_id = req.param("blocked_user_id")?.parse()?;
+    tide::log::info!("User ID {user_id} is unblocking user ID {blocked_user_id}");
+
+    BlockService::unblock_user(&ctx, user_id, blocked_user_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
@@ -20,7 +20,7 @@
 
 use super::prelude::*;
 use crate::models::page_category::Model as PageCategoryModel;
-use crate::services::category::CategoryOutput;
+use crate::services::category::{CategoryOutput, UpdateCategory};
 
 pub async fn category_head_direct(req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
@@ -88,6 +88,26 @@ pub async fn category_get(req: ApiRequest) -> ApiResponse {
     Ok(body.into())
 }
 
+pub async fn category_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: UpdateCategory = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let reference = Reference::try_from(&req)?;
+    tide::log::info!("Updating page category {reference:?} in site ID {site_id}");
+
+    let category = CategoryService::update(&ctx, site_id, reference, input)
+        .await
+        .to_api()?;
+
+    let output: CategoryOutput = category.into();
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
 pub async fn category_all_get(req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
     let ctx = ServiceContext::new(&req, &txn);
@@ -23,7 +23,7 @@ use crate::models::file::Model as FileModel;
 use crate::models::file_revision::Model as FileRevisionModel;
 use crate::services::file::GetFileOutput;
 use crate::services::Result;
-use crate::web::FileDetailsQuery;
+use crate::web::{etag_from_parts, json_response, not_modified, FileDetailsQuery};
 
 pub async fn file_head_direct(req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
@@ -50,9 +50,10 @@ pub async fn file_get_direct(req: ApiRequest) -> ApiResponse {
         .await
         .to_api()?;
 
-    let response = build_file_response(&ctx, &file, &revision, details, StatusCode::Ok)
-        .await
-        .to_api()?;
+    let response =
+        build_file_response(&req, &ctx, &file, &revision, details, StatusCode::Ok)
+            .await
+            .to_api()?;
 
     txn.commit().await?;
     Ok(response)
@@ -67,7 +68,7 @@ pub async fn file_head(req: ApiRequest) -> ApiResponse {
     let file_reference = CuidReference::try_from(&req)?;
     tide::log::info!("Checking existence of file {file_reference:?}");
 
-    let page = PageService::get(&ctx, site_id, page_reference)
+    let page = PageService::get_resolved(&ctx, site_id, page_reference)
         .await
         .to_api()?;
 
@@ -91,7 +92,7 @@ pub async fn file_get(req: ApiRequest) -> ApiResponse {
 
     tide::log::info!("Getting file {file_reference:?}");
 
-    let page = PageService::get(&ctx, site_id, page_reference)
+    let page = PageService::get_resolved(&ctx, site_id, page_reference)
         .await
         .to_api()?;
 
@@ -103,9 +104,10 @@ pub async fn file_get(req: ApiRequest) -> ApiResponse {
         .await
         .to_api()?;
 
-    let response = build_file_response(&ctx, &file, &revision, details, StatusCode::Ok)
-        .await
-        .to_api()?;
+    let response =
+        build_file_response(&req, &ctx, &file, &revision, details, StatusCode::Ok)
+            .await
+            .to_api()?;
 
     txn.commit().await?;
     Ok(response)
@@ -132,12 +134,30 @@ pub async fn file_restore(_req: ApiRequest) -> ApiResponse {
 }
 
 async fn build_file_response(
+    req: &ApiRequest,
     ctx: &ServiceContext<'_>,
     file: &FileModel,
     revision: &FileRevisionModel,
     details: FileDetailsQuery,
     status: StatusCode,
 ) -> Result<Response> {
+    // Tag the response from what it is built from, so if the client
+    // already has this version, the blob doesn't need to be fetched
+    let etag = etag_from_parts(&(
+        &file.file_id,
+        file.updated_at,
+        file.deleted_at,
+        &file.name,
+        revision.revision_id,
+        &revision.s3_hash,
+        &revision.hidden,
+        details,
+    ))?;
+
+    if let Some(response) = not_modified(req, &etag, status) {
+        return Ok(response);
+    }
+
     // Get blob data, if requested
     let data = BlobService::get_maybe(ctx, details.data, &revision.s3_hash).await?;
 
@@ -162,7 +182,6 @@ async fn build_file_response(
         hidden_fields: &revision.hidden,
     };
 
-    let body = Body::from_json(&output)?;
-    let response = Response::builder(status).body(body).into();
+    let response = json_response(req, status, etag, &output)?;
     Ok(response)
 }
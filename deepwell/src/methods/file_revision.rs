@@ -73,7 +73,7 @@ pub async fn file_revision_head(req: ApiRequest) -> ApiResponse {
         page_reference,
     );
 
-    let page = PageService::get(&ctx, site_id, page_reference)
+    let page = PageService::get_resolved(&ctx, site_id, page_reference)
         .await
         .to_api()?;
 
@@ -107,7 +107,7 @@ pub async fn file_revision_get(req: ApiRequest) -> ApiResponse {
         page_reference,
     );
 
-    let page = PageService::get(&ctx, site_id, page_reference)
+    let page = PageService::get_resolved(&ctx, site_id, page_reference)
         .await
         .to_api()?;
 
@@ -182,7 +182,7 @@ pub async fn file_revision_range_get(req: ApiRequest) -> ApiResponse {
     let file_reference =
         CuidReference::try_from_fields_key(&req, "file_type", "id_or_name")?;
 
-    let page = PageService::get(&ctx, site_id, page_reference)
+    let page = PageService::get_resolved(&ctx, site_id, page_reference)
         .await
         .to_api()?;
     let file = FileService::get(&ctx, page.page_id, file_reference)
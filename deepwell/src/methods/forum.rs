@@ -0,0 +1,370 @@
+/*
+ * methods/forum.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::forum_category::{
+    CreateForumCategory, CreateForumGroup, UpdateForumCategory,
+};
+use crate::services::forum_post::{
+    CreateForumPost, CreatePageComment, DeleteForumPost, EditForumPost,
+};
+use crate::services::forum_thread::{
+    CreateForumThread, DeleteForumThread, UpdateForumThread,
+};
+use crate::web::OffsetLimitQuery;
+
+pub async fn forum_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting forum groups and categories in site ID {site_id}");
+
+    let groups = ForumCategoryService::get_all(&ctx, site_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&groups)?;
+    Ok(body.into())
+}
+
+pub async fn forum_group_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: CreateForumGroup = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Creating forum group in site ID {site_id}");
+
+    let group = ForumCategoryService::create_group(&ctx, site_id, input)
+        .await
+        .to_api()?;
+
+    let body = Body::from_json(&group)?;
+    txn.commit().await?;
+
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn forum_category_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: CreateForumCategory = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Creating forum category in site ID {site_id}");
+
+    let category = ForumCategoryService::create(&ctx, site_id, input)
+        .await
+        .to_api()?;
+
+    let body = Body::from_json(&category)?;
+    txn.commit().await?;
+
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn forum_category_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    let category_id = req.param("category_id")?.parse()?;
+    tide::log::info!("Getting forum category ID {category_id} in site ID {site_id}");
+
+    let category = ForumCategoryService::get(&ctx, site_id, category_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&category)?;
+    Ok(body.into())
+}
+
+pub async fn forum_category_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: UpdateForumCategory = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let category_id = req.param("category_id")?.parse()?;
+    tide::log::info!("Updating forum category ID {category_id} in site ID {site_id}");
+
+    let category = ForumCategoryService::update(&ctx, site_id, category_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&category)?;
+    Ok(body.into())
+}
+
+pub async fn forum_threads_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let OffsetLimitQuery { limit, offset } = req.query()?;
+    let site_id = req.param("site_id")?.parse()?;
+    let category_id = req.param("category_id")?.parse()?;
+    tide::log::info!(
+        "Getting forum threads in category ID {category_id} in site ID {site_id}",
+    );
+
+    let threads =
+        ForumThreadService::get_all(&ctx, site_id, category_id, offset, limit.into())
+            .await
+            .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&threads)?;
+    Ok(body.into())
+}
+
+pub async fn forum_thread_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: CreateForumThread = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!(
+        "Creating forum thread in category ID {} in site ID {site_id}",
+        input.category_id,
+    );
+
+    let output = ForumThreadService::create(&ctx, site_id, input)
+        .await
+        .to_api()?;
+
+    let body = Body::from_json(&output)?;
+    txn.commit().await?;
+
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn forum_thread_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    let thread_id = req.param("thread_id")?.parse()?;
+    tide::log::info!("Getting forum thread ID {thread_id} in site ID {site_id}");
+
+    let thread = ForumThreadService::get(&ctx, site_id, thread_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&thread)?;
+    Ok(body.into())
+}
+
+pub async fn forum_thread_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: UpdateForumThread = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let thread_id = req.param("thread_id")?.parse()?;
+    tide::log::info!("Updating forum thread ID {thread_id} in site ID {site_id}");
+
+    let thread = ForumThreadService::update(&ctx, site_id, thread_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&thread)?;
+    Ok(body.into())
+}
+
+pub async fn forum_thread_delete(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: DeleteForumThread = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let thread_id = req.param("thread_id")?.parse()?;
+    tide::log::info!("Deleting forum thread ID {thread_id} in site ID {site_id}");
+
+    ForumThreadService::delete(&ctx, site_id, thread_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn forum_posts_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let OffsetLimitQuery { limit, offset } = req.query()?;
+    let site_id = req.param("site_id")?.parse()?;
+    let thread_id = req.param("thread_id")?.parse()?;
+    tide::log::info!("Getting forum posts in thread ID {thread_id} in site ID {site_id}");
+
+    let posts = ForumPostService::get_all(&ctx, site_id, thread_id, offset, limit.into())
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&posts)?;
+    Ok(body.into())
+}
+
+pub async fn forum_post_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: CreateForumPost = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!(
+        "Creating forum post in thread ID {} in site ID {site_id}",
+        input.thread_id,
+    );
+
+    let post = ForumPostService::create(&ctx, site_id, input)
+        .await
+        .to_api()?;
+
+    let body = Body::from_json(&post)?;
+    txn.commit().await?;
+
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn forum_post_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    let post_id = req.param("post_id")?.parse()?;
+    tide::log::info!("Getting forum post ID {post_id} in site ID {site_id}");
+
+    let post = ForumPostService::get(&ctx, site_id, post_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&post)?;
+    Ok(body.into())
+}
+
+pub async fn forum_post_edit(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: EditForumPost = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let post_id = req.param("post_id")?.parse()?;
+    tide::log::info!("Editing forum post ID {post_id} in site ID {site_id}");
+
+    let post = ForumPostService::edit(&ctx, site_id, post_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&post)?;
+    Ok(body.into())
+}
+
+pub async fn forum_post_delete(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: DeleteForumPost = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let post_id = req.param("post_id")?.parse()?;
+    tide::log::info!("Deleting forum post ID {post_id} in site ID {site_id}");
+
+    ForumPostService::delete(&ctx, site_id, post_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn forum_post_revisions_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    let post_id = req.param("post_id")?.parse()?;
+    tide::log::info!(
+        "Getting revisions for forum post ID {post_id} in site ID {site_id}"
+    );
+
+    let revisions = ForumPostService::get_revisions(&ctx, site_id, post_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&revisions)?;
+    Ok(body.into())
+}
+
+pub async fn page_comments_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let OffsetLimitQuery { limit, offset } = req.query()?;
+    let site_id = req.param("site_id")?.parse()?;
+    let reference = Reference::try_from(&req)?;
+    tide::log::info!("Getting comments for page {reference:?} in site ID {site_id}");
+
+    let posts = ForumPostService::get_page_comments(
+        &ctx,
+        site_id,
+        reference,
+        offset,
+        limit.into(),
+    )
+    .await
+    .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&posts)?;
+    Ok(body.into())
+}
+
+pub async fn page_comment_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: CreatePageComment = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let reference = Reference::try_from(&req)?;
+    tide::log::info!("Creating comment on page {reference:?} in site ID {site_id}");
+
+    let post = ForumPostService::create_page_comment(&ctx, site_id, reference, input)
+        .await
+        .to_api()?;
+
+    let body = Body::from_json(&post)?;
+    txn.commit().await?;
+
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
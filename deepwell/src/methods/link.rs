@@ -19,6 +19,8 @@
  */
 
 use super::prelude::*;
+use crate::services::link::LinkReportQuery;
+use crate::web::FetchLimitQuery;
 
 pub async fn page_links_from_get(req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
@@ -28,7 +30,9 @@ pub async fn page_links_from_get(req: ApiRequest) -> ApiResponse {
     let reference = Reference::try_from(&req)?;
     tide::log::info!("Getting page links for page {reference:?} in site ID {site_id}");
 
-    let page = PageService::get(&ctx, site_id, reference).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site_id, reference)
+        .await
+        .to_api()?;
     let output = LinkService::get_from(&ctx, page.page_id).await.to_api()?;
     let body = Body::from_json(&output)?;
     txn.commit().await?;
@@ -44,7 +48,9 @@ pub async fn page_links_to_get(req: ApiRequest) -> ApiResponse {
     let reference = Reference::try_from(&req)?;
     tide::log::info!("Getting page links from page {reference:?} in site ID {site_id}");
 
-    let page = PageService::get(&ctx, site_id, reference).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site_id, reference)
+        .await
+        .to_api()?;
     let output = LinkService::get_to(&ctx, page.page_id, None)
         .await
         .to_api()?;
@@ -83,7 +89,9 @@ pub async fn page_links_external_from(req: ApiRequest) -> ApiResponse {
         "Getting external links from page {reference:?} in site ID {site_id}",
     );
 
-    let page = PageService::get(&ctx, site_id, reference).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site_id, reference)
+        .await
+        .to_api()?;
     let output = LinkService::get_external_from(&ctx, page.page_id)
         .await
         .to_api()?;
@@ -109,3 +117,57 @@ pub async fn page_links_external_to(req: ApiRequest) -> ApiResponse {
     txn.commit().await?;
     Ok(body.into())
 }
+
+pub async fn link_report_wanted_get(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let FetchLimitQuery { limit } = req.query()?;
+    let input: LinkReportQuery = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting wanted pages report for site ID {site_id}");
+
+    let output = LinkService::get_wanted(&ctx, site_id, input, limit.into())
+        .await
+        .to_api()?;
+
+    let body = Body::from_json(&output)?;
+    txn.commit().await?;
+    Ok(body.into())
+}
+
+pub async fn link_report_orphaned_get(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let FetchLimitQuery { limit } = req.query()?;
+    let input: LinkReportQuery = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting orphaned pages report for site ID {site_id}");
+
+    let output = LinkService::get_orphaned(&ctx, site_id, input, limit.into())
+        .await
+        .to_api()?;
+
+    let body = Body::from_json(&output)?;
+    txn.commit().await?;
+    Ok(body.into())
+}
+
+pub async fn link_report_dead_ends_get(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let FetchLimitQuery { limit } = req.query()?;
+    let input: LinkReportQuery = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting dead-end pages report for site ID {site_id}");
+
+    let output = LinkService::get_dead_ends(&ctx, site_id, input, limit.into())
+        .await
+        .to_api()?;
+
+    let body = Body::from_json(&output)?;
+    txn.commit().await?;
+    Ok(body.into())
+}
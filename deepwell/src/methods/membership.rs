@@ -0,0 +1,218 @@
+/*
+ * methods/membership.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::membership::{
+    ApplicationsQuery, ApplyToSite, InviteUser, JoinSite, LeaveSite, RespondToInvitation,
+    ReviewApplication,
+};
+
+pub async fn members_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting members of site ID {site_id}");
+
+    let members = MembershipService::get_members(&ctx, site_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&members)?;
+    Ok(body.into())
+}
+
+pub async fn join_options_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Getting join options for user ID {user_id} in site ID {site_id}");
+
+    let options = MembershipService::get_join_options(&ctx, site_id, user_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&options)?;
+    Ok(body.into())
+}
+
+pub async fn membership_join(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: JoinSite = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("User ID {} joining site ID {site_id}", input.user_id);
+
+    MembershipService::join(&ctx, site_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn membership_leave(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: LeaveSite = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("User ID {} leaving site ID {site_id}", input.user_id);
+
+    MembershipService::leave(&ctx, site_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn applications_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let query: ApplicationsQuery = req.query()?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!(
+        "Getting pending membership applications in site ID {site_id} (by user ID {})",
+        query.user_id,
+    );
+
+    let applications = MembershipService::get_applications(&ctx, site_id, query)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&applications)?;
+    Ok(body.into())
+}
+
+pub async fn application_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: ApplyToSite = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!(
+        "User ID {} applying for membership in site ID {site_id}",
+        input.user_id,
+    );
+
+    let application = MembershipService::apply(&ctx, site_id, input)
+        .await
+        .to_api()?;
+
+    let body = Body::from_json(&application)?;
+    txn.commit().await?;
+
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn application_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: ReviewApplication = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let application_id = req.param("application_id")?.parse()?;
+    tide::log::info!(
+        "{} membership application ID {application_id} in site ID {site_id} (by user ID {})",
+        if input.accept { "Accepting" } else { "Rejecting" },
+        input.user_id,
+    );
+
+    let application = MembershipService::review(&ctx, site_id, application_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&application)?;
+    Ok(body.into())
+}
+
+pub async fn invitation_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: InviteUser = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!(
+        "Inviting user ID {} to site ID {site_id} (by user ID {})",
+        input.user_id,
+        input.invited_by,
+    );
+
+    let invitation = MembershipService::invite(&ctx, site_id, input)
+        .await
+        .to_api()?;
+
+    let body = Body::from_json(&invitation)?;
+    txn.commit().await?;
+
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn invitations_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Getting pending site invitations for user ID {user_id}");
+
+    let invitations = MembershipService::get_invitations(&ctx, user_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&invitations)?;
+    Ok(body.into())
+}
+
+pub async fn invitation_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: RespondToInvitation = req.body_json().await?;
+    let invitation_id = req.param("invitation_id")?.parse()?;
+    tide::log::info!(
+        "{} site invitation ID {invitation_id} (by user ID {})",
+        if input.accept {
+            "Accepting"
+        } else {
+            "Declining"
+        },
+        input.user_id,
+    );
+
+    let invitation = MembershipService::respond(&ctx, invitation_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&invitation)?;
+    Ok(body.into())
+}
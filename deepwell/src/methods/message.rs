@@ -0,0 +1,113 @@
+/*
+ * methods/message.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::message::{MessageFolder, SendMessage, UpdateMessageFlags};
+use crate::web::OffsetLimitQuery;
+use sea_orm::prelude::Uuid;
+
+pub async fn message_send(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: SendMessage = req.body_json().await?;
+    tide::log::info!(
+        "Sending message from user ID {} to user ID {}",
+        input.from_user_id,
+        input.to_user_id,
+    );
+
+    let output = MessageService::send(&ctx, input).await.to_api()?;
+    let body = Body::from_json(&output)?;
+    txn.commit().await?;
+
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn message_folder_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let OffsetLimitQuery { limit, offset } = req.query()?;
+    let user_id = req.param("user_id")?.parse()?;
+    let folder: MessageFolder = req.param("folder")?.parse()?;
+    tide::log::info!("Getting {} messages for user ID {user_id}", folder.name());
+
+    let messages =
+        MessageService::get_folder(&ctx, user_id, folder, offset, limit.into())
+            .await
+            .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&messages)?;
+    Ok(body.into())
+}
+
+pub async fn message_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let user_id = req.param("user_id")?.parse()?;
+    let message_id: Uuid = req.param("message_id")?.parse()?;
+    tide::log::info!("Getting message {message_id} for user ID {user_id}");
+
+    let message = MessageService::get(&ctx, user_id, message_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&message)?;
+    Ok(body.into())
+}
+
+pub async fn message_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: UpdateMessageFlags = req.body_json().await?;
+    let user_id = req.param("user_id")?.parse()?;
+    let message_id: Uuid = req.param("message_id")?.parse()?;
+    tide::log::info!("Updating message {message_id} for user ID {user_id}");
+
+    let message = MessageService::update_flags(&ctx, user_id, message_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&message)?;
+    Ok(body.into())
+}
+
+pub async fn message_delete(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let user_id = req.param("user_id")?.parse()?;
+    let message_id: Uuid = req.param("message_id")?.parse()?;
+    tide::log::info!("Deleting message {message_id} for user ID {user_id}");
+
+    MessageService::delete(&ctx, user_id, message_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
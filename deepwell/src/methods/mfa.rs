@@ -0,0 +1,102 @@
+/*
+ * methods/mfa.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::mfa::{DisableMultiFactor, EnableMultiFactor, MultiFactorCode};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ResetRecoveryCodes {
+    totp: String,
+}
+
+pub async fn mfa_setup(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Generating MFA secret for user ID {user_id}");
+
+    let output = MfaService::setup(&ctx, user_id).await.to_api()?;
+    txn.commit().await?;
+
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
+pub async fn mfa_enable(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: EnableMultiFactor = req.body_json().await?;
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Enabling MFA for user ID {user_id}");
+
+    let output = MfaService::enable(&ctx, user_id, input).await.to_api()?;
+    txn.commit().await?;
+
+    let body = Body::from_json(&output)?;
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn mfa_verify(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: MultiFactorCode = req.body_json().await?;
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Verifying MFA code for user ID {user_id}");
+
+    MfaService::verify(&ctx, user_id, input).await.to_api()?;
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn mfa_recovery_reset(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let ResetRecoveryCodes { totp } = req.body_json().await?;
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Resetting MFA recovery codes for user ID {user_id}");
+
+    let output = MfaService::reset_recovery_codes(&ctx, user_id, totp)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
+pub async fn mfa_disable(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: DisableMultiFactor = req.body_json().await?;
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Disabling MFA for user ID {user_id}");
+
+    MfaService::disable(&ctx, user_id, input).await.to_api()?;
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
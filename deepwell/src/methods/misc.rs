@@ -49,6 +49,14 @@ pub async fn full_version(_: ApiRequest) -> ApiResponse {
     Ok(info::FULL_VERSION_WITH_NAME.as_str().into())
 }
 
+pub async fn render_cache_get(req: ApiRequest) -> ApiResponse {
+    tide::log::info!("Getting render cache statistics");
+
+    let stats = req.state().render_cache.stats();
+    let body = Body::from_json(&stats)?;
+    Ok(body.into())
+}
+
 pub async fn ratelimit_exempt(req: ApiRequest) -> ApiResponse {
     if is_ratelimit_exempt(&req) {
         tide::log::info!("Requester is rate-limit exempt");
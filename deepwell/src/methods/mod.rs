@@ -30,10 +30,14 @@
 mod prelude {
     pub use crate::api::{ApiRequest, ApiResponse};
     pub use crate::services::{
-        BlobService, CategoryService, Error as ServiceError, FileRevisionService,
-        FileService, LinkService, PageService, PostTransactionToApiResponse,
-        RenderService, RequestFetchService, RevisionService, ScoreService,
-        ServiceContext, SiteService, TextService, UserService, VoteService,
+        AuditService, AuthenticationService, BlobService, BlockService, CategoryService,
+        Error as ServiceError, FileRevisionService, FileService, ForumCategoryService,
+        ForumPostService, ForumThreadService, LinkService, MembershipService,
+        MessageService, MfaService, PageService, PostTransactionToApiResponse,
+        RedirectService, RenderService, RequestFetchService, RevisionService,
+        RoleService, ScoreService, SearchService, ServiceContext, SiteService,
+        SiteSettingsService, TagService, TextService, TokenService, UserService,
+        VoteAnalysisService, VoteService,
     };
     pub use crate::web::{utils::error_response, CuidReference, HttpUnwrap, Reference};
     pub use chrono::prelude::*;
@@ -50,16 +54,29 @@ mod prelude {
     }
 }
 
+pub mod audit;
+pub mod auth;
+pub mod block;
 pub mod category;
 pub mod file;
 pub mod file_revision;
+pub mod forum;
 pub mod link;
 pub mod locales;
+pub mod membership;
+pub mod message;
+pub mod mfa;
 pub mod misc;
 pub mod page;
 pub mod page_revision;
 pub mod parent;
+pub mod redirect;
+pub mod role;
+pub mod search;
 pub mod site;
+pub mod tag;
 pub mod text;
+pub mod token;
 pub mod user;
+pub mod v1;
 pub mod vote;
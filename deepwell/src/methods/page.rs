@@ -21,11 +21,14 @@
 use super::prelude::*;
 use crate::models::page::Model as PageModel;
 use crate::models::page_revision::Model as PageRevisionModel;
+use crate::services::job::RerenderSweep;
 use crate::services::page::{
     CreatePage, DeletePage, EditPage, GetPageOutput, MovePage, RestorePage, RollbackPage,
 };
 use crate::services::{Result, TextService};
-use crate::web::PageDetailsQuery;
+use crate::web::{
+    etag_from_parts, json_response, not_modified, trim_default, PageDetailsQuery,
+};
 use ref_map::*;
 
 pub async fn page_invalid(req: ApiRequest) -> ApiResponse {
@@ -58,9 +61,10 @@ pub async fn page_get_direct(req: ApiRequest) -> ApiResponse {
         .await
         .to_api()?;
 
-    let response = build_page_response(&ctx, &page, &revision, details, StatusCode::Ok)
-        .await
-        .to_api()?;
+    let response =
+        build_page_response(&req, &ctx, &page, &revision, None, details, StatusCode::Ok)
+            .await
+            .to_api()?;
 
     txn.commit().await?;
     Ok(response)
@@ -106,15 +110,33 @@ pub async fn page_get(req: ApiRequest) -> ApiResponse {
     let reference = Reference::try_from(&req)?;
     tide::log::info!("Getting page {reference:?} in site ID {site_id}");
 
-    let page = PageService::get(&ctx, site_id, reference).await.to_api()?;
-    let revision = RevisionService::get_latest(&ctx, site_id, page.page_id)
+    let page = PageService::get_resolved(&ctx, site_id, reference)
         .await
         .to_api()?;
-
-    let response = build_page_response(&ctx, &page, &revision, details, StatusCode::Ok)
+    let revision = RevisionService::get_latest(&ctx, site_id, page.page_id)
         .await
         .to_api()?;
 
+    // If the page was found at a different slug, a redirect was followed
+    let redirected_from = match reference {
+        Reference::Slug(slug) if trim_default(slug) != trim_default(&page.slug) => {
+            Some(slug)
+        }
+        _ => None,
+    };
+
+    let response = build_page_response(
+        &req,
+        &ctx,
+        &page,
+        &revision,
+        redirected_from,
+        details,
+        StatusCode::Ok,
+    )
+    .await
+    .to_api()?;
+
     txn.commit().await?;
     Ok(response)
 }
@@ -196,6 +218,25 @@ pub async fn page_rerender(req: ApiRequest) -> ApiResponse {
     Ok(Response::new(StatusCode::NoContent))
 }
 
+pub async fn page_rerender_sweep_get(req: ApiRequest) -> ApiResponse {
+    tide::log::info!("Getting status of rerender sweep");
+
+    let status = RerenderSweep::status(req.state()).await.to_api()?;
+    let body = Body::from_json(&status)?;
+    Ok(body.into())
+}
+
+pub async fn page_rerender_sweep_post(req: ApiRequest) -> ApiResponse {
+    tide::log::info!("Starting rerender sweep of outdated pages");
+
+    if RerenderSweep::start(req.state()) {
+        Ok(Response::new(StatusCode::Accepted))
+    } else {
+        tide::log::warn!("Rerender sweep is already running");
+        Ok(Response::new(StatusCode::Conflict))
+    }
+}
+
 pub async fn page_restore(mut req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
     let ctx = ServiceContext::new(&req, &txn);
@@ -242,9 +283,11 @@ pub async fn page_rollback(mut req: ApiRequest) -> ApiResponse {
 }
 
 async fn build_page_response(
+    req: &ApiRequest,
     ctx: &ServiceContext<'_>,
     page: &PageModel,
     revision: &PageRevisionModel,
+    redirected_from: Option<&str>,
     details: PageDetailsQuery,
     status: StatusCode,
 ) -> Result<Response> {
@@ -253,14 +296,62 @@ async fn build_page_response(
         CategoryService::get(ctx, page.site_id, Reference::from(page.page_category_id))
             .await?;
 
+    // Calculate score
+    let score_settings = ScoreService::get_settings(ctx, page.page_id).await?;
+    let scorer = ScoreService::scorer_for(score_settings.score_type);
+    let rating = ScoreService::score_with(ctx, scorer, page.page_id).await?;
+
+    // Get number of comments, if there's a discussion
+    let discussion_post_count = match page.discussion_thread_id {
+        Some(thread_id) => ForumThreadService::get_optional(ctx, page.site_id, thread_id)
+            .await?
+            .map(|thread| thread.number_posts)
+            .unwrap_or(0),
+        None => 0,
+    };
+
+    // Tag the response from what it is built from, so if the client
+    // already has this version, the text doesn't need to be fetched
+    let etag = etag_from_parts(&(
+        page.page_id,
+        page.updated_at,
+        page.deleted_at,
+        revision.revision_id,
+        &revision.wikitext_hash,
+        &revision.compiled_hash,
+        revision.compiled_at,
+        &revision.hidden,
+        category.category_id,
+        redirected_from,
+        details,
+        rating,
+        discussion_post_count,
+        score_settings,
+    ))?;
+
+    if let Some(response) = not_modified(req, &etag, status) {
+        return Ok(response);
+    }
+
     // Get text data, if requested
     let (wikitext, compiled_html) = try_join!(
         TextService::get_maybe(ctx, details.wikitext, &revision.wikitext_hash),
-        TextService::get_maybe(ctx, details.compiled_html, &revision.compiled_hash),
+        TextService::get_cached_maybe(
+            ctx,
+            details.compiled_html,
+            &revision.compiled_hash
+        ),
     )?;
 
-    // Calculate score
-    let rating = ScoreService::score(ctx, page.page_id).await?;
+    // Include styles and meta tags alongside the compiled HTML
+    let (compiled_styles, compiled_meta) = if details.compiled_html {
+        (
+            Some(&revision.compiled_styles),
+            Some(&revision.compiled_meta),
+        )
+    } else {
+        (None, None)
+    };
 
     // Build result struct
     let output = GetPageOutput {
@@ -272,7 +363,9 @@ async fn build_page_response(
         site_id: page.site_id,
         page_category_id: category.category_id,
         page_category_slug: &category.slug,
+        redirected_from,
         discussion_thread_id: page.discussion_thread_id,
+        discussion_post_count,
         revision_id: revision.revision_id,
         revision_type: revision.revision_type,
         revision_created_at: revision.created_at,
@@ -280,6 +373,8 @@ async fn build_page_response(
         revision_user_id: revision.user_id,
         wikitext,
         compiled_html,
+        compiled_styles,
+        compiled_meta,
         compiled_at: revision.compiled_at,
         compiled_generator: &revision.compiled_generator,
         revision_comments: &revision.comments,
@@ -289,9 +384,10 @@ async fn build_page_response(
         slug: &revision.slug,
         tags: &revision.tags,
         rating,
+        score_type: score_settings.score_type,
+        vote_type: score_settings.vote_type,
     };
 
-    let body = Body::from_json(&output)?;
-    let response = Response::builder(status).body(body).into();
+    let response = json_response(req, status, etag, &output)?;
     Ok(response)
 }
@@ -38,7 +38,9 @@ pub async fn page_revision_info(req: ApiRequest) -> ApiResponse {
         "Getting latest revision for page {reference:?} in site ID {site_id}",
     );
 
-    let page = PageService::get(&ctx, site_id, reference).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site_id, reference)
+        .await
+        .to_api()?;
     let revision_count = RevisionService::count(&ctx, site_id, page.page_id)
         .await
         .to_api()?;
@@ -67,7 +69,9 @@ pub async fn page_revision_head(req: ApiRequest) -> ApiResponse {
         "Checking existence of revision {revision_number} for page {reference:?} in site ID {site_id}",
     );
 
-    let page = PageService::get(&ctx, site_id, reference).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site_id, reference)
+        .await
+        .to_api()?;
     let exists = RevisionService::exists(&ctx, site_id, page.page_id, revision_number)
         .await
         .to_api()?;
@@ -89,7 +93,9 @@ pub async fn page_revision_get(req: ApiRequest) -> ApiResponse {
         "Getting revision {revision_number} for page {reference:?} in site ID {site_id}",
     );
 
-    let page = PageService::get(&ctx, site_id, reference).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site_id, reference)
+        .await
+        .to_api()?;
     let revision = RevisionService::get(&ctx, site_id, page.page_id, revision_number)
         .await
         .to_api()?;
@@ -153,7 +159,9 @@ pub async fn page_revision_range_get(req: ApiRequest) -> ApiResponse {
     let direction = req.param("direction")?.parse()?;
     let reference = Reference::try_from(&req)?;
 
-    let page = PageService::get(&ctx, site_id, reference).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site_id, reference)
+        .await
+        .to_api()?;
     let revisions = RevisionService::get_range(
         &ctx,
         site_id,
@@ -192,6 +200,8 @@ async fn filter_and_populate_revision(
         compiled_hash,
         compiled_at,
         compiled_generator,
+        compiled_styles,
+        compiled_meta,
         comments,
         hidden,
         title,
@@ -229,10 +239,17 @@ async fn filter_and_populate_revision(
     // Get text data, if requested
     let (wikitext, compiled_html) = try_join!(
         TextService::get_maybe(ctx, details.wikitext, &wikitext_hash),
-        TextService::get_maybe(ctx, details.compiled_html, &compiled_hash),
+        TextService::get_cached_maybe(ctx, details.compiled_html, &compiled_hash),
     )
     .to_api()?;
 
+    // Styles and meta tags go with the compiled HTML
+    let (compiled_styles, compiled_meta) = if details.compiled_html {
+        (Some(compiled_styles), Some(compiled_meta))
+    } else {
+        (None, None)
+    };
+
     Ok(PageRevisionModelFiltered {
         revision_id,
         revision_type,
@@ -244,6 +261,8 @@ async fn filter_and_populate_revision(
         changes,
         wikitext,
         compiled_html,
+        compiled_styles,
+        compiled_meta,
         compiled_at,
         compiled_generator,
         comments,
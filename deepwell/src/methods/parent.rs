@@ -19,9 +19,16 @@
  */
 
 use super::prelude::*;
-use crate::services::{parent::ParentalRelationshipType, ParentService};
+use crate::services::parent::{EditParent, ParentalRelationshipType};
+use crate::services::ParentService;
 use serde::Serialize;
 
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct DepthQuery {
+    depth: Option<u32>,
+}
+
 pub async fn parent_relationships_get(req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
     let ctx = ServiceContext::new(&req, &txn);
@@ -47,6 +54,64 @@ pub async fn parent_relationships_get(req: ApiRequest) -> ApiResponse {
     build_parent_response(&models, StatusCode::Ok)
 }
 
+pub async fn parent_ancestors_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let reference = Reference::try_from(&req)?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!(
+        "Getting ancestors of {:?} in site ID {}",
+        reference,
+        site_id
+    );
+
+    let nodes = ParentService::get_ancestors(&ctx, site_id, reference)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    build_parent_response(&nodes, StatusCode::Ok)
+}
+
+pub async fn parent_descendants_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let DepthQuery { depth } = req.query()?;
+    let reference = Reference::try_from(&req)?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!(
+        "Getting descendants of {:?} in site ID {} (depth {:?})",
+        reference,
+        site_id,
+        depth,
+    );
+
+    let nodes = ParentService::get_descendants(&ctx, site_id, reference, depth)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    build_parent_response(&nodes, StatusCode::Ok)
+}
+
+pub async fn parent_siblings_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let reference = Reference::try_from(&req)?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting siblings of {:?} in site ID {}", reference, site_id);
+
+    let pages = ParentService::get_siblings(&ctx, site_id, reference)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    build_parent_response(&pages, StatusCode::Ok)
+}
+
 pub async fn parent_head(req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
     let ctx = ServiceContext::new(&req, &txn);
@@ -97,10 +162,11 @@ pub async fn parent_get(req: ApiRequest) -> ApiResponse {
     build_parent_response(&model, StatusCode::Ok)
 }
 
-pub async fn parent_put(req: ApiRequest) -> ApiResponse {
+pub async fn parent_put(mut req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
     let ctx = ServiceContext::new(&req, &txn);
 
+    let input: EditParent = req.body_json().await?;
     let site_id = req.param("site_id")?.parse()?;
     let parent_reference =
         Reference::try_from_fields_key(&req, "parent_type", "parent_id_or_slug")?;
@@ -114,9 +180,10 @@ pub async fn parent_put(req: ApiRequest) -> ApiResponse {
         site_id,
     );
 
-    let created = ParentService::create(&ctx, site_id, parent_reference, child_reference)
-        .await
-        .to_api()?;
+    let created =
+        ParentService::create(&ctx, site_id, parent_reference, child_reference, input)
+            .await
+            .to_api()?;
 
     let status = if created {
         StatusCode::Created
@@ -128,10 +195,11 @@ pub async fn parent_put(req: ApiRequest) -> ApiResponse {
     Ok(Response::new(status))
 }
 
-pub async fn parent_delete(req: ApiRequest) -> ApiResponse {
+pub async fn parent_delete(mut req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
     let ctx = ServiceContext::new(&req, &txn);
 
+    let input: EditParent = req.body_json().await?;
     let site_id = req.param("site_id")?.parse()?;
     let parent_reference =
         Reference::try_from_fields_key(&req, "parent_type", "parent_id_or_slug")?;
@@ -145,7 +213,7 @@ pub async fn parent_delete(req: ApiRequest) -> ApiResponse {
         site_id,
     );
 
-    ParentService::remove(&ctx, site_id, parent_reference, child_reference)
+    ParentService::remove(&ctx, site_id, parent_reference, child_reference, input)
         .await
         .to_api()?;
 
@@ -0,0 +1,84 @@
+/*
+ * methods/redirect.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::redirect::{CreateAlias, DeleteRedirect};
+
+pub async fn page_redirects_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    let reference = Reference::try_from(&req)?;
+    tide::log::info!("Getting redirects to page {reference:?} in site ID {site_id}");
+
+    let redirects = RedirectService::get_all(&ctx, site_id, reference)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&redirects)?;
+    Ok(body.into())
+}
+
+pub async fn page_alias_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: CreateAlias = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let reference = Reference::try_from(&req)?;
+    tide::log::info!(
+        "Creating alias '{}' for page {:?} in site ID {}",
+        input.slug,
+        reference,
+        site_id,
+    );
+
+    let redirect = RedirectService::create_alias(&ctx, site_id, reference, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&redirect)?;
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn page_redirect_delete(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: DeleteRedirect = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let slug = req.param("slug")?;
+    tide::log::info!("Deleting redirect at '{slug}' in site ID {site_id}");
+
+    let deleted = RedirectService::delete(&ctx, site_id, slug, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    if deleted {
+        Ok(Response::new(StatusCode::NoContent))
+    } else {
+        Ok(Response::new(StatusCode::NotFound))
+    }
+}
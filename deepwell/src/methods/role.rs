@@ -0,0 +1,203 @@
+/*
+ * methods/role.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::role::{
+    AssignRole, CreateRole, DeleteRole, RemoveRole, SetCategoryPermissions, UpdateRole,
+};
+
+pub async fn roles_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting roles in site ID {site_id}");
+
+    let roles = RoleService::get_all(&ctx, site_id).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&roles)?;
+    Ok(body.into())
+}
+
+pub async fn role_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: CreateRole = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Creating role '{}' in site ID {site_id}", input.slug);
+
+    let role = RoleService::create(&ctx, site_id, input).await.to_api()?;
+    let body = Body::from_json(&role)?;
+    txn.commit().await?;
+
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn role_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    let role_id = req.param("role_id")?.parse()?;
+    tide::log::info!("Getting role ID {role_id} in site ID {site_id}");
+
+    let role = RoleService::get(&ctx, site_id, role_id).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&role)?;
+    Ok(body.into())
+}
+
+pub async fn role_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: UpdateRole = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let role_id = req.param("role_id")?.parse()?;
+    tide::log::info!("Updating role ID {role_id} in site ID {site_id}");
+
+    let role = RoleService::update(&ctx, site_id, role_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&role)?;
+    Ok(body.into())
+}
+
+pub async fn role_delete(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: DeleteRole = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let role_id = req.param("role_id")?.parse()?;
+    tide::log::info!("Deleting role ID {role_id} in site ID {site_id}");
+
+    RoleService::delete(&ctx, site_id, role_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn user_role_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Getting role for user ID {user_id} in site ID {site_id}");
+
+    let role = RoleService::get_user_role(&ctx, site_id, user_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&role)?;
+    Ok(body.into())
+}
+
+pub async fn user_role_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: AssignRole = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!(
+        "Assigning role ID {} to user ID {user_id} in site ID {site_id} (by user ID {})",
+        input.role_id,
+        input.assigned_by,
+    );
+
+    RoleService::assign(&ctx, site_id, user_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn user_role_delete(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: RemoveRole = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!(
+        "Removing role from user ID {user_id} in site ID {site_id} (by user ID {})",
+        input.removed_by,
+    );
+
+    RoleService::remove(&ctx, site_id, user_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn category_permissions_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    let category_id = req.param("category_id")?.parse()?;
+    tide::log::info!(
+        "Getting permission overrides for category ID {category_id} in site ID {site_id}",
+    );
+
+    let permissions = RoleService::get_category_permissions(&ctx, site_id, category_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&permissions)?;
+    Ok(body.into())
+}
+
+pub async fn category_permissions_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: SetCategoryPermissions = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    let category_id = req.param("category_id")?.parse()?;
+    tide::log::info!(
+        "Setting permission overrides for role ID {} on category ID {category_id} in site ID {site_id}",
+        input.role_id,
+    );
+
+    let permissions =
+        RoleService::set_category_permissions(&ctx, site_id, category_id, input)
+            .await
+            .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&permissions)?;
+    Ok(body.into())
+}
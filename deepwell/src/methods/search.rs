@@ -0,0 +1,41 @@
+/*
+ * methods/search.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::search::SearchPages;
+use crate::web::FetchLimitQuery;
+
+pub async fn search_get(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let FetchLimitQuery { limit } = req.query()?;
+    let input: SearchPages = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Searching pages in site ID {site_id}");
+
+    let results = SearchService::search(&ctx, site_id, input, limit.into())
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&results)?;
+    Ok(body.into())
+}
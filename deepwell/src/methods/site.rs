@@ -20,11 +20,22 @@
 
 use super::prelude::*;
 use crate::models::site::Model as SiteModel;
+use crate::services::site::CreateSite;
+use crate::services::site_settings::{SiteSettingsOutput, UpdateSiteSettings};
 
-pub async fn site_create(_req: ApiRequest) -> ApiResponse {
-    // TODO when we get to site table refactoring
+pub async fn site_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
 
-    todo!()
+    let input: CreateSite = req.body_json().await?;
+    tide::log::info!("Creating new site with slug '{}'", input.slug);
+
+    let output = SiteService::create(&ctx, input).await.to_api()?;
+    let body = Body::from_json(&output)?;
+    txn.commit().await?;
+
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
 }
 
 pub async fn site_head(req: ApiRequest) -> ApiResponse {
@@ -50,6 +61,45 @@ pub async fn site_get(req: ApiRequest) -> ApiResponse {
     build_site_response(&site, StatusCode::Ok)
 }
 
+pub async fn site_settings_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let reference = Reference::try_from(&req)?;
+    tide::log::info!("Getting settings for site {:?}", reference);
+
+    let site = SiteService::get(&ctx, reference).await.to_api()?;
+    let settings = SiteSettingsService::get(&ctx, site.site_id)
+        .await
+        .to_api()?;
+
+    let output: SiteSettingsOutput = settings.into();
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
+pub async fn site_settings_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: UpdateSiteSettings = req.body_json().await?;
+    let reference = Reference::try_from(&req)?;
+    tide::log::info!("Updating settings for site {:?}", reference);
+
+    let site = SiteService::get(&ctx, reference).await.to_api()?;
+    let settings = SiteSettingsService::update(&ctx, site.site_id, input)
+        .await
+        .to_api()?;
+
+    let output: SiteSettingsOutput = settings.into();
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
 fn build_site_response(site: &SiteModel, status: StatusCode) -> ApiResponse {
     let body = Body::from_json(site)?;
     let response = Response::builder(status).body(body).into();
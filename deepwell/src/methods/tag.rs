@@ -0,0 +1,102 @@
+/*
+ * methods/tag.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::tag::{RenameTag, SetAllowedTags, TagQuery};
+use crate::web::FetchLimitQuery;
+
+pub async fn tag_all_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting all tags in site ID {site_id}");
+
+    let tags = TagService::get_all(&ctx, site_id).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&tags)?;
+    Ok(body.into())
+}
+
+pub async fn tag_pages_get(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let FetchLimitQuery { limit } = req.query()?;
+    let input: TagQuery = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting pages by tag in site ID {site_id}");
+
+    let pages = TagService::get_pages(&ctx, site_id, input, limit.into())
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&pages)?;
+    Ok(body.into())
+}
+
+pub async fn tag_rename(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: RenameTag = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+
+    let output = TagService::rename(&ctx, site_id, input).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
+pub async fn tag_allowed_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting allowed tags in site ID {site_id}");
+
+    let tags = TagService::get_allowed(&ctx, site_id).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&tags)?;
+    Ok(body.into())
+}
+
+pub async fn tag_allowed_put(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: SetAllowedTags = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!(
+        "Setting {} allowed tags in site ID {site_id}",
+        input.tags.len(),
+    );
+
+    TagService::set_allowed(&ctx, site_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
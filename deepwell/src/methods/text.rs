@@ -20,6 +20,7 @@
 
 use super::prelude::*;
 use crate::hash::Hash;
+use crate::web::{conditional_response, etag_from_hash};
 
 pub async fn text_put(mut req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
@@ -46,7 +47,8 @@ pub async fn text_get(req: ApiRequest) -> ApiResponse {
     let body = Body::from_string(contents);
     txn.commit().await?;
 
-    Ok(body.into())
+    let etag = etag_from_hash(&hash);
+    Ok(conditional_response(&req, etag, StatusCode::Ok, body))
 }
 
 pub async fn text_head(req: ApiRequest) -> ApiResponse {
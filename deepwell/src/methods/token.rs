@@ -0,0 +1,139 @@
+/*
+ * methods/token.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::mail::Email;
+use crate::services::token::{
+    ConsumeToken, RequestEmailChange, RequestPasswordReset, ResetPassword,
+};
+
+pub async fn token_email_verification_create(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Sending email verification token for user ID {user_id}");
+
+    let (output, email) = TokenService::send_email_verification(&ctx, user_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    send_email(&req, &email).await?;
+
+    let body = Body::from_json(&output)?;
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn token_email_change_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: RequestEmailChange = req.body_json().await?;
+    let user_id = req.param("user_id")?.parse()?;
+    tide::log::info!("Sending email change token for user ID {user_id}");
+
+    let (output, email) = TokenService::send_email_change(&ctx, user_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    send_email(&req, &email).await?;
+
+    let body = Body::from_json(&output)?;
+    let response = Response::builder(StatusCode::Created).body(body).into();
+    Ok(response)
+}
+
+pub async fn token_password_reset_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: RequestPasswordReset = req.body_json().await?;
+    tide::log::info!("Requesting password reset token");
+
+    let email = TokenService::send_password_reset(&ctx, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+
+    if let Some(email) = email {
+        send_email(&req, &email).await?;
+    }
+
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn token_verify_email(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let ConsumeToken { token } = req.body_json().await?;
+    tide::log::info!("Consuming email verification token");
+
+    TokenService::verify_email(&ctx, &token).await.to_api()?;
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn token_confirm_email_change(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let ConsumeToken { token } = req.body_json().await?;
+    tide::log::info!("Consuming email change token");
+
+    TokenService::confirm_email_change(&ctx, &token)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+pub async fn token_reset_password(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let ResetPassword { token, password } = req.body_json().await?;
+    tide::log::info!("Consuming password reset token");
+
+    TokenService::reset_password(&ctx, &token, &password)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    Ok(Response::new(StatusCode::NoContent))
+}
+
+/// Sends an email containing a token.
+///
+/// This must be called after the transaction is committed,
+/// so that the token is not sent if issuing it failed.
+async fn send_email(req: &ApiRequest, email: &Email) -> Result<(), TideError> {
+    req.state()
+        .mailer
+        .send(email)
+        .await
+        .map_err(ServiceError::Mail)
+        .to_api()
+}
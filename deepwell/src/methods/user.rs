@@ -21,7 +21,8 @@
 use super::prelude::*;
 use crate::models::users::Model as UserModel;
 use crate::services::user::{
-    CreateUser, UpdateUser, UserIdentityOutput, UserInfoOutput, UserProfileOutput,
+    CreateUser, DeleteUser, UpdateUser, UserIdentityOutput, UserInfoOutput,
+    UserProfileOutput,
 };
 use crate::web::{UserDetails, UserDetailsQuery};
 
@@ -55,13 +56,37 @@ pub async fn user_get(req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
     let ctx = ServiceContext::new(&req, &txn);
 
-    let UserDetailsQuery { detail } = req.query()?;
+    let UserDetailsQuery { detail, site_id } = req.query()?;
     let reference = Reference::try_from(&req)?;
     tide::log::info!("Getting user {:?} (details {})", reference, detail.name());
 
     let user = UserService::get(&ctx, reference).await.to_api()?;
+    let role = match site_id {
+        Some(site_id) => {
+            let role = RoleService::get_user_role(&ctx, site_id, user.id)
+                .await
+                .to_api()?;
+
+            role.slug
+        }
+        None => String::new(),
+    };
+
     txn.commit().await?;
-    build_user_response(&user, detail, StatusCode::Ok)
+    build_user_response(&user, detail, role, StatusCode::Ok)
+}
+
+pub async fn user_aliases_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let reference = Reference::try_from(&req)?;
+    tide::log::info!("Getting username history for user {:?}", reference);
+
+    let aliases = UserService::get_aliases(&ctx, reference).await.to_api()?;
+    let body = Body::from_json(&aliases)?;
+    txn.commit().await?;
+    Ok(body.into())
 }
 
 pub async fn user_put(mut req: ApiRequest) -> ApiResponse {
@@ -78,29 +103,42 @@ pub async fn user_put(mut req: ApiRequest) -> ApiResponse {
     Ok(Response::new(StatusCode::NoContent))
 }
 
-pub async fn user_delete(req: ApiRequest) -> ApiResponse {
+pub async fn user_delete(mut req: ApiRequest) -> ApiResponse {
     let txn = req.database().begin().await?;
     let ctx = ServiceContext::new(&req, &txn);
 
-    let UserDetailsQuery { detail } = req.query()?;
+    let input: DeleteUser = req.body_json().await?;
+    let UserDetailsQuery { detail, .. } = req.query()?;
     let reference = Reference::try_from(&req)?;
-    tide::log::info!("Deleting user {:?} (details {})", reference, detail.name());
-
-    let user = UserService::delete(&ctx, reference).await.to_api()?;
+    tide::log::info!(
+        "Deleting user {:?} (details {}, by user ID {})",
+        reference,
+        detail.name(),
+        input.deleted_by,
+    );
+
+    let user = UserService::delete(&ctx, reference, input).await.to_api()?;
     txn.commit().await?;
-    build_user_response(&user, detail, StatusCode::Ok)
+    build_user_response(&user, detail, String::new(), StatusCode::Ok)
 }
 
 fn build_user_response(
     user: &UserModel,
     user_detail: UserDetails,
+    role: String,
     status: StatusCode,
 ) -> ApiResponse {
     // TODO: allow dumping the entire user model (internal API only)
     let body = match user_detail {
-        UserDetails::Identity => Body::from_json(&UserIdentityOutput::from(user))?,
-        UserDetails::Info => Body::from_json(&UserInfoOutput::from(user))?,
-        UserDetails::Profile => Body::from_json(&UserProfileOutput::from(user))?,
+        UserDetails::Identity => {
+            Body::from_json(&UserIdentityOutput::from(user).with_role(role))?
+        }
+        UserDetails::Info => {
+            Body::from_json(&UserInfoOutput::from(user).with_role(role))?
+        }
+        UserDetails::Profile => {
+            Body::from_json(&UserProfileOutput::from(user).with_role(role))?
+        }
     };
     let response = Response::builder(status).body(body).into();
     Ok(response)
@@ -0,0 +1,94 @@
+/*
+ * methods/v1/file.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+
+pub async fn files_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let query: CursorQuery = req.query()?;
+    let site_slug = req.param("site_slug")?;
+    let page_slug = req.param("page_slug")?;
+    tide::log::info!("Getting files on page '{page_slug}' in site '{site_slug}' (v1)");
+
+    let site = get_site(&ctx, site_slug).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site.site_id, Reference::Slug(page_slug))
+        .await
+        .to_api()?;
+
+    let start_id: String = query.key()?.unwrap_or_default();
+    let files =
+        FileService::get_range(&ctx, page.page_id, &start_id, query.fetch_limit())
+            .await
+            .to_api()?;
+
+    let files = CursorPage::new(files, &query, |file| file.file_id.clone());
+    let mut data = Vec::with_capacity(files.data.len());
+    for file in files.data {
+        let revision = FileRevisionService::get_latest(&ctx, page.page_id, &file.file_id)
+            .await
+            .to_api()?;
+
+        data.push(FileOutput::new(file, revision));
+    }
+
+    let output = CursorPage {
+        data,
+        next_cursor: files.next_cursor,
+    };
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
+pub async fn file_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_slug = req.param("site_slug")?;
+    let page_slug = req.param("page_slug")?;
+    let filename = req.param("filename")?;
+    tide::log::info!(
+        "Getting file '{filename}' on page '{page_slug}' in site '{site_slug}' (v1)",
+    );
+
+    let site = get_site(&ctx, site_slug).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site.site_id, Reference::Slug(page_slug))
+        .await
+        .to_api()?;
+
+    let file = FileService::get(&ctx, page.page_id, CuidReference::Name(filename))
+        .await
+        .to_api()?;
+
+    if file.deleted_at.is_some() {
+        return Ok(Response::new(StatusCode::NotFound));
+    }
+
+    let revision = FileRevisionService::get_latest(&ctx, page.page_id, &file.file_id)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&FileOutput::new(file, revision))?;
+    Ok(body.into())
+}
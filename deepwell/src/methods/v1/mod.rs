@@ -0,0 +1,70 @@
+/*
+ * methods/v1/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Implementations of the routes in version 1 of the API.
+//!
+//! Unlike the internal API, this is a stable, read-only API meant for
+//! third-party tools such as bots and archivers. It exposes only public
+//! information, in consistent shapes described by the OpenAPI document
+//! in `openapi/v1.json`, which is also served at `/api/v1/openapi.json`.
+//!
+//! Listings are paginated with cursors, see `CursorQuery`.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::get_site;
+    pub use super::structs::*;
+    pub use crate::web::{CursorPage, CursorQuery};
+}
+
+mod file;
+mod page;
+mod site;
+mod structs;
+mod user;
+
+pub use self::file::*;
+pub use self::page::*;
+pub use self::site::*;
+pub use self::user::*;
+
+use self::prelude::*;
+use crate::models::site::Model as SiteModel;
+use crate::services::Result;
+use tide::http::mime;
+
+/// The OpenAPI description of this version of the API.
+pub const OPENAPI_DOCUMENT: &str = include_str!("../../../openapi/v1.json");
+
+pub async fn openapi_get(_req: ApiRequest) -> ApiResponse {
+    let mut body = Body::from_string(str!(OPENAPI_DOCUMENT));
+    body.set_mime(mime::JSON);
+    Ok(body.into())
+}
+
+/// Gets a site by slug, if it is publicly visible.
+pub async fn get_site(ctx: &ServiceContext<'_>, slug: &str) -> Result<SiteModel> {
+    let site = SiteService::get(ctx, Reference::Slug(slug)).await?;
+    if site.deleted || site.private {
+        return Err(ServiceError::NotFound);
+    }
+
+    Ok(site)
+}
@@ -0,0 +1,239 @@
+/*
+ * methods/v1/page.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::json_utils::json_to_string_list;
+use crate::models::page::Model as PageModel;
+use crate::models::page_revision::Model as PageRevisionModel;
+use crate::services::vote::VoteHistoryKind;
+use crate::services::Result;
+use crate::web::{split_category_name, FetchDirection};
+use std::collections::HashMap;
+
+pub async fn pages_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let query: CursorQuery = req.query()?;
+    let site_slug = req.param("site_slug")?;
+    tide::log::info!("Getting pages in site '{site_slug}' (v1)");
+
+    let site = get_site(&ctx, site_slug).await.to_api()?;
+    let start_id = query.key()?.unwrap_or(0);
+    let pages = PageService::get_range(&ctx, site.site_id, start_id, query.fetch_limit())
+        .await
+        .to_api()?;
+
+    let pages = CursorPage::new(pages, &query, |page| page.page_id);
+    let output = build_page_list(&ctx, site.site_id, pages).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
+pub async fn page_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_slug = req.param("site_slug")?;
+    let page_slug = req.param("page_slug")?;
+    tide::log::info!("Getting page '{page_slug}' in site '{site_slug}' (v1)");
+
+    let site = get_site(&ctx, site_slug).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site.site_id, Reference::Slug(page_slug))
+        .await
+        .to_api()?;
+
+    let revision = RevisionService::get_latest(&ctx, site.site_id, page.page_id)
+        .await
+        .to_api()?;
+
+    let output = build_page(&ctx, page, revision).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
+pub async fn revisions_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let query: CursorQuery = req.query()?;
+    let site_slug = req.param("site_slug")?;
+    let page_slug = req.param("page_slug")?;
+    tide::log::info!(
+        "Getting revisions of page '{page_slug}' in site '{site_slug}' (v1)"
+    );
+
+    let site = get_site(&ctx, site_slug).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site.site_id, Reference::Slug(page_slug))
+        .await
+        .to_api()?;
+
+    // The cursor is the last revision number returned
+    let start_number = query.key()?.map(|number: i32| number + 1).unwrap_or(0);
+    let revisions = RevisionService::get_range(
+        &ctx,
+        site.site_id,
+        page.page_id,
+        start_number,
+        FetchDirection::After,
+        query.fetch_limit(),
+    )
+    .await
+    .to_api()?;
+
+    let revisions =
+        CursorPage::new(revisions, &query, |revision| revision.revision_number);
+
+    let output = CursorPage {
+        data: revisions
+            .data
+            .into_iter()
+            .map(RevisionOutput::try_from)
+            .collect::<Result<Vec<_>>>()
+            .to_api()?,
+        next_cursor: revisions.next_cursor,
+    };
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
+pub async fn revision_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_slug = req.param("site_slug")?;
+    let page_slug = req.param("page_slug")?;
+    let revision_number = req.param("revision_number")?.parse()?;
+    tide::log::info!(
+        "Getting revision {revision_number} of page '{page_slug}' in site '{site_slug}' (v1)",
+    );
+
+    let site = get_site(&ctx, site_slug).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site.site_id, Reference::Slug(page_slug))
+        .await
+        .to_api()?;
+
+    let revision =
+        RevisionService::get(&ctx, site.site_id, page.page_id, revision_number)
+            .await
+            .to_api()?;
+
+    let output = RevisionOutput::try_from(revision).to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
+pub async fn votes_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let query: CursorQuery = req.query()?;
+    let site_slug = req.param("site_slug")?;
+    let page_slug = req.param("page_slug")?;
+    tide::log::info!("Getting votes on page '{page_slug}' in site '{site_slug}' (v1)");
+
+    let site = get_site(&ctx, site_slug).await.to_api()?;
+    let page = PageService::get_resolved(&ctx, site.site_id, Reference::Slug(page_slug))
+        .await
+        .to_api()?;
+
+    // Only current votes are public
+    let start_id = query.key()?.unwrap_or(0);
+    let votes = VoteService::get_history(
+        &ctx,
+        VoteHistoryKind::Page(page.page_id),
+        start_id,
+        Some(false),
+        Some(false),
+        query.fetch_limit(),
+    )
+    .await
+    .to_api()?;
+
+    let output =
+        CursorPage::new(votes, &query, |vote| vote.page_vote_id).map(VoteOutput::from);
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
+
+async fn build_page(
+    ctx: &ServiceContext<'_>,
+    page: PageModel,
+    revision: PageRevisionModel,
+) -> Result<PageOutput> {
+    let rating = ScoreService::score(ctx, page.page_id).await?;
+    let category = str!(split_category_name(&page.slug).0);
+
+    Ok(PageOutput {
+        page_id: page.page_id,
+        site_id: page.site_id,
+        slug: page.slug,
+        category,
+        title: revision.title,
+        alt_title: revision.alt_title,
+        tags: json_to_string_list(revision.tags)?,
+        rating,
+        revision_count: revision.revision_number + 1,
+        created_at: page.created_at,
+        updated_at: page.updated_at,
+    })
+}
+
+async fn build_page_list(
+    ctx: &ServiceContext<'_>,
+    site_id: i64,
+    pages: CursorPage<PageModel>,
+) -> Result<CursorPage<PageOutput>> {
+    let page_ids = pages
+        .data
+        .iter()
+        .map(|page| page.page_id)
+        .collect::<Vec<_>>();
+    let mut revisions = RevisionService::get_latest_many(ctx, site_id, &page_ids)
+        .await?
+        .into_iter()
+        .map(|revision| (revision.page_id, revision))
+        .collect::<HashMap<_, _>>();
+
+    let mut data = Vec::with_capacity(pages.data.len());
+    for page in pages.data {
+        // All extant pages have at least one revision
+        let revision = revisions
+            .remove(&page.page_id)
+            .ok_or(ServiceError::NotFound)?;
+
+        data.push(build_page(ctx, page, revision).await?);
+    }
+
+    Ok(CursorPage {
+        data,
+        next_cursor: pages.next_cursor,
+    })
+}
@@ -0,0 +1,57 @@
+/*
+ * methods/v1/site.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+
+pub async fn site_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let site_slug = req.param("site_slug")?;
+    tide::log::info!("Getting site '{site_slug}' (v1)");
+
+    let site = get_site(&ctx, site_slug).await.to_api()?;
+
+    txn.commit().await?;
+    let body = Body::from_json(&SiteOutput::from(site))?;
+    Ok(body.into())
+}
+
+pub async fn tags_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let query: CursorQuery = req.query()?;
+    let site_slug = req.param("site_slug")?;
+    tide::log::info!("Getting tags in site '{site_slug}' (v1)");
+
+    let site = get_site(&ctx, site_slug).await.to_api()?;
+    let start_tag: String = query.key()?.unwrap_or_default();
+    let tags = TagService::get_range(&ctx, site.site_id, &start_tag, query.fetch_limit())
+        .await
+        .to_api()?;
+
+    let output =
+        CursorPage::new(tags, &query, |tag| tag.tag.clone()).map(TagOutput::from);
+
+    txn.commit().await?;
+    let body = Body::from_json(&output)?;
+    Ok(body.into())
+}
@@ -0,0 +1,481 @@
+/*
+ * methods/v1/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Output structures for version 1 of the API.
+//!
+//! These are part of the API's stability guarantee, and must be kept in sync
+//! with the schemas in the OpenAPI document. This is checked by tests below.
+
+use super::super::prelude::*;
+use crate::json_utils::json_to_string_list;
+use crate::models::file::Model as FileModel;
+use crate::models::file_revision::Model as FileRevisionModel;
+use crate::models::page_revision::Model as PageRevisionModel;
+use crate::models::page_vote::Model as PageVoteModel;
+use crate::models::sea_orm_active_enums::PageRevisionType;
+use crate::models::site::Model as SiteModel;
+use crate::models::users::Model as UserModel;
+use crate::services::tag::TagCount;
+use sea_orm::entity::prelude::DateTimeWithTimeZone;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteOutput {
+    pub site_id: i64,
+    pub slug: String,
+    pub name: Option<String>,
+    pub subtitle: Option<String>,
+    pub description: Option<String>,
+    pub language: String,
+    pub default_page: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<SiteModel> for SiteOutput {
+    fn from(site: SiteModel) -> Self {
+        SiteOutput {
+            site_id: site.site_id,
+            slug: site.slug,
+            name: site.name,
+            subtitle: site.subtitle,
+            description: site.description,
+            language: site.language,
+            default_page: site.default_page,
+            created_at: site.date_created,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PageOutput {
+    pub page_id: i64,
+    pub site_id: i64,
+    pub slug: String,
+    pub category: String,
+    pub title: String,
+    pub alt_title: Option<String>,
+    pub tags: Vec<String>,
+    pub rating: f64,
+    pub revision_count: i32,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: Option<DateTimeWithTimeZone>,
+}
+
+/// A page revision, with any fields hidden by staff set to `null`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionOutput {
+    pub revision_id: i64,
+    pub revision_number: i32,
+    pub revision_type: PageRevisionType,
+    pub page_id: i64,
+    pub user_id: i64,
+    pub created_at: DateTimeWithTimeZone,
+    pub changes: Vec<String>,
+    pub comments: Option<String>,
+    pub title: Option<String>,
+    pub alt_title: Option<String>,
+    pub slug: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl TryFrom<PageRevisionModel> for RevisionOutput {
+    type Error = ServiceError;
+
+    fn try_from(revision: PageRevisionModel) -> Result<Self, ServiceError> {
+        let mut output = RevisionOutput {
+            revision_id: revision.revision_id,
+            revision_number: revision.revision_number,
+            revision_type: revision.revision_type,
+            page_id: revision.page_id,
+            user_id: revision.user_id,
+            created_at: revision.created_at,
+            changes: json_to_string_list(revision.changes)?,
+            comments: Some(revision.comments),
+            title: Some(revision.title),
+            alt_title: revision.alt_title,
+            slug: Some(revision.slug),
+            tags: Some(json_to_string_list(revision.tags)?),
+        };
+
+        // Text fields are not exposed here, so only these need stripping
+        for field in json_to_string_list(revision.hidden)? {
+            match field.as_str() {
+                "comments" => output.comments = None,
+                "title" => output.title = None,
+                "alt_title" => output.alt_title = None,
+                "slug" => output.slug = None,
+                "tags" => output.tags = None,
+                _ => (),
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteOutput {
+    pub vote_id: i64,
+    pub page_id: i64,
+    pub user_id: i64,
+    pub value: i16,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl From<PageVoteModel> for VoteOutput {
+    fn from(vote: PageVoteModel) -> Self {
+        VoteOutput {
+            vote_id: vote.page_vote_id,
+            page_id: vote.page_id,
+            user_id: vote.user_id,
+            value: vote.value,
+            created_at: vote.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOutput {
+    pub file_id: String,
+    pub page_id: i64,
+    pub name: String,
+    pub mime: String,
+    pub size: i64,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: Option<DateTimeWithTimeZone>,
+}
+
+impl FileOutput {
+    /// Builds output from a file and its latest revision.
+    ///
+    /// The latest revision of a file cannot be hidden, so all its fields are public.
+    pub fn new(file: FileModel, revision: FileRevisionModel) -> Self {
+        FileOutput {
+            file_id: file.file_id,
+            page_id: file.page_id,
+            name: file.name,
+            mime: revision.mime_hint,
+            size: revision.size_hint,
+            created_at: file.created_at,
+            updated_at: file.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TagOutput {
+    pub name: String,
+    pub page_count: i64,
+}
+
+impl From<TagCount> for TagOutput {
+    fn from(tag: TagCount) -> Self {
+        TagOutput {
+            name: tag.tag,
+            page_count: tag.count,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOutput {
+    pub user_id: i64,
+    pub name: String,
+    pub slug: String,
+    pub pronouns: Option<String>,
+    pub about: Option<String>,
+    pub avatar: Option<String>,
+    pub karma: u8,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<UserModel> for UserOutput {
+    fn from(user: UserModel) -> Self {
+        UserOutput {
+            user_id: user.id,
+            name: user.username,
+            slug: user.slug,
+            pronouns: user.pronouns,
+            about: user.bio,
+            avatar: user.avatar_path,
+            karma: user.karma_level as u8,
+            created_at: user.created_at,
+        }
+    }
+}
+
+#[test]
+fn openapi_schemas() {
+    use super::OPENAPI_DOCUMENT;
+    use crate::utils::now;
+    use crate::web::CursorPage;
+    use serde_json::{Map as JsonMap, Value as JsonValue};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    let document: JsonValue = serde_json::from_str(OPENAPI_DOCUMENT)
+        .expect("OpenAPI document is not valid JSON");
+
+    /// Follows a "$ref" to a schema in the document, if present.
+    fn resolve<'a>(document: &'a JsonValue, schema: &'a JsonValue) -> &'a JsonValue {
+        match schema["$ref"].as_str() {
+            Some(reference) => {
+                let name = reference
+                    .strip_prefix("#/components/schemas/")
+                    .unwrap_or_else(|| panic!("Unexpected reference {reference}"));
+
+                &document["components"]["schemas"][name]
+            }
+            None => schema,
+        }
+    }
+
+    /// Checks that a serialized output has exactly the schema's properties.
+    ///
+    /// Every property in these schemas is required, nullable ones are marked as such.
+    /// Nested objects and array items are checked against their own schemas.
+    fn check(document: &JsonValue, schema: &JsonValue, output: &JsonValue, path: &str) {
+        let schema = resolve(document, schema);
+
+        match output {
+            JsonValue::Object(fields) => check_object(document, schema, fields, path),
+            JsonValue::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    let path = format!("{path}[{index}]");
+                    check(document, &schema["items"], item, &path);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn check_object(
+        document: &JsonValue,
+        schema: &JsonValue,
+        fields: &JsonMap<String, JsonValue>,
+        path: &str,
+    ) {
+        let actual = fields.keys().map(String::as_str).collect::<BTreeSet<_>>();
+
+        let properties = schema["properties"]
+            .as_object()
+            .unwrap_or_else(|| panic!("No properties in schema for {path}"));
+
+        let required = schema["required"]
+            .as_array()
+            .unwrap_or_else(|| panic!("No required list in schema for {path}"))
+            .iter()
+            .map(|value| value.as_str().expect("Required item is not a string"))
+            .collect::<BTreeSet<_>>();
+
+        assert_eq!(
+            actual,
+            properties
+                .keys()
+                .map(String::as_str)
+                .collect::<BTreeSet<_>>(),
+            "Fields of {path} differ from OpenAPI schema",
+        );
+        assert_eq!(
+            actual, required,
+            "Required fields of {path} differ from OpenAPI schema",
+        );
+
+        for (name, value) in fields {
+            let path = format!("{path}.{name}");
+            check(document, &properties[name], value, &path);
+        }
+    }
+
+    // Sample outputs for each schema, as the routes serialize them
+    let mut samples = BTreeMap::new();
+
+    macro_rules! sample {
+        ($name:expr, $output:expr $(,)?) => {
+            samples.insert(
+                $name,
+                serde_json::to_value(&$output).expect("Unable to serialize output"),
+            );
+        };
+    }
+
+    sample!(
+        "Site",
+        SiteOutput {
+            site_id: 1,
+            slug: str!("scp-wiki"),
+            name: None,
+            subtitle: None,
+            description: None,
+            language: str!("en"),
+            default_page: str!("start"),
+            created_at: None,
+        },
+    );
+
+    sample!(
+        "Page",
+        PageOutput {
+            page_id: 1,
+            site_id: 1,
+            slug: str!("scp-001"),
+            category: str!("_default"),
+            title: str!("SCP-001"),
+            alt_title: None,
+            tags: vec![],
+            rating: 0.0,
+            revision_count: 1,
+            created_at: now(),
+            updated_at: None,
+        },
+    );
+
+    sample!(
+        "Revision",
+        RevisionOutput {
+            revision_id: 1,
+            revision_number: 0,
+            revision_type: PageRevisionType::Create,
+            page_id: 1,
+            user_id: 1,
+            created_at: now(),
+            changes: vec![],
+            comments: None,
+            title: None,
+            alt_title: None,
+            slug: None,
+            tags: None,
+        },
+    );
+
+    sample!(
+        "Vote",
+        VoteOutput {
+            vote_id: 1,
+            page_id: 1,
+            user_id: 1,
+            value: 1,
+            created_at: now(),
+        },
+    );
+
+    sample!(
+        "File",
+        FileOutput {
+            file_id: str!("ckzxqlqd50000lp9k1a5ygbq6"),
+            page_id: 1,
+            name: str!("image.png"),
+            mime: str!("image/png"),
+            size: 0,
+            created_at: now(),
+            updated_at: None,
+        },
+    );
+
+    sample!(
+        "Tag",
+        TagOutput {
+            name: str!("scp"),
+            page_count: 1,
+        },
+    );
+
+    sample!(
+        "User",
+        UserOutput {
+            user_id: 1,
+            name: str!("Example"),
+            slug: str!("example"),
+            pronouns: None,
+            about: None,
+            avatar: None,
+            karma: 0,
+            created_at: None,
+        },
+    );
+
+    // Listings are a cursor page wrapping the item schema
+    for (list, item) in [
+        ("PageList", "Page"),
+        ("RevisionList", "Revision"),
+        ("VoteList", "Vote"),
+        ("FileList", "File"),
+        ("TagList", "Tag"),
+    ] {
+        sample!(
+            list,
+            CursorPage {
+                data: vec![samples[item].clone()],
+                next_cursor: None,
+            },
+        );
+    }
+
+    // Check the response of every route against its sample
+    let paths = document["paths"]
+        .as_object()
+        .expect("OpenAPI document has no paths");
+
+    let mut checked = BTreeSet::new();
+
+    for (path, item) in paths {
+        // The document itself is free-form
+        if path == "/openapi.json" {
+            continue;
+        }
+
+        let schema =
+            &item["get"]["responses"]["200"]["content"]["application/json"]["schema"];
+
+        let name = schema["$ref"]
+            .as_str()
+            .and_then(|reference| reference.strip_prefix("#/components/schemas/"))
+            .unwrap_or_else(|| panic!("Response of {path} is not a schema reference"));
+
+        let output = samples
+            .get(name)
+            .unwrap_or_else(|| panic!("No sample output for schema {name} ({path})"));
+
+        check(&document, schema, output, name);
+        checked.insert(name);
+    }
+
+    // Every schema should be returned by some route
+    let schemas = document["components"]["schemas"]
+        .as_object()
+        .expect("OpenAPI document has no schemas")
+        .keys()
+        .map(String::as_str)
+        .filter(|name| !name.ends_with("List"))
+        .collect::<BTreeSet<_>>();
+
+    for name in schemas {
+        let listed = format!("{name}List");
+        assert!(
+            checked.contains(name) || checked.contains(listed.as_str()),
+            "Schema {name} is not returned by any route",
+        );
+    }
+}
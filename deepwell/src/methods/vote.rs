@@ -20,8 +20,10 @@
 
 use super::prelude::*;
 use crate::services::vote::{
-    CreateVote, GetVote, GetVoteHistory, VoteAction, VoteReference,
+    CreateVote, GetVote, GetVoteAnalytics, GetVoteHistory, VoteAction, VoteReference,
 };
+use crate::services::vote_analysis::CreateVoteAnalysis;
+use crate::services::JobService;
 use crate::web::FetchLimitQuery;
 use serde::Serialize;
 
@@ -190,6 +192,68 @@ pub async fn vote_count_get(mut req: ApiRequest) -> ApiResponse {
     build_vote_response(&count, StatusCode::Ok)
 }
 
+pub async fn vote_analytics_get(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let GetVoteAnalytics { kind, bucket } = req.body_json().await?;
+    tide::log::info!("Getting vote analytics for {kind:?}");
+
+    let analytics = VoteService::get_analytics(&ctx, kind, bucket)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    build_vote_response(&analytics, StatusCode::Ok)
+}
+
+pub async fn vote_analysis_create(mut req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let input: CreateVoteAnalysis = req.body_json().await?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Requesting vote analysis for site ID {site_id}");
+
+    let output = VoteAnalysisService::create(&ctx, site_id, input)
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+
+    // The job must be queued after commit, so the report row is visible to it
+    JobService::queue_vote_analysis(output.report_id);
+    build_vote_response(&output, StatusCode::Accepted)
+}
+
+pub async fn vote_analysis_list_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let FetchLimitQuery { limit } = req.query()?;
+    let site_id = req.param("site_id")?.parse()?;
+    tide::log::info!("Getting vote analysis reports for site ID {site_id}");
+
+    let reports = VoteAnalysisService::get_all(&ctx, site_id, limit.into())
+        .await
+        .to_api()?;
+
+    txn.commit().await?;
+    build_vote_response(&reports, StatusCode::Ok)
+}
+
+pub async fn vote_analysis_get(req: ApiRequest) -> ApiResponse {
+    let txn = req.database().begin().await?;
+    let ctx = ServiceContext::new(&req, &txn);
+
+    let report_id = req.param("report_id")?.parse()?;
+    tide::log::info!("Getting vote analysis report ID {report_id}");
+
+    let report = VoteAnalysisService::get(&ctx, report_id).await.to_api()?;
+    txn.commit().await?;
+    build_vote_response(&report, StatusCode::Ok)
+}
+
 fn build_vote_response<T: Serialize>(data: &T, status: StatusCode) -> ApiResponse {
     let body = Body::from_json(data)?;
     let response = Response::builder(status).body(body).into();
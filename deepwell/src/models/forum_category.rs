@@ -8,17 +8,17 @@ use serde::{Deserialize, Serialize};
 pub struct Model {
     #[sea_orm(primary_key)]
     pub category_id: i64,
-    pub group_id: Option<i32>,
+    pub group_id: Option<i64>,
     pub name: Option<String>,
     pub description: Option<String>,
     pub number_posts: i32,
     pub number_threads: i32,
-    pub last_post_id: Option<i32>,
+    pub last_post_id: Option<i64>,
     pub permissions_default: bool,
     pub permissions: Option<String>,
     pub max_nest_level: Option<i32>,
     pub sort_index: i32,
-    pub site_id: Option<i32>,
+    pub site_id: Option<i64>,
     pub per_page_discussion: bool,
 }
 
@@ -11,7 +11,7 @@ pub struct Model {
     pub name: Option<String>,
     pub description: Option<String>,
     pub sort_index: i32,
-    pub site_id: Option<i32>,
+    pub site_id: Option<i64>,
     pub visible: bool,
 }
 
@@ -8,19 +8,24 @@ use serde::{Deserialize, Serialize};
 pub struct Model {
     #[sea_orm(primary_key)]
     pub post_id: i64,
-    pub thread_id: Option<i32>,
-    pub parent_id: Option<i32>,
-    pub user_id: Option<i32>,
+    pub thread_id: Option<i64>,
+    pub parent_id: Option<i64>,
+    pub user_id: Option<i64>,
     pub user_string: Option<String>,
     pub title: Option<String>,
     pub text: Option<String>,
     pub date_posted: Option<DateTimeUtc>,
-    pub site_id: Option<i32>,
+    pub site_id: Option<i64>,
     pub revision_number: i32,
-    pub revision_id: Option<i32>,
+    pub revision_id: Option<i64>,
     pub date_last_edited: Option<DateTimeUtc>,
-    pub edited_user_id: Option<i32>,
+    pub edited_user_id: Option<i64>,
     pub edited_user_string: Option<String>,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+    pub compiled_hash: Option<Vec<u8>>,
+    pub compiled_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub compiled_generator: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
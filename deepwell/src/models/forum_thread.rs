@@ -8,18 +8,20 @@ use serde::{Deserialize, Serialize};
 pub struct Model {
     #[sea_orm(primary_key)]
     pub thread_id: i64,
-    pub user_id: Option<i32>,
+    pub user_id: Option<i64>,
     pub user_string: Option<String>,
-    pub category_id: Option<i32>,
+    pub category_id: Option<i64>,
     pub title: Option<String>,
     pub description: Option<String>,
     pub number_posts: i32,
     pub date_started: Option<DateTimeUtc>,
-    pub site_id: Option<i32>,
-    pub last_post_id: Option<i32>,
-    pub page_id: Option<i32>,
+    pub site_id: Option<i64>,
+    pub last_post_id: Option<i64>,
+    pub page_id: Option<i64>,
     pub sticky: bool,
     pub blocked: bool,
+    pub archived: bool,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -5,25 +5,38 @@
 
 pub mod prelude;
 
+pub mod audit_log;
 pub mod file;
 pub mod file_revision;
 pub mod forum_category;
 pub mod forum_group;
 pub mod forum_post;
+pub mod forum_post_revision;
 pub mod forum_thread;
 pub mod page;
 pub mod page_category;
+pub mod page_category_permission;
 pub mod page_connection;
 pub mod page_connection_missing;
 pub mod page_link;
 pub mod page_parent;
+pub mod page_redirect;
 pub mod page_revision;
+pub mod page_tag;
 pub mod page_vote;
 pub mod sea_orm_active_enums;
 pub mod settings;
 pub mod site;
+pub mod site_allowed_tag;
+pub mod site_application;
+pub mod site_invitation;
+pub mod site_role;
 pub mod site_settings;
+pub mod site_user_role;
 pub mod text;
+pub mod user_alias;
 pub mod user_block;
 pub mod user_messages;
+pub mod user_token;
 pub mod users;
+pub mod vote_analysis_report;
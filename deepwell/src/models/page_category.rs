@@ -1,5 +1,6 @@
 //! SeaORM Entity. Generated by sea-orm-codegen 0.6.0
 
+use super::sea_orm_active_enums::{ScoreType, VoteType};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +14,8 @@ pub struct Model {
     pub site_id: i64,
     #[sea_orm(column_type = "Text")]
     pub slug: String,
+    pub score_type: Option<ScoreType>,
+    pub vote_type: Option<VoteType>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
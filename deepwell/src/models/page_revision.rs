@@ -21,6 +21,8 @@ pub struct Model {
     pub compiled_at: DateTimeWithTimeZone,
     #[sea_orm(column_type = "Text")]
     pub compiled_generator: String,
+    pub compiled_styles: Json,
+    pub compiled_meta: Json,
     #[sea_orm(column_type = "Text")]
     pub comments: String,
     pub hidden: Json,
@@ -1,23 +1,36 @@
 //! SeaORM Entity. Generated by sea-orm-codegen 0.6.0
 
+pub use super::audit_log::Entity as AuditLog;
 pub use super::file::Entity as File;
 pub use super::file_revision::Entity as FileRevision;
 pub use super::forum_category::Entity as ForumCategory;
 pub use super::forum_group::Entity as ForumGroup;
 pub use super::forum_post::Entity as ForumPost;
+pub use super::forum_post_revision::Entity as ForumPostRevision;
 pub use super::forum_thread::Entity as ForumThread;
 pub use super::page::Entity as Page;
 pub use super::page_category::Entity as PageCategory;
+pub use super::page_category_permission::Entity as PageCategoryPermission;
 pub use super::page_connection::Entity as PageConnection;
 pub use super::page_connection_missing::Entity as PageConnectionMissing;
 pub use super::page_link::Entity as PageLink;
 pub use super::page_parent::Entity as PageParent;
+pub use super::page_redirect::Entity as PageRedirect;
 pub use super::page_revision::Entity as PageRevision;
+pub use super::page_tag::Entity as PageTag;
 pub use super::page_vote::Entity as PageVote;
 pub use super::settings::Entity as Settings;
 pub use super::site::Entity as Site;
+pub use super::site_allowed_tag::Entity as SiteAllowedTag;
+pub use super::site_application::Entity as SiteApplication;
+pub use super::site_invitation::Entity as SiteInvitation;
+pub use super::site_role::Entity as SiteRole;
 pub use super::site_settings::Entity as SiteSettings;
+pub use super::site_user_role::Entity as SiteUserRole;
 pub use super::text::Entity as Text;
+pub use super::user_alias::Entity as UserAlias;
 pub use super::user_block::Entity as UserBlock;
 pub use super::user_messages::Entity as UserMessages;
+pub use super::user_token::Entity as UserToken;
 pub use super::users::Entity as Users;
+pub use super::vote_analysis_report::Entity as VoteAnalysisReport;
@@ -34,3 +34,33 @@ pub enum PageRevisionType {
     #[sea_orm(string_value = "undelete")]
     Undelete,
 }
+#[derive(
+    Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "score_type")]
+#[serde(rename_all = "camelCase")]
+pub enum ScoreType {
+    #[sea_orm(string_value = "mean")]
+    Mean,
+    #[sea_orm(string_value = "median")]
+    Median,
+    #[sea_orm(string_value = "null")]
+    Null,
+    #[sea_orm(string_value = "percent")]
+    Percent,
+    #[sea_orm(string_value = "sum")]
+    Sum,
+    #[sea_orm(string_value = "wilson")]
+    Wilson,
+}
+#[derive(
+    Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "vote_type")]
+#[serde(rename_all = "camelCase")]
+pub enum VoteType {
+    #[sea_orm(string_value = "five_star")]
+    FiveStar,
+    #[sea_orm(string_value = "ups_downs")]
+    UpsDowns,
+}
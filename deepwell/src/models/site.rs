@@ -1,5 +1,6 @@
 //! SeaORM Entity. Generated by sea-orm-codegen 0.6.0
 
+use super::sea_orm_active_enums::{ScoreType, VoteType};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -20,6 +21,8 @@ pub struct Model {
     pub default_page: String,
     pub private: bool,
     pub deleted: bool,
+    pub score_type: ScoreType,
+    pub vote_type: VoteType,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -8,10 +8,15 @@ use serde::{Deserialize, Serialize};
 pub struct Model {
     #[sea_orm(primary_key)]
     pub block_id: i64,
-    pub site_id: Option<i32>,
-    pub user_id: Option<i32>,
+    pub site_id: Option<i64>,
+    pub user_id: i64,
     pub reason: Option<String>,
     pub date_blocked: Option<DateTimeUtc>,
+    pub blocker_user_id: Option<i64>,
+    pub created_by: Option<i64>,
+    pub expires_at: Option<DateTimeWithTimeZone>,
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+    pub revoked_by: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -24,6 +29,14 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Site,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Users,
 }
 
 impl Related<super::site::Entity> for Entity {
@@ -32,4 +45,10 @@ impl Related<super::site::Entity> for Entity {
     }
 }
 
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -21,6 +21,7 @@ pub struct Model {
     pub multi_factor_secret: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub multi_factor_recovery_codes: Option<String>,
+    pub multi_factor_last_step: Option<i64>,
     pub remember_token: Option<String>,
     pub language: Option<String>,
     pub karma_points: i32,
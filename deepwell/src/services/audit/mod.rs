@@ -0,0 +1,40 @@
+/*
+ * services/audit/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Append-only log of moderation and administrative actions.
+//!
+//! Unlike page and file revisions, many sensitive actions (disabling votes,
+//! changing roles, banning users, etc.) do not otherwise leave a trace.
+//! Each entry records the actor, the action and its target, the value before
+//! and after the change, and the IP address and user agent of the request.
+//!
+//! Entries are never modified or removed, which is also enforced by a
+//! trigger in the database.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::AuditService;
+pub use self::structs::*;
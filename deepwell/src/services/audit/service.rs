@@ -0,0 +1,122 @@
+/*
+ * services/audit/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::audit_log::{self, Entity as AuditLog, Model as AuditLogModel};
+use crate::services::permission::{Action, PermissionService};
+
+#[derive(Debug)]
+pub struct AuditService;
+
+impl AuditService {
+    /// Appends an entry to the audit log.
+    ///
+    /// The IP address and user agent are taken from the current request, if any.
+    pub async fn record(
+        ctx: &ServiceContext<'_>,
+        CreateAuditEntry {
+            site_id,
+            user_id,
+            action,
+            target,
+            before,
+            after,
+        }: CreateAuditEntry,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let metadata = ctx.metadata();
+
+        tide::log::info!(
+            "Recording audit entry '{}' on {} {}",
+            action.name(),
+            target.kind(),
+            target.id(),
+        );
+
+        let model = audit_log::ActiveModel {
+            site_id: Set(site_id),
+            user_id: Set(user_id),
+            action: Set(str!(action.name())),
+            target_type: Set(str!(target.kind())),
+            target_id: Set(target.id()),
+            value_before: Set(before),
+            value_after: Set(after),
+            ip_address: Set(metadata.ip_address.clone()),
+            user_agent: Set(metadata.user_agent.clone()),
+            created_at: Set(now()),
+            ..Default::default()
+        };
+        model.insert(txn).await?;
+
+        Ok(())
+    }
+
+    /// Gets audit entries for a site matching the given filters, newest first.
+    ///
+    /// If `before` is set, only entries older than that audit ID are returned.
+    pub async fn get_entries(
+        ctx: &ServiceContext<'_>,
+        AuditQuery {
+            site_id,
+            acting_user_id,
+            user_id,
+            action,
+            target_type,
+            target_id,
+        }: AuditQuery,
+        before: Option<i64>,
+        limit: u64,
+    ) -> Result<Vec<AuditLogModel>> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, acting_user_id, Action::Moderate)
+            .await?;
+
+        let mut condition = Condition::all().add(audit_log::Column::SiteId.eq(site_id));
+
+        if let Some(before) = before {
+            condition = condition.add(audit_log::Column::AuditId.lt(before));
+        }
+
+        if let Some(user_id) = user_id {
+            condition = condition.add(audit_log::Column::UserId.eq(user_id));
+        }
+
+        if let Some(action) = action {
+            condition = condition.add(audit_log::Column::Action.eq(action));
+        }
+
+        if let Some(target_type) = target_type {
+            condition = condition.add(audit_log::Column::TargetType.eq(target_type));
+        }
+
+        if let Some(target_id) = target_id {
+            condition = condition.add(audit_log::Column::TargetId.eq(target_id));
+        }
+
+        let entries = AuditLog::find()
+            .filter(condition)
+            .order_by_desc(audit_log::Column::AuditId)
+            .limit(limit)
+            .all(txn)
+            .await?;
+
+        Ok(entries)
+    }
+}
@@ -0,0 +1,152 @@
+/*
+ * services/audit/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum AuditAction {
+    VoteEnable,
+    VoteDisable,
+    UserDelete,
+    RoleCreate,
+    RoleUpdate,
+    RoleDelete,
+    RoleAssign,
+    RoleRemove,
+    CategoryUpdate,
+    CategoryPermissionsSet,
+    SiteBan,
+    SiteUnban,
+    SiteSettingsUpdate,
+    RevisionUpdate,
+    FileRevisionUpdate,
+    FileHardDelete,
+}
+
+impl AuditAction {
+    pub fn name(self) -> &'static str {
+        match self {
+            AuditAction::VoteEnable => "vote.enable",
+            AuditAction::VoteDisable => "vote.disable",
+            AuditAction::UserDelete => "user.delete",
+            AuditAction::RoleCreate => "role.create",
+            AuditAction::RoleUpdate => "role.update",
+            AuditAction::RoleDelete => "role.delete",
+            AuditAction::RoleAssign => "role.assign",
+            AuditAction::RoleRemove => "role.remove",
+            AuditAction::CategoryUpdate => "category.update",
+            AuditAction::CategoryPermissionsSet => "category-permissions.set",
+            AuditAction::SiteBan => "site.ban",
+            AuditAction::SiteUnban => "site.unban",
+            AuditAction::SiteSettingsUpdate => "site-settings.update",
+            AuditAction::RevisionUpdate => "revision.update",
+            AuditAction::FileRevisionUpdate => "file-revision.update",
+            AuditAction::FileHardDelete => "file.hard-delete",
+        }
+    }
+}
+
+/// The object an audited action was performed on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditTarget {
+    Vote(i64),
+    User(i64),
+    Role(i64),
+    Category(i64),
+    Site(i64),
+    Revision(i64),
+    FileRevision(i64),
+    File(String),
+}
+
+impl AuditTarget {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AuditTarget::Vote(_) => "vote",
+            AuditTarget::User(_) => "user",
+            AuditTarget::Role(_) => "role",
+            AuditTarget::Category(_) => "category",
+            AuditTarget::Site(_) => "site",
+            AuditTarget::Revision(_) => "revision",
+            AuditTarget::FileRevision(_) => "file-revision",
+            AuditTarget::File(_) => "file",
+        }
+    }
+
+    pub fn id(&self) -> String {
+        match self {
+            AuditTarget::Vote(id)
+            | AuditTarget::User(id)
+            | AuditTarget::Role(id)
+            | AuditTarget::Category(id)
+            | AuditTarget::Site(id)
+            | AuditTarget::Revision(id)
+            | AuditTarget::FileRevision(id) => id.to_string(),
+            AuditTarget::File(id) => id.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateAuditEntry {
+    pub site_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub action: AuditAction,
+    pub target: AuditTarget,
+    pub before: Option<JsonValue>,
+    pub after: Option<JsonValue>,
+}
+
+/// Filters for listing audit entries.
+///
+/// Pagination is handled separately with `CursorQuery`,
+/// which is read from the same query string.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditQuery {
+    /// The site to return entries for.
+    pub site_id: i64,
+
+    /// The user requesting the entries, who must be able to moderate the site.
+    pub acting_user_id: i64,
+
+    /// Only return entries for actions performed by this user.
+    pub user_id: Option<i64>,
+
+    /// Only return entries for this action, e.g. `vote.disable`.
+    pub action: Option<String>,
+
+    /// Only return entries for this kind of target, e.g. `user`.
+    pub target_type: Option<String>,
+
+    /// Only return entries for this target ID. Usually used with `target_type`.
+    pub target_id: Option<String>,
+}
+
+#[test]
+fn audit_targets() {
+    let target = AuditTarget::Vote(42);
+    assert_eq!(target.kind(), "vote");
+    assert_eq!(target.id(), "42");
+
+    let target = AuditTarget::File(str!("ckzxqlqd50000lp9k1a5ygbq6"));
+    assert_eq!(target.kind(), "file");
+    assert_eq!(target.id(), "ckzxqlqd50000lp9k1a5ygbq6");
+}
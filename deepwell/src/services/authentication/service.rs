@@ -0,0 +1,83 @@
+/*
+ * services/authentication/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::users;
+use crate::services::password::{PasswordService, PasswordVerification};
+use crate::services::UserService;
+
+#[derive(Debug)]
+pub struct AuthenticationService;
+
+impl AuthenticationService {
+    /// Checks the credentials for a user logging in.
+    ///
+    /// Fails with `Error::InvalidAuthentication` if the user does not exist
+    /// or the password is incorrect, without distinguishing between the two.
+    ///
+    /// If the user has MFA enabled, the caller must also check their second
+    /// factor with `MfaService::verify()` before considering them logged in.
+    pub async fn login(
+        ctx: &ServiceContext<'_>,
+        input: LoginUser,
+    ) -> Result<LoginUserOutput> {
+        let txn = ctx.transaction();
+        let user =
+            match UserService::get_by_name_or_email(ctx, &input.name_or_email).await? {
+                Some(user) => user,
+                None => {
+                    // Hash anyway, so response times do not reveal which users exist
+                    PasswordService::hash(ctx, &input.password).await?;
+
+                    tide::log::warn!("No user found for login, rejecting");
+                    return Err(Error::InvalidAuthentication);
+                }
+            };
+
+        let verification =
+            PasswordService::verify(ctx, &input.password, &user.password).await?;
+
+        match verification {
+            PasswordVerification::Invalid => {
+                tide::log::warn!("Invalid password for user ID {}, rejecting", user.id);
+                return Err(Error::InvalidAuthentication);
+            }
+            PasswordVerification::Valid => (),
+            PasswordVerification::ValidNeedsRehash => {
+                tide::log::info!("Upgrading password hash for user ID {}", user.id);
+
+                let hash = PasswordService::hash(ctx, &input.password).await?;
+                let model = users::ActiveModel {
+                    id: Set(user.id),
+                    password: Set(hash),
+                    ..Default::default()
+                };
+
+                model.update(txn).await?;
+            }
+        }
+
+        Ok(LoginUserOutput {
+            user_id: user.id,
+            slug: user.slug,
+            multi_factor: user.multi_factor_secret.is_some(),
+        })
+    }
+}
@@ -0,0 +1,40 @@
+/*
+ * services/block/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Site bans and blocks between users.
+//!
+//! A site ban prevents a user from making changes to a site, such as editing
+//! pages, voting, or posting in its forums. A user block prevents either user
+//! from sending messages to the other.
+//!
+//! Both may have an expiry time, after which they no longer apply. Lifting a
+//! ban or block marks it as revoked rather than deleting it, so that staff can
+//! see who issued and lifted past bans.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::BlockService;
+pub use self::structs::*;
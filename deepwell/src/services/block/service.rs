@@ -0,0 +1,459 @@
+/*
+ * services/block/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::site_role::Model as SiteRoleModel;
+use crate::models::user_block::{self, Entity as UserBlock, Model as UserBlockModel};
+use crate::services::audit::{AuditAction, AuditTarget, CreateAuditEntry};
+use crate::services::permission::{Action, PermissionService};
+use crate::services::{AuditService, RoleService};
+use sea_orm::{DatabaseBackend, FromQueryResult, Statement};
+
+/// The `interactions.interaction_type` for a user blocking another user.
+///
+/// See `InteractionType::USER_BLOCKS_USER` in the web application.
+const USER_BLOCKS_USER_INTERACTION: i16 = 5;
+
+/// The `interactions.setter_type` and `target_type` used for users.
+const USER_INTERACTION_TYPE: &str = "Wikijump\\Models\\User";
+
+#[derive(Debug)]
+pub struct BlockService;
+
+impl BlockService {
+    /// Bans a user from a site.
+    ///
+    /// If the user is already banned, the reason and expiry are replaced.
+    /// Otherwise a new ban is added, keeping any expired or lifted ones as history.
+    pub async fn ban(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        CreateSiteBan {
+            user_id,
+            reason,
+            expires_at,
+            created_by,
+        }: CreateSiteBan,
+    ) -> Result<UserBlockModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, created_by, Action::Moderate).await?;
+
+        if user_id == created_by {
+            tide::log::error!("User ID {user_id} cannot ban themselves");
+            return Err(Error::BadRequest);
+        }
+
+        // Bans apply before any role, so staff could otherwise lock out their superiors
+        let banner_role = RoleService::get_user_role(ctx, site_id, created_by).await?;
+        let target_role = RoleService::get_user_role(ctx, site_id, user_id).await?;
+        if !outranks(&banner_role, &target_role) {
+            tide::log::error!(
+                "User ID {created_by} ('{}') cannot ban user ID {user_id} ('{}')",
+                banner_role.slug,
+                target_role.slug,
+            );
+
+            return Err(Error::PermissionDenied("ban this user"));
+        }
+
+        let existing = UserBlock::find()
+            .filter(
+                Condition::all()
+                    .add(user_block::Column::SiteId.eq(site_id))
+                    .add(user_block::Column::UserId.eq(user_id))
+                    .add(active_condition()),
+            )
+            .one(txn)
+            .await?;
+
+        let mut model = user_block::ActiveModel {
+            site_id: Set(Some(site_id)),
+            user_id: Set(user_id),
+            reason: Set(reason),
+            date_blocked: Set(Some(Utc::now())),
+            created_by: Set(Some(created_by)),
+            expires_at: Set(expires_at),
+            ..Default::default()
+        };
+
+        let (ban, before) = match existing {
+            Some(block) => {
+                let before = serde_json::to_value(&block)?;
+                model.block_id = Set(block.block_id);
+                (model.update(txn).await?, Some(before))
+            }
+            None => (model.insert(txn).await?, None),
+        };
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(site_id),
+                user_id: Some(created_by),
+                action: AuditAction::SiteBan,
+                target: AuditTarget::User(user_id),
+                before,
+                after: Some(serde_json::to_value(&ban)?),
+            },
+        )
+        .await?;
+
+        Ok(ban)
+    }
+
+    /// Lifts a user's ban from a site.
+    pub async fn unban(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        RemoveSiteBan {
+            user_id,
+            revoked_by,
+        }: RemoveSiteBan,
+    ) -> Result<UserBlockModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, revoked_by, Action::Moderate).await?;
+
+        let ban = UserBlock::find()
+            .filter(
+                Condition::all()
+                    .add(user_block::Column::SiteId.eq(site_id))
+                    .add(user_block::Column::UserId.eq(user_id))
+                    .add(active_condition()),
+            )
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        let before = serde_json::to_value(&ban)?;
+        let model = user_block::ActiveModel {
+            block_id: Set(ban.block_id),
+            revoked_at: Set(Some(now())),
+            revoked_by: Set(Some(revoked_by)),
+            ..Default::default()
+        };
+
+        let ban = model.update(txn).await?;
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(site_id),
+                user_id: Some(revoked_by),
+                action: AuditAction::SiteUnban,
+                target: AuditTarget::User(user_id),
+                before: Some(before),
+                after: Some(serde_json::to_value(&ban)?),
+            },
+        )
+        .await?;
+
+        Ok(ban)
+    }
+
+    /// Gets all the bans currently in effect on a site, newest first.
+    pub async fn get_site_bans(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+    ) -> Result<Vec<UserBlockModel>> {
+        let txn = ctx.transaction();
+        let bans = UserBlock::find()
+            .filter(
+                Condition::all()
+                    .add(user_block::Column::SiteId.eq(site_id))
+                    .add(active_condition()),
+            )
+            .order_by_desc(user_block::Column::DateBlocked)
+            .all(txn)
+            .await?;
+
+        Ok(bans)
+    }
+
+    pub async fn is_banned(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+    ) -> Result<bool> {
+        let txn = ctx.transaction();
+        let count = UserBlock::find()
+            .filter(
+                Condition::all()
+                    .add(user_block::Column::SiteId.eq(site_id))
+                    .add(user_block::Column::UserId.eq(user_id))
+                    .add(active_condition()),
+            )
+            .count(txn)
+            .await?;
+
+        Ok(count > 0)
+    }
+
+    /// Checks that the user is not banned from the site.
+    ///
+    /// If they are, this method fails with `Error::UserBanned`.
+    pub async fn check_banned(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+    ) -> Result<()> {
+        if Self::is_banned(ctx, site_id, user_id).await? {
+            tide::log::error!("User ID {user_id} is banned from site ID {site_id}");
+            return Err(Error::UserBanned);
+        }
+
+        Ok(())
+    }
+
+    /// Has one user block another.
+    ///
+    /// If the user is already blocked, the reason and expiry are replaced.
+    pub async fn block_user(
+        ctx: &ServiceContext<'_>,
+        CreateUserBlock {
+            user_id,
+            blocked_user_id,
+            reason,
+            expires_at,
+        }: CreateUserBlock,
+    ) -> Result<UserBlockModel> {
+        let txn = ctx.transaction();
+
+        if user_id == blocked_user_id {
+            tide::log::error!("User ID {user_id} cannot block themselves");
+            return Err(Error::BadRequest);
+        }
+
+        let existing = UserBlock::find()
+            .filter(
+                Condition::all()
+                    .add(user_block::Column::BlockerUserId.eq(user_id))
+                    .add(user_block::Column::UserId.eq(blocked_user_id))
+                    .add(active_condition()),
+            )
+            .one(txn)
+            .await?;
+
+        let mut model = user_block::ActiveModel {
+            user_id: Set(blocked_user_id),
+            blocker_user_id: Set(Some(user_id)),
+            reason: Set(reason),
+            date_blocked: Set(Some(Utc::now())),
+            created_by: Set(Some(user_id)),
+            expires_at: Set(expires_at),
+            ..Default::default()
+        };
+
+        let block = match existing {
+            Some(block) => {
+                model.block_id = Set(block.block_id);
+                model.update(txn).await?
+            }
+            None => model.insert(txn).await?,
+        };
+
+        Ok(block)
+    }
+
+    pub async fn unblock_user(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        blocked_user_id: i64,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let block = UserBlock::find()
+            .filter(
+                Condition::all()
+                    .add(user_block::Column::BlockerUserId.eq(user_id))
+                    .add(user_block::Column::UserId.eq(blocked_user_id))
+                    .add(active_condition()),
+            )
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        let model = user_block::ActiveModel {
+            block_id: Set(block.block_id),
+            revoked_at: Set(Some(now())),
+            revoked_by: Set(Some(user_id)),
+            ..Default::default()
+        };
+
+        model.update(txn).await?;
+        Ok(())
+    }
+
+    /// Gets all the users currently blocked by this user.
+    pub async fn get_user_blocks(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+    ) -> Result<Vec<UserBlockModel>> {
+        let txn = ctx.transaction();
+        let blocks = UserBlock::find()
+            .filter(
+                Condition::all()
+                    .add(user_block::Column::BlockerUserId.eq(user_id))
+                    .add(active_condition()),
+            )
+            .order_by_desc(user_block::Column::DateBlocked)
+            .all(txn)
+            .await?;
+
+        Ok(blocks)
+    }
+
+    /// Checks that neither user is blocking the other.
+    ///
+    /// If either is, this method fails with `Error::UserBlocked`.
+    pub async fn check_user_blocked(
+        ctx: &ServiceContext<'_>,
+        user_id_1: i64,
+        user_id_2: i64,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let count = UserBlock::find()
+            .filter(
+                Condition::all()
+                    .add(
+                        Condition::any()
+                            .add(
+                                Condition::all()
+                                    .add(user_block::Column::BlockerUserId.eq(user_id_1))
+                                    .add(user_block::Column::UserId.eq(user_id_2)),
+                            )
+                            .add(
+                                Condition::all()
+                                    .add(user_block::Column::BlockerUserId.eq(user_id_2))
+                                    .add(user_block::Column::UserId.eq(user_id_1)),
+                            ),
+                    )
+                    .add(active_condition()),
+            )
+            .count(txn)
+            .await?;
+
+        if count > 0 || Self::has_interaction_block(ctx, user_id_1, user_id_2).await? {
+            tide::log::error!("User ID {user_id_1} or {user_id_2} is blocking the other",);
+            return Err(Error::UserBlocked);
+        }
+
+        Ok(())
+    }
+
+    /// Checks for a block between users made through the web application.
+    ///
+    /// These are still stored in the `interactions` table, rather than in `user_block`.
+    async fn has_interaction_block(
+        ctx: &ServiceContext<'_>,
+        user_id_1: i64,
+        user_id_2: i64,
+    ) -> Result<bool> {
+        #[derive(FromQueryResult, Debug)]
+        struct Row {
+            blocked: bool,
+        }
+
+        let txn = ctx.transaction();
+        let row = Row::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r"
+            SELECT EXISTS (
+                SELECT 1
+                FROM interactions
+                WHERE interaction_type = $1
+                    AND setter_type = $2
+                    AND target_type = $2
+                    AND (
+                        (setter_id = $3 AND target_id = $4)
+                        OR (setter_id = $4 AND target_id = $3)
+                    )
+            ) AS blocked
+            ",
+            vec![
+                USER_BLOCKS_USER_INTERACTION.into(),
+                USER_INTERACTION_TYPE.into(),
+                user_id_1.into(),
+                user_id_2.into(),
+            ],
+        ))
+        .one(txn)
+        .await?;
+
+        Ok(row.map(|row| row.blocked).unwrap_or(false))
+    }
+}
+
+/// Whether a user with the first role may ban a user with the second.
+///
+/// Managers cannot be banned, and moderators can only be banned by managers.
+fn outranks(banner: &SiteRoleModel, target: &SiteRoleModel) -> bool {
+    if target.can_manage {
+        return false;
+    }
+
+    banner.can_manage || !target.can_moderate
+}
+
+/// Condition for bans and blocks which are currently in effect.
+///
+/// That is, those which have not been revoked or expired.
+fn active_condition() -> Condition {
+    Condition::all()
+        .add(user_block::Column::RevokedAt.is_null())
+        .add(
+            Condition::any()
+                .add(user_block::Column::ExpiresAt.is_null())
+                .add(user_block::Column::ExpiresAt.gt(now())),
+        )
+}
+
+#[test]
+fn ban_ranks() {
+    use crate::utils::now;
+
+    let role = |can_moderate, can_manage| SiteRoleModel {
+        role_id: 1,
+        site_id: 1,
+        slug: str!("role"),
+        name: str!("Role"),
+        builtin: false,
+        can_create: true,
+        can_edit: true,
+        can_move: true,
+        can_delete: true,
+        can_vote: true,
+        can_upload: true,
+        can_post: true,
+        can_moderate,
+        can_manage,
+        created_at: now(),
+        updated_at: None,
+    };
+
+    let member = role(false, false);
+    let moderator = role(true, false);
+    let admin = role(true, true);
+
+    assert!(outranks(&moderator, &member));
+    assert!(outranks(&admin, &member));
+    assert!(outranks(&admin, &moderator));
+    assert!(!outranks(&moderator, &moderator));
+    assert!(!outranks(&moderator, &admin));
+    assert!(!outranks(&admin, &admin));
+}
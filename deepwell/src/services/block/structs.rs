@@ -0,0 +1,50 @@
+/*
+ * services/block/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use sea_orm::entity::prelude::DateTimeWithTimeZone;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSiteBan {
+    pub user_id: i64,
+    pub reason: Option<String>,
+    pub expires_at: Option<DateTimeWithTimeZone>,
+
+    /// The staff member issuing the ban.
+    pub created_by: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveSiteBan {
+    pub user_id: i64,
+
+    /// The staff member lifting the ban.
+    pub revoked_by: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUserBlock {
+    pub user_id: i64,
+    pub blocked_user_id: i64,
+    pub reason: Option<String>,
+    pub expires_at: Option<DateTimeWithTimeZone>,
+}
@@ -22,6 +22,10 @@ use super::prelude::*;
 use crate::models::page_category::{
     self, Entity as PageCategory, Model as PageCategoryModel,
 };
+use crate::services::audit::{AuditAction, AuditTarget, CreateAuditEntry};
+use crate::services::permission::{Action, PermissionService};
+use crate::services::score::ScoreSettings;
+use crate::services::{AuditService, ScoreService, SiteService};
 
 #[derive(Debug)]
 pub struct CategoryService;
@@ -48,6 +52,68 @@ impl CategoryService {
         Ok(category)
     }
 
+    /// Updates a category's scoring and voting overrides.
+    ///
+    /// Along with the site's settings for any values which aren't
+    /// overridden, these must be compatible with each other.
+    pub async fn update(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        reference: Reference<'_>,
+        UpdateCategory {
+            score_type,
+            vote_type,
+            user_id,
+        }: UpdateCategory,
+    ) -> Result<PageCategoryModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Manage).await?;
+        let category = Self::get(ctx, site_id, reference).await?;
+        let before = serde_json::to_value(&category)?;
+
+        let mut model = page_category::ActiveModel {
+            category_id: Set(category.category_id),
+            updated_at: Set(Some(now())),
+            ..Default::default()
+        };
+
+        let mut new_score_type = category.score_type;
+        let mut new_vote_type = category.vote_type;
+
+        if let ProvidedValue::Set(value) = score_type {
+            model.score_type = Set(value);
+            new_score_type = value;
+        }
+
+        if let ProvidedValue::Set(value) = vote_type {
+            model.vote_type = Set(value);
+            new_vote_type = value;
+        }
+
+        let site = SiteService::get(ctx, Reference::from(site_id)).await?;
+        ScoreService::check_settings(ScoreSettings {
+            score_type: new_score_type.unwrap_or(site.score_type),
+            vote_type: new_vote_type.unwrap_or(site.vote_type),
+        })?;
+
+        let category = model.update(txn).await?;
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(site_id),
+                user_id: Some(user_id),
+                action: AuditAction::CategoryUpdate,
+                target: AuditTarget::Category(category.category_id),
+                before: Some(before),
+                after: Some(serde_json::to_value(&category)?),
+            },
+        )
+        .await?;
+
+        Ok(category)
+    }
+
     pub async fn get_optional(
         ctx: &ServiceContext<'_>,
         site_id: i64,
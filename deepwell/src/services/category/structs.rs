@@ -18,9 +18,27 @@
  * along with this program. If not, see <http://www.gnu.org/licenses/>.
  */
 
+use super::prelude::*;
 use crate::models::page_category::Model as PageCategoryModel;
+use crate::models::sea_orm_active_enums::{ScoreType, VoteType};
 use sea_orm::entity::prelude::DateTimeWithTimeZone;
 
+/// Changes to a category's settings.
+///
+/// Setting a value to `null` removes the override,
+/// so the site's setting is used instead.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCategory {
+    #[serde(default)]
+    pub score_type: ProvidedValue<Option<ScoreType>>,
+
+    #[serde(default)]
+    pub vote_type: ProvidedValue<Option<VoteType>>,
+
+    pub user_id: i64,
+}
+
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CategoryOutput {
@@ -29,6 +47,8 @@ pub struct CategoryOutput {
     updated_at: Option<DateTimeWithTimeZone>,
     site_id: i64,
     slug: String,
+    score_type: Option<ScoreType>,
+    vote_type: Option<VoteType>,
 }
 
 impl From<PageCategoryModel> for CategoryOutput {
@@ -40,6 +60,8 @@ impl From<PageCategoryModel> for CategoryOutput {
             updated_at,
             site_id,
             slug,
+            score_type,
+            vote_type,
         } = model;
 
         CategoryOutput {
@@ -48,6 +70,8 @@ impl From<PageCategoryModel> for CategoryOutput {
             updated_at,
             site_id,
             slug,
+            score_type,
+            vote_type,
         }
     }
 }
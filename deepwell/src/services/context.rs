@@ -20,21 +20,58 @@
 
 use super::error::Result;
 use crate::api::{ApiRequest, ApiServerState};
+use crate::cache::RenderCache;
+use crate::config::Config;
 use cuid::cuid;
 use s3::bucket::Bucket;
 use sea_orm::DatabaseTransaction;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
+/// Information about the HTTP request being served, if any.
+///
+/// This is recorded in the audit log alongside the action taken.
+#[derive(Debug, Clone, Default)]
+pub struct RequestMetadata {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl RequestMetadata {
+    fn from_request(req: &ApiRequest) -> Self {
+        // Strip the port, if present
+        let ip_address = req
+            .remote()
+            .map(|remote| match remote.parse::<SocketAddr>() {
+                Ok(addr) => addr.ip().to_string(),
+                Err(_) => str!(remote),
+            });
+
+        let user_agent = req
+            .header("User-Agent")
+            .map(|values| str!(values.last().as_str()));
+
+        RequestMetadata {
+            ip_address,
+            user_agent,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ServiceContext<'txn> {
     state: ApiServerState,
     transaction: &'txn DatabaseTransaction,
+    metadata: RequestMetadata,
 }
 
 impl<'txn> ServiceContext<'txn> {
     #[inline]
     pub fn new(req: &ApiRequest, transaction: &'txn DatabaseTransaction) -> Self {
-        Self::from_raw(req.state(), transaction)
+        ServiceContext {
+            metadata: RequestMetadata::from_request(req),
+            ..Self::from_raw(req.state(), transaction)
+        }
     }
 
     pub fn from_raw(
@@ -44,15 +81,31 @@ impl<'txn> ServiceContext<'txn> {
         ServiceContext {
             state: Arc::clone(state),
             transaction,
+            metadata: RequestMetadata::default(),
         }
     }
 
     // Getters
+    #[inline]
+    pub fn config(&self) -> &Config {
+        &self.state.config
+    }
+
     #[inline]
     pub fn s3_bucket(&self) -> &Bucket {
         &self.state.s3_bucket
     }
 
+    #[inline]
+    pub fn render_cache(&self) -> &RenderCache {
+        &self.state.render_cache
+    }
+
+    #[inline]
+    pub fn metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+
     #[inline]
     pub fn transaction(&self) -> &'txn DatabaseTransaction {
         self.transaction
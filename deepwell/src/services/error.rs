@@ -19,6 +19,8 @@
  */
 
 use crate::locales::LocalizationTranslateError;
+use argon2::password_hash::Error as PasswordHashError;
+use bcrypt::BcryptError;
 use cuid::CuidError;
 use filemagic::FileMagicError;
 use s3::error::S3Error;
@@ -46,6 +48,15 @@ pub enum Error {
     #[error("Localization error: {0}")]
     Localization(#[from] LocalizationTranslateError),
 
+    #[error("Password hashing error: {0}")]
+    PasswordHash(#[from] PasswordHashError),
+
+    #[error("Legacy password hashing error: {0}")]
+    Bcrypt(#[from] BcryptError),
+
+    #[error("Mail sending error: {0}")]
+    Mail(anyhow::Error),
+
     #[error("Magic library error: {0}")]
     Magic(#[from] FileMagicError),
 
@@ -78,6 +89,48 @@ pub enum Error {
 
     #[error("Cannot hide the wikitext for the latest page revision")]
     CannotHideLatestRevision,
+
+    #[error("The vote is not permitted by the page's voting settings")]
+    InvalidVote,
+
+    #[error("The tag is not in the site's list of allowed tags")]
+    TagNotAllowed,
+
+    #[error("The file exceeds the site's maximum upload size")]
+    FileTooLarge,
+
+    #[error("The site does not have enough file storage remaining")]
+    SiteStorageFull,
+
+    #[error("The forum thread is locked and cannot be posted to")]
+    ThreadLocked,
+
+    #[error("One of the users involved is blocking the other")]
+    UserBlocked,
+
+    #[error("The user is banned from this site")]
+    UserBanned,
+
+    #[error("The username, email, or password is incorrect")]
+    InvalidAuthentication,
+
+    #[error("The token is invalid, expired, or has already been used")]
+    InvalidToken,
+
+    #[error("The site does not accept members by this method")]
+    MembershipClosed,
+
+    #[error("The site's membership password is incorrect")]
+    IncorrectMembershipPassword,
+
+    #[error("The user does not have permission to {0} here")]
+    PermissionDenied(&'static str),
+
+    #[error("The user has no username changes remaining")]
+    InsufficientNameChanges,
+
+    #[error("Fetched included pages do not match those requested")]
+    IncludeMismatch,
 }
 
 impl Error {
@@ -88,6 +141,13 @@ impl Error {
                 TideError::new(StatusCode::InternalServerError, inner)
             }
             Error::Magic(inner) => TideError::new(StatusCode::InternalServerError, inner),
+            Error::Mail(inner) => TideError::new(StatusCode::InternalServerError, inner),
+            Error::PasswordHash(inner) => {
+                TideError::new(StatusCode::InternalServerError, inner)
+            }
+            Error::Bcrypt(inner) => {
+                TideError::new(StatusCode::InternalServerError, inner)
+            }
             Error::Localization(inner) => TideError::new(StatusCode::NotFound, inner),
             Error::Serde(inner) => TideError::new(StatusCode::InternalServerError, inner),
             Error::S3(inner) => TideError::new(StatusCode::InternalServerError, inner),
@@ -95,7 +155,7 @@ impl Error {
             Error::InvalidEnumValue => {
                 TideError::from_str(StatusCode::InternalServerError, "")
             }
-            Error::RemoteOperationFailed => {
+            Error::RemoteOperationFailed | Error::IncludeMismatch => {
                 TideError::from_str(StatusCode::InternalServerError, "")
             }
             Error::BadRequest => TideError::from_str(StatusCode::BadRequest, ""),
@@ -103,9 +163,30 @@ impl Error {
                 TideError::from_str(StatusCode::Conflict, "")
             }
             Error::NotFound => TideError::from_str(StatusCode::NotFound, ""),
-            Error::CannotHideLatestRevision => {
-                TideError::from_str(StatusCode::BadRequest, "")
+            Error::InvalidAuthentication => {
+                TideError::from_str(StatusCode::Unauthorized, "")
+            }
+            Error::InsufficientNameChanges => {
+                TideError::from_str(StatusCode::TooManyRequests, "")
+            }
+            Error::FileTooLarge => TideError::from_str(StatusCode::PayloadTooLarge, ""),
+            Error::SiteStorageFull => {
+                TideError::from_str(StatusCode::InsufficientStorage, "")
+            }
+            Error::ThreadLocked
+            | Error::UserBlocked
+            | Error::UserBanned
+            | Error::MembershipClosed
+            | Error::IncorrectMembershipPassword => {
+                TideError::from_str(StatusCode::Forbidden, "")
+            }
+            error @ Error::PermissionDenied(_) => {
+                TideError::from_str(StatusCode::Forbidden, error.to_string())
             }
+            Error::CannotHideLatestRevision
+            | Error::InvalidVote
+            | Error::TagNotAllowed
+            | Error::InvalidToken => TideError::from_str(StatusCode::BadRequest, ""),
         }
     }
 }
@@ -20,12 +20,20 @@
 
 use super::prelude::*;
 use crate::models::file::{self, Entity as File, Model as FileModel};
+use crate::models::file_revision::{
+    self, Entity as FileRevision, Model as FileRevisionModel,
+};
+use crate::services::audit::{AuditAction, AuditTarget, CreateAuditEntry};
 use crate::services::blob::CreateBlobOutput;
 use crate::services::file_revision::{
     CreateFileRevision, CreateFileRevisionBody, CreateFirstFileRevision,
     CreateResurrectionFileRevision, CreateTombstoneFileRevision, FileBlob,
 };
-use crate::services::{BlobService, FileRevisionService};
+use crate::services::permission::{Action, PermissionService};
+use crate::services::{
+    AuditService, BlobService, FileRevisionService, PageService, SiteSettingsService,
+};
+use std::collections::hash_map::{Entry, HashMap};
 
 #[derive(Debug)]
 pub struct FileService;
@@ -56,7 +64,9 @@ impl FileService {
             data.len(),
         );
 
+        Self::check_permission(ctx, site_id, page_id, user_id).await?;
         Self::check_conflicts(ctx, page_id, &name, "create").await?;
+        SiteSettingsService::check_upload(ctx, site_id, data.len() as i64, 0).await?;
 
         // Upload to S3, get derived metadata
         let CreateBlobOutput {
@@ -112,6 +122,8 @@ impl FileService {
         }: UpdateFile,
     ) -> Result<Option<UpdateFileOutput>> {
         let txn = ctx.transaction();
+        Self::check_permission(ctx, site_id, page_id, user_id).await?;
+
         let last_revision =
             FileRevisionService::get_latest(ctx, page_id, &file_id).await?;
 
@@ -137,6 +149,15 @@ impl FileService {
         let blob = match data {
             ProvidedValue::Unset => ProvidedValue::Unset,
             ProvidedValue::Set(bytes) => {
+                // The new data replaces the file's current data
+                SiteSettingsService::check_upload(
+                    ctx,
+                    site_id,
+                    bytes.len() as i64,
+                    last_revision.size_hint,
+                )
+                .await?;
+
                 let CreateBlobOutput {
                     hash,
                     mime,
@@ -203,6 +224,10 @@ impl FileService {
             destination_page_id,
         } = input;
 
+        // Uploading is needed on both the source and the destination
+        Self::check_permission(ctx, site_id, current_page_id, user_id).await?;
+        Self::check_permission(ctx, site_id, destination_page_id, user_id).await?;
+
         let last_revision =
             FileRevisionService::get_latest(ctx, current_page_id, &file_id).await?;
 
@@ -270,6 +295,8 @@ impl FileService {
             user_id,
         } = input;
 
+        Self::check_permission(ctx, site_id, page_id, user_id).await?;
+
         // Ensure file exists
         let FileModel { file_id, .. } = Self::get(ctx, page_id, reference).await?;
 
@@ -345,6 +372,11 @@ impl FileService {
             return Err(Error::BadRequest);
         }
 
+        Self::check_permission(ctx, site_id, page_id, user_id).await?;
+        if new_page_id != page_id {
+            Self::check_permission(ctx, site_id, new_page_id, user_id).await?;
+        }
+
         Self::check_conflicts(ctx, page_id, &new_name, "restore").await?;
 
         let last_revision =
@@ -459,6 +491,32 @@ impl FileService {
             .map(|file| file.is_some())
     }
 
+    /// Gets extant files on a page, in order of their IDs.
+    ///
+    /// The `start_id` argument gives the ID to start after, exclusive.
+    /// If empty, then it starts from the first file.
+    pub async fn get_range(
+        ctx: &ServiceContext<'_>,
+        page_id: i64,
+        start_id: &str,
+        limit: u64,
+    ) -> Result<Vec<FileModel>> {
+        let txn = ctx.transaction();
+        let files = File::find()
+            .filter(
+                Condition::all()
+                    .add(file::Column::PageId.eq(page_id))
+                    .add(file::Column::FileId.gt(start_id))
+                    .add(file::Column::DeletedAt.is_null()),
+            )
+            .order_by_asc(file::Column::FileId)
+            .limit(limit)
+            .all(txn)
+            .await?;
+
+        Ok(files)
+    }
+
     /// Hard deletes this file and all duplicates.
     ///
     /// This is a very powerful method and needs to be used carefully.
@@ -470,16 +528,139 @@ impl FileService {
     ///
     /// This method should only be used very rarely to clear content such
     /// as severe copyright violations, abuse content, or comply with court orders.
+    ///
+    /// The user must be able to manage every site which has one of these files.
+    /// Each deleted file is recorded in the audit log.
+    ///
+    /// # Returns
+    /// The hashes of blobs which are no longer used by any file. These are not
+    /// removed here, since S3 is not part of the transaction. Once it has been
+    /// committed, the caller should remove them using `BlobService::hard_delete()`.
     #[allow(dead_code)] // TEMP
     pub async fn hard_delete_all(
-        _ctx: &ServiceContext<'_>,
-        _file_id: &str,
+        ctx: &ServiceContext<'_>,
+        file_id: &str,
+        user_id: i64,
+    ) -> Result<Vec<Vec<u8>>> {
+        let txn = ctx.transaction();
+
+        // Find all the content this file has ever had
+        let hashes = get_hashes(
+            FileRevision::find()
+                .filter(file_revision::Column::FileId.eq(file_id))
+                .all(txn)
+                .await?,
+        );
+
+        if hashes.is_empty() {
+            tide::log::error!("No revisions found for file ID {file_id}");
+            return Err(Error::NotFound);
+        }
+
+        // Find every file which has had any of that content
+        let mut file_ids = FileRevision::find()
+            .filter(file_revision::Column::S3Hash.is_in(hashes))
+            .all(txn)
+            .await?
+            .into_iter()
+            .map(|revision| revision.file_id)
+            .collect::<Vec<_>>();
+
+        file_ids.sort();
+        file_ids.dedup();
+
+        let files = File::find()
+            .filter(file::Column::FileId.is_in(file_ids.iter().cloned()))
+            .all(txn)
+            .await?;
+
+        // Check permissions for every affected site before deleting anything
+        let mut pages = HashMap::new();
+        for file in &files {
+            if let Entry::Vacant(entry) = pages.entry(file.page_id) {
+                entry.insert(PageService::get_direct(ctx, file.page_id).await?);
+            }
+        }
+
+        let mut site_ids = pages.values().map(|page| page.site_id).collect::<Vec<_>>();
+        site_ids.sort_unstable();
+        site_ids.dedup();
+
+        for site_id in site_ids {
+            PermissionService::check_site(ctx, site_id, user_id, Action::Manage).await?;
+        }
+
+        // Delete the files and all their revisions
+        let revisions = FileRevision::find()
+            .filter(file_revision::Column::FileId.is_in(file_ids.iter().cloned()))
+            .all(txn)
+            .await?;
+
+        FileRevision::delete_many()
+            .filter(file_revision::Column::FileId.is_in(file_ids.iter().cloned()))
+            .exec(txn)
+            .await?;
+
+        for file in files {
+            tide::log::warn!("Hard deleting file ID {}", file.file_id);
+
+            let site_id = pages[&file.page_id].site_id;
+            let before = serde_json::to_value(&file)?;
+            let file_id = file.file_id.clone();
+            file.delete(txn).await?;
+
+            AuditService::record(
+                ctx,
+                CreateAuditEntry {
+                    site_id: Some(site_id),
+                    user_id: Some(user_id),
+                    action: AuditAction::FileHardDelete,
+                    target: AuditTarget::File(file_id),
+                    before: Some(before),
+                    after: None,
+                },
+            )
+            .await?;
+        }
+
+        // Find blobs which are no longer used by any other file
+        let mut unused_hashes = Vec::new();
+        for hash in get_hashes(revisions) {
+            let still_used = FileRevision::find()
+                .filter(file_revision::Column::S3Hash.eq(hash.clone()))
+                .one(txn)
+                .await?
+                .is_some();
+
+            if !still_used {
+                unused_hashes.push(hash);
+            }
+        }
+
+        Ok(unused_hashes)
+    }
+
+    /// Checks that the user may upload files to the given page.
+    async fn check_permission(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        page_id: i64,
+        user_id: i64,
     ) -> Result<()> {
-        // TODO find hash. update all files with the same hash
-        // TODO add to audit log
-        // TODO hard delete BlobService
+        let page = PageService::get_direct(ctx, page_id).await?;
+        if page.site_id != site_id {
+            tide::log::warn!("Page ID {page_id} is not in site ID {site_id}");
+            return Err(Error::NotFound);
+        }
 
-        todo!()
+        PermissionService::check_category(
+            ctx,
+            site_id,
+            page.page_category_id,
+            user_id,
+            Action::Upload,
+        )
+        .await
     }
 
     /// Checks to see if a file already exists at the name specified.
@@ -519,3 +700,15 @@ impl FileService {
         }
     }
 }
+
+/// Gets the distinct blob hashes used by the given file revisions.
+fn get_hashes(revisions: Vec<FileRevisionModel>) -> Vec<Vec<u8>> {
+    let mut hashes = revisions
+        .into_iter()
+        .map(|revision| revision.s3_hash)
+        .collect::<Vec<_>>();
+
+    hashes.sort();
+    hashes.dedup();
+    hashes
+}
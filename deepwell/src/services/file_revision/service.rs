@@ -23,7 +23,9 @@ use crate::json_utils::string_list_to_json;
 use crate::models::file_revision::{
     self, Entity as FileRevision, Model as FileRevisionModel,
 };
-use crate::services::{OutdateService, PageService};
+use crate::services::audit::{AuditAction, AuditTarget, CreateAuditEntry};
+use crate::services::permission::{Action, PermissionService};
+use crate::services::{AuditService, OutdateService, PageService};
 use crate::web::FetchDirection;
 use serde_json::json;
 use std::num::NonZeroI32;
@@ -368,6 +370,9 @@ impl FileRevisionService {
         UpdateFileRevision { user_id, hidden }: UpdateFileRevision,
     ) -> Result<()> {
         let txn = ctx.transaction();
+        let page = PageService::get_direct(ctx, page_id).await?;
+        PermissionService::check_site(ctx, page.site_id, user_id, Action::Moderate)
+            .await?;
 
         // The latest file revision cannot be hidden, because
         // the file, its name, contents, etc are exposed.
@@ -378,20 +383,43 @@ impl FileRevisionService {
             return Err(Error::CannotHideLatestRevision);
         }
 
-        // TODO: record revision edit in audit log
-        let _ = user_id;
+        let revision = FileRevision::find()
+            .filter(
+                Condition::all()
+                    .add(file_revision::Column::RevisionId.eq(revision_id))
+                    .add(file_revision::Column::PageId.eq(page_id))
+                    .add(file_revision::Column::FileId.eq(file_id)),
+            )
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
 
         // Update the revision
 
         let hidden = string_list_to_json(&hidden)?;
         let model = file_revision::ActiveModel {
             revision_id: Set(revision_id),
-            hidden: Set(hidden),
+            hidden: Set(hidden.clone()),
             ..Default::default()
         };
 
-        // Update and return
         model.update(txn).await?;
+
+        // Record the change in the audit log
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(page.site_id),
+                user_id: Some(user_id),
+                action: AuditAction::FileRevisionUpdate,
+                target: AuditTarget::FileRevision(revision_id),
+                before: Some(json!({ "hidden": revision.hidden })),
+                after: Some(json!({ "hidden": hidden })),
+            },
+        )
+        .await?;
+
         Ok(())
     }
 
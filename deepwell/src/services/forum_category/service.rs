@@ -0,0 +1,323 @@
+/*
+ * services/forum_category/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::forum_category::{
+    self, Entity as ForumCategory, Model as ForumCategoryModel,
+};
+use crate::models::forum_group::{self, Entity as ForumGroup, Model as ForumGroupModel};
+use crate::models::forum_post::{self, Entity as ForumPost};
+use crate::models::forum_thread::{self, Entity as ForumThread};
+use crate::services::permission::{Action, PermissionService};
+use sea_orm::sea_query::Expr;
+
+#[derive(Debug)]
+pub struct ForumCategoryService;
+
+impl ForumCategoryService {
+    pub async fn create_group(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        CreateForumGroup {
+            name,
+            description,
+            sort_index,
+            user_id,
+        }: CreateForumGroup,
+    ) -> Result<ForumGroupModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Manage).await?;
+
+        let model = forum_group::ActiveModel {
+            name: Set(Some(name)),
+            description: Set(description),
+            sort_index: Set(sort_index),
+            site_id: Set(Some(site_id)),
+            visible: Set(true),
+            ..Default::default()
+        };
+
+        let group = model.insert(txn).await?;
+        Ok(group)
+    }
+
+    pub async fn get_group(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        group_id: i64,
+    ) -> Result<ForumGroupModel> {
+        let txn = ctx.transaction();
+        let group = ForumGroup::find()
+            .filter(
+                Condition::all()
+                    .add(forum_group::Column::GroupId.eq(group_id))
+                    .add(forum_group::Column::SiteId.eq(site_id)),
+            )
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        Ok(group)
+    }
+
+    pub async fn create(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        CreateForumCategory {
+            group_id,
+            name,
+            description,
+            max_nest_level,
+            sort_index,
+            user_id,
+        }: CreateForumCategory,
+    ) -> Result<ForumCategoryModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Manage).await?;
+
+        // Ensure the group is in this site
+        Self::get_group(ctx, site_id, group_id).await?;
+
+        let model = forum_category::ActiveModel {
+            group_id: Set(Some(group_id)),
+            name: Set(Some(name)),
+            description: Set(description),
+            max_nest_level: Set(max_nest_level),
+            sort_index: Set(sort_index),
+            site_id: Set(Some(site_id)),
+            ..Default::default()
+        };
+
+        let category = model.insert(txn).await?;
+        Ok(category)
+    }
+
+    pub async fn update(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        category_id: i64,
+        UpdateForumCategory {
+            name,
+            description,
+            max_nest_level,
+            sort_index,
+            user_id,
+        }: UpdateForumCategory,
+    ) -> Result<ForumCategoryModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Manage).await?;
+
+        // Ensure the category is in this site
+        Self::get(ctx, site_id, category_id).await?;
+
+        let mut model = forum_category::ActiveModel {
+            category_id: Set(category_id),
+            ..Default::default()
+        };
+
+        if let ProvidedValue::Set(name) = name {
+            model.name = Set(Some(name));
+        }
+
+        if let ProvidedValue::Set(description) = description {
+            model.description = Set(description);
+        }
+
+        if let ProvidedValue::Set(max_nest_level) = max_nest_level {
+            model.max_nest_level = Set(max_nest_level);
+        }
+
+        if let ProvidedValue::Set(sort_index) = sort_index {
+            model.sort_index = Set(sort_index);
+        }
+
+        let category = model.update(txn).await?;
+        Ok(category)
+    }
+
+    pub async fn get(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        category_id: i64,
+    ) -> Result<ForumCategoryModel> {
+        let txn = ctx.transaction();
+        let category = ForumCategory::find()
+            .filter(
+                Condition::all()
+                    .add(forum_category::Column::CategoryId.eq(category_id))
+                    .add(forum_category::Column::SiteId.eq(site_id)),
+            )
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        Ok(category)
+    }
+
+    /// Gets the category holding a site's per-page discussion threads.
+    ///
+    /// If the site does not have one yet, it is created in a hidden group.
+    pub async fn get_or_create_discussion(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+    ) -> Result<ForumCategoryModel> {
+        let txn = ctx.transaction();
+        let category = ForumCategory::find()
+            .filter(
+                Condition::all()
+                    .add(forum_category::Column::SiteId.eq(site_id))
+                    .add(forum_category::Column::PerPageDiscussion.eq(true)),
+            )
+            .order_by_asc(forum_category::Column::CategoryId)
+            .one(txn)
+            .await?;
+
+        if let Some(category) = category {
+            return Ok(category);
+        }
+
+        tide::log::info!("Creating per-page discussion category for site ID {site_id}");
+
+        let group = forum_group::ActiveModel {
+            name: Set(Some(str!("Hidden"))),
+            description: Set(Some(str!(
+                "Hidden group used for storing some discussion threads."
+            ))),
+            sort_index: Set(0),
+            site_id: Set(Some(site_id)),
+            visible: Set(false),
+            ..Default::default()
+        }
+        .insert(txn)
+        .await?;
+
+        let category = forum_category::ActiveModel {
+            group_id: Set(Some(group.group_id)),
+            name: Set(Some(str!("Per page discussions"))),
+            description: Set(Some(str!(
+                "Discussions related to particular pages within this site."
+            ))),
+            sort_index: Set(0),
+            site_id: Set(Some(site_id)),
+            per_page_discussion: Set(true),
+            ..Default::default()
+        }
+        .insert(txn)
+        .await?;
+
+        Ok(category)
+    }
+
+    /// Gets all the forum groups in a site, each with their categories.
+    pub async fn get_all(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+    ) -> Result<Vec<ForumGroupOutput>> {
+        let txn = ctx.transaction();
+        let groups = ForumGroup::find()
+            .filter(forum_group::Column::SiteId.eq(site_id))
+            .order_by_asc(forum_group::Column::SortIndex)
+            .order_by_asc(forum_group::Column::GroupId)
+            .find_with_related(ForumCategory)
+            .order_by_asc(forum_category::Column::SortIndex)
+            .order_by_asc(forum_category::Column::CategoryId)
+            .all(txn)
+            .await?
+            .into_iter()
+            .map(|(group, categories)| ForumGroupOutput { group, categories })
+            .collect();
+
+        Ok(groups)
+    }
+
+    /// Updates the running totals for a category.
+    ///
+    /// If `last_post_id` is set, then it replaces the category's latest post.
+    pub async fn add_counts(
+        ctx: &ServiceContext<'_>,
+        category_id: i64,
+        threads: i32,
+        posts: i32,
+        last_post_id: Option<i64>,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+
+        // Updated in place, so concurrent posts don't overwrite each other's totals
+        let mut update = ForumCategory::update_many()
+            .col_expr(
+                forum_category::Column::NumberThreads,
+                Expr::col(forum_category::Column::NumberThreads).add(threads),
+            )
+            .col_expr(
+                forum_category::Column::NumberPosts,
+                Expr::col(forum_category::Column::NumberPosts).add(posts),
+            );
+
+        if let Some(post_id) = last_post_id {
+            update =
+                update.col_expr(forum_category::Column::LastPostId, Expr::value(post_id));
+        }
+
+        let result = update
+            .filter(forum_category::Column::CategoryId.eq(category_id))
+            .exec(txn)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(Error::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Recalculates a category's latest post, such as after one is deleted.
+    pub async fn refresh_last_post(
+        ctx: &ServiceContext<'_>,
+        category_id: i64,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let last_post = ForumPost::find()
+            .join(
+                JoinType::InnerJoin,
+                ForumPost::belongs_to(ForumThread)
+                    .from(forum_post::Column::ThreadId)
+                    .to(forum_thread::Column::ThreadId)
+                    .into(),
+            )
+            .filter(
+                Condition::all()
+                    .add(forum_thread::Column::CategoryId.eq(category_id))
+                    .add(forum_thread::Column::DeletedAt.is_null())
+                    .add(forum_post::Column::DeletedAt.is_null()),
+            )
+            .order_by_desc(forum_post::Column::PostId)
+            .one(txn)
+            .await?;
+
+        let model = forum_category::ActiveModel {
+            category_id: Set(category_id),
+            last_post_id: Set(last_post.map(|post| post.post_id)),
+            ..Default::default()
+        };
+
+        model.update(txn).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+/*
+ * services/forum_category/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::forum_category::Model as ForumCategoryModel;
+use crate::models::forum_group::Model as ForumGroupModel;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateForumGroup {
+    pub name: String,
+    pub description: Option<String>,
+
+    #[serde(default)]
+    pub sort_index: i32,
+
+    pub user_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateForumCategory {
+    pub group_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+
+    /// How deeply replies may be nested, or `None` for no limit.
+    #[serde(default)]
+    pub max_nest_level: Option<i32>,
+
+    #[serde(default)]
+    pub sort_index: i32,
+
+    pub user_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateForumCategory {
+    #[serde(default)]
+    pub name: ProvidedValue<String>,
+
+    #[serde(default)]
+    pub description: ProvidedValue<Option<String>>,
+
+    #[serde(default)]
+    pub max_nest_level: ProvidedValue<Option<i32>>,
+
+    #[serde(default)]
+    pub sort_index: ProvidedValue<i32>,
+
+    pub user_id: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ForumGroupOutput {
+    #[serde(flatten)]
+    pub group: ForumGroupModel,
+    pub categories: Vec<ForumCategoryModel>,
+}
@@ -0,0 +1,563 @@
+/*
+ * services/forum_post/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::forum_post::{self, Entity as ForumPost, Model as ForumPostModel};
+use crate::models::forum_post_revision::{
+    self, Entity as ForumPostRevision, Model as ForumPostRevisionModel,
+};
+use crate::models::forum_thread::Model as ForumThreadModel;
+use crate::services::permission::{Action, PermissionService};
+use crate::services::render::RenderOutput;
+use crate::services::{
+    ForumCategoryService, ForumThreadService, PageService, RenderService,
+    RevisionService, SiteService, TextService,
+};
+use ftml::data::PageInfo;
+use ftml::settings::{WikitextMode, WikitextSettings};
+
+#[derive(Debug)]
+pub struct ForumPostService;
+
+impl ForumPostService {
+    pub async fn create(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        CreateForumPost {
+            thread_id,
+            parent_id,
+            title,
+            wikitext,
+            user_id,
+        }: CreateForumPost,
+    ) -> Result<ForumPostModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Post).await?;
+        let thread = ForumThreadService::get(ctx, site_id, thread_id).await?;
+
+        if is_locked(&thread) {
+            tide::log::error!("Cannot create post in locked thread ID {thread_id}");
+            return Err(Error::ThreadLocked);
+        }
+
+        // Ensure the reply is within the thread and nesting limit
+        if let Some(parent_id) = parent_id {
+            Self::check_reply(ctx, site_id, &thread, parent_id).await?;
+        }
+
+        let RenderOutput {
+            compiled_hash,
+            compiled_generator,
+            ..
+        } = Self::render(ctx, site_id, &thread, wikitext.clone()).await?;
+
+        // Insert post
+        let model = forum_post::ActiveModel {
+            thread_id: Set(Some(thread_id)),
+            parent_id: Set(parent_id),
+            user_id: Set(Some(user_id)),
+            title: Set(title.clone()),
+            text: Set(Some(wikitext.clone())),
+            date_posted: Set(Some(Utc::now())),
+            site_id: Set(Some(site_id)),
+            revision_number: Set(0),
+            compiled_hash: Set(Some(compiled_hash.to_vec())),
+            compiled_at: Set(Some(now())),
+            compiled_generator: Set(Some(compiled_generator)),
+            ..Default::default()
+        };
+
+        let post = model.insert(txn).await?;
+        let post_id = post.post_id;
+
+        // Insert first revision, then point the post to it
+        let revision_id =
+            Self::create_revision(ctx, post_id, user_id, title, wikitext).await?;
+
+        let model = forum_post::ActiveModel {
+            post_id: Set(post_id),
+            revision_id: Set(Some(revision_id)),
+            ..Default::default()
+        };
+
+        let post = model.update(txn).await?;
+
+        // Update thread and category totals
+        ForumThreadService::add_counts(ctx, &thread, 1, Some(post_id)).await?;
+
+        Ok(post)
+    }
+
+    /// Posts a comment on a page, creating its discussion thread if needed.
+    pub async fn create_page_comment(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        reference: Reference<'_>,
+        CreatePageComment {
+            parent_id,
+            title,
+            wikitext,
+            user_id,
+        }: CreatePageComment,
+    ) -> Result<ForumPostModel> {
+        let page = PageService::get(ctx, site_id, reference).await?;
+        let revision = RevisionService::get_latest(ctx, site_id, page.page_id).await?;
+        let thread = ForumThreadService::get_or_create_for_page(
+            ctx,
+            &page,
+            &revision.title,
+            user_id,
+        )
+        .await?;
+
+        Self::create(
+            ctx,
+            site_id,
+            CreateForumPost {
+                thread_id: thread.thread_id,
+                parent_id,
+                title,
+                wikitext,
+                user_id,
+            },
+        )
+        .await
+    }
+
+    /// Gets the comments posted on a page.
+    ///
+    /// If the page's discussion thread has not been created yet, this is empty.
+    pub async fn get_page_comments(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        reference: Reference<'_>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<ForumPostOutput>> {
+        let page = PageService::get(ctx, site_id, reference).await?;
+        match page.discussion_thread_id {
+            Some(thread_id) => {
+                Self::get_all(ctx, site_id, thread_id, offset, limit).await
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Edits a post, keeping the previous version as a revision.
+    pub async fn edit(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        post_id: i64,
+        EditForumPost {
+            title,
+            wikitext,
+            user_id,
+        }: EditForumPost,
+    ) -> Result<ForumPostModel> {
+        let txn = ctx.transaction();
+        let post = Self::get(ctx, site_id, post_id).await?;
+        Self::check_author(ctx, site_id, &post, user_id).await?;
+        let thread_id = post.thread_id.ok_or(Error::NotFound)?;
+        let thread = ForumThreadService::get(ctx, site_id, thread_id).await?;
+
+        if is_locked(&thread) {
+            tide::log::error!("Cannot edit post in locked thread ID {thread_id}");
+            return Err(Error::ThreadLocked);
+        }
+
+        let RenderOutput {
+            compiled_hash,
+            compiled_generator,
+            ..
+        } = Self::render(ctx, site_id, &thread, wikitext.clone()).await?;
+
+        let revision_id =
+            Self::create_revision(ctx, post_id, user_id, title.clone(), wikitext.clone())
+                .await?;
+
+        let model = forum_post::ActiveModel {
+            post_id: Set(post_id),
+            title: Set(title),
+            text: Set(Some(wikitext)),
+            revision_number: Set(post.revision_number + 1),
+            revision_id: Set(Some(revision_id)),
+            date_last_edited: Set(Some(Utc::now())),
+            edited_user_id: Set(Some(user_id)),
+            compiled_hash: Set(Some(compiled_hash.to_vec())),
+            compiled_at: Set(Some(now())),
+            compiled_generator: Set(Some(compiled_generator)),
+            ..Default::default()
+        };
+
+        let post = model.update(txn).await?;
+        Ok(post)
+    }
+
+    /// Soft-deletes a post, removing it from its thread's totals.
+    pub async fn delete(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        post_id: i64,
+        DeleteForumPost { user_id }: DeleteForumPost,
+    ) -> Result<ForumPostModel> {
+        let txn = ctx.transaction();
+        let post = Self::get(ctx, site_id, post_id).await?;
+        Self::check_author(ctx, site_id, &post, user_id).await?;
+
+        let model = forum_post::ActiveModel {
+            post_id: Set(post_id),
+            deleted_at: Set(Some(now())),
+            ..Default::default()
+        };
+
+        let post_after = model.update(txn).await?;
+
+        if let Some(thread_id) = post.thread_id {
+            let thread = ForumThreadService::get(ctx, site_id, thread_id).await?;
+            ForumThreadService::add_counts(ctx, &thread, -1, None).await?;
+
+            // Don't leave the thread pointing at a deleted post
+            if thread.last_post_id == Some(post_id) {
+                ForumThreadService::refresh_last_post(ctx, &thread).await?;
+            }
+        }
+
+        Ok(post_after)
+    }
+
+    /// Ensures the user may change this post.
+    ///
+    /// Authors may change their own posts, but changing
+    /// anyone else's post requires moderator permissions.
+    async fn check_author(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        post: &ForumPostModel,
+        user_id: i64,
+    ) -> Result<()> {
+        let action = author_action(post, user_id);
+        PermissionService::check_site(ctx, site_id, user_id, action).await
+    }
+
+    pub async fn get_optional(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        post_id: i64,
+    ) -> Result<Option<ForumPostModel>> {
+        let txn = ctx.transaction();
+        let post = ForumPost::find()
+            .filter(
+                Condition::all()
+                    .add(forum_post::Column::PostId.eq(post_id))
+                    .add(forum_post::Column::SiteId.eq(site_id))
+                    .add(forum_post::Column::DeletedAt.is_null()),
+            )
+            .one(txn)
+            .await?;
+
+        Ok(post)
+    }
+
+    #[inline]
+    pub async fn get(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        post_id: i64,
+    ) -> Result<ForumPostModel> {
+        match Self::get_optional(ctx, site_id, post_id).await? {
+            Some(post) => Ok(post),
+            None => Err(Error::NotFound),
+        }
+    }
+
+    /// Gets the posts in a thread, in the order they were made.
+    pub async fn get_all(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        thread_id: i64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<ForumPostOutput>> {
+        let txn = ctx.transaction();
+
+        // Ensure the thread is in this site
+        ForumThreadService::get(ctx, site_id, thread_id).await?;
+
+        let posts = ForumPost::find()
+            .filter(
+                Condition::all()
+                    .add(forum_post::Column::ThreadId.eq(thread_id))
+                    .add(forum_post::Column::DeletedAt.is_null()),
+            )
+            .order_by_asc(forum_post::Column::PostId)
+            .offset(offset)
+            .limit(limit)
+            .all(txn)
+            .await?;
+
+        // Add compiled HTML
+        let hashes: Vec<Vec<u8>> = posts
+            .iter()
+            .filter_map(|post| post.compiled_hash.clone())
+            .collect();
+
+        let mut compiled = TextService::get_many(ctx, &hashes).await?;
+        let output = posts
+            .into_iter()
+            .map(|post| {
+                let compiled_html = post
+                    .compiled_hash
+                    .as_ref()
+                    .and_then(|hash| compiled.remove(hash));
+
+                ForumPostOutput {
+                    post,
+                    compiled_html,
+                }
+            })
+            .collect();
+
+        Ok(output)
+    }
+
+    pub async fn get_revisions(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        post_id: i64,
+    ) -> Result<Vec<ForumPostRevisionModel>> {
+        let txn = ctx.transaction();
+
+        // Ensure the post is in this site
+        Self::get(ctx, site_id, post_id).await?;
+
+        let revisions = ForumPostRevision::find()
+            .filter(forum_post_revision::Column::PostId.eq(post_id))
+            .order_by_asc(forum_post_revision::Column::RevisionId)
+            .all(txn)
+            .await?;
+
+        Ok(revisions)
+    }
+
+    async fn create_revision(
+        ctx: &ServiceContext<'_>,
+        post_id: i64,
+        user_id: i64,
+        title: Option<String>,
+        wikitext: String,
+    ) -> Result<i64> {
+        let txn = ctx.transaction();
+        let model = forum_post_revision::ActiveModel {
+            post_id: Set(Some(post_id)),
+            user_id: Set(Some(user_id)),
+            text: Set(Some(wikitext)),
+            title: Set(title),
+            date: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+
+        let revision = model.insert(txn).await?;
+        Ok(revision.revision_id)
+    }
+
+    /// Checks that a reply's parent is in the same thread, and that
+    /// the reply would not exceed the category's nesting limit.
+    async fn check_reply(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        thread: &ForumThreadModel,
+        parent_id: i64,
+    ) -> Result<()> {
+        let parent = Self::get(ctx, site_id, parent_id).await?;
+        if parent.thread_id != Some(thread.thread_id) {
+            tide::log::error!(
+                "Parent post ID {parent_id} is not in thread ID {}",
+                thread.thread_id,
+            );
+            return Err(Error::BadRequest);
+        }
+
+        let max_nest_level = match thread.category_id {
+            Some(category_id) => {
+                ForumCategoryService::get(ctx, site_id, category_id)
+                    .await?
+                    .max_nest_level
+            }
+            None => None,
+        };
+
+        if let Some(max_nest_level) = max_nest_level {
+            // Walk up the parents to find how deep the reply would be
+            let mut level = 1;
+            let mut current = parent.parent_id;
+
+            while let Some(post_id) = current {
+                level += 1;
+                if level > max_nest_level {
+                    break;
+                }
+
+                current = Self::get_parent_id(ctx, site_id, post_id).await?;
+            }
+
+            if level > max_nest_level {
+                tide::log::error!(
+                    "Reply to post ID {parent_id} exceeds maximum nesting level {max_nest_level}",
+                );
+                return Err(Error::BadRequest);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the parent of a post, even if the post has since been deleted.
+    ///
+    /// Replies to a deleted post's descendants still count towards nesting.
+    async fn get_parent_id(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        post_id: i64,
+    ) -> Result<Option<i64>> {
+        let txn = ctx.transaction();
+        let post = ForumPost::find()
+            .filter(
+                Condition::all()
+                    .add(forum_post::Column::PostId.eq(post_id))
+                    .add(forum_post::Column::SiteId.eq(site_id)),
+            )
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        Ok(post.parent_id)
+    }
+
+    async fn render(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        thread: &ForumThreadModel,
+        wikitext: String,
+    ) -> Result<RenderOutput> {
+        let site = SiteService::get(ctx, Reference::from(site_id)).await?;
+        let settings = WikitextSettings::from_mode(WikitextMode::ForumPost);
+        let page_slug = format!("t-{}", thread.thread_id);
+        let title = thread.title.as_deref().unwrap_or("");
+        let page_info = PageInfo {
+            page: cow!(&page_slug),
+            category: Some(cow!("forum")),
+            site: cow!(&site.slug),
+            title: cow!(title),
+            alt_title: None,
+            rating: 0.0,
+            tags: vec![],
+            language: cow!(&site.language),
+        };
+
+        RenderService::render(ctx, wikitext, &page_info, &settings).await
+    }
+}
+
+// Helpers
+
+/// Whether a thread is closed to new posts and edits.
+///
+/// This is either from a moderator lock, or because its page was deleted.
+#[inline]
+fn is_locked(thread: &ForumThreadModel) -> bool {
+    thread.blocked || thread.archived
+}
+
+/// The permission needed for a user to change this post.
+fn author_action(post: &ForumPostModel, user_id: i64) -> Action {
+    if post.user_id == Some(user_id) {
+        Action::Post
+    } else {
+        Action::Moderate
+    }
+}
+
+#[test]
+fn locked_threads() {
+    let mut thread = ForumThreadModel {
+        thread_id: 1,
+        user_id: Some(1),
+        user_string: None,
+        category_id: Some(1),
+        title: Some("Thread".to_string()),
+        description: None,
+        number_posts: 1,
+        date_started: None,
+        site_id: Some(1),
+        last_post_id: None,
+        page_id: None,
+        sticky: false,
+        blocked: false,
+        archived: false,
+        deleted_at: None,
+    };
+
+    assert!(!is_locked(&thread));
+
+    thread.archived = true;
+    assert!(is_locked(&thread));
+
+    // Restoring the page keeps a moderator lock
+    thread.blocked = true;
+    thread.archived = false;
+    assert!(is_locked(&thread));
+}
+
+#[test]
+fn author_actions() {
+    let post = ForumPostModel {
+        post_id: 1,
+        thread_id: Some(1),
+        parent_id: None,
+        user_id: Some(10),
+        user_string: None,
+        title: None,
+        text: Some("Hello".to_string()),
+        date_posted: None,
+        site_id: Some(1),
+        revision_number: 0,
+        revision_id: None,
+        date_last_edited: None,
+        edited_user_id: None,
+        edited_user_string: None,
+        deleted_at: None,
+        compiled_hash: None,
+        compiled_at: None,
+        compiled_generator: None,
+    };
+
+    assert_eq!(author_action(&post, 10), Action::Post);
+    assert_eq!(author_action(&post, 11), Action::Moderate);
+    assert_eq!(
+        author_action(
+            &ForumPostModel {
+                user_id: None,
+                ..post
+            },
+            10,
+        ),
+        Action::Moderate,
+    );
+}
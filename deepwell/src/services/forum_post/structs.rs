@@ -0,0 +1,67 @@
+/*
+ * services/forum_post/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::models::forum_post::Model as ForumPostModel;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateForumPost {
+    pub thread_id: i64,
+
+    /// The post this is replying to, if any.
+    pub parent_id: Option<i64>,
+
+    pub title: Option<String>,
+    pub wikitext: String,
+    pub user_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePageComment {
+    /// The comment this is replying to, if any.
+    pub parent_id: Option<i64>,
+
+    pub title: Option<String>,
+    pub wikitext: String,
+    pub user_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EditForumPost {
+    pub title: Option<String>,
+    pub wikitext: String,
+    pub user_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteForumPost {
+    pub user_id: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ForumPostOutput {
+    #[serde(flatten)]
+    pub post: ForumPostModel,
+    pub compiled_html: Option<String>,
+}
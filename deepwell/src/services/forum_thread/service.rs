@@ -0,0 +1,384 @@
+/*
+ * services/forum_thread/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::forum_post::{self, Entity as ForumPost};
+use crate::models::forum_thread::{
+    self, Entity as ForumThread, Model as ForumThreadModel,
+};
+use crate::models::page::{self, Entity as Page, Model as PageModel};
+use crate::services::forum_post::CreateForumPost;
+use crate::services::permission::{Action, PermissionService};
+use crate::services::{ForumCategoryService, ForumPostService};
+use sea_orm::sea_query::Expr;
+
+#[derive(Debug)]
+pub struct ForumThreadService;
+
+impl ForumThreadService {
+    /// Creates a new thread, along with its first post.
+    pub async fn create(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        CreateForumThread {
+            category_id,
+            title,
+            description,
+            wikitext,
+            user_id,
+        }: CreateForumThread,
+    ) -> Result<CreateForumThreadOutput> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Post).await?;
+
+        // Ensure the category is in this site
+        ForumCategoryService::get(ctx, site_id, category_id).await?;
+
+        // Insert thread
+        let model = forum_thread::ActiveModel {
+            user_id: Set(Some(user_id)),
+            category_id: Set(Some(category_id)),
+            title: Set(Some(title.clone())),
+            description: Set(description),
+            number_posts: Set(0),
+            date_started: Set(Some(Utc::now())),
+            site_id: Set(Some(site_id)),
+            sticky: Set(false),
+            blocked: Set(false),
+            archived: Set(false),
+            ..Default::default()
+        };
+
+        let thread = model.insert(txn).await?;
+        let thread_id = thread.thread_id;
+        ForumCategoryService::add_counts(ctx, category_id, 1, 0, None).await?;
+
+        // Insert first post
+        let post = ForumPostService::create(
+            ctx,
+            site_id,
+            CreateForumPost {
+                thread_id,
+                parent_id: None,
+                title: Some(title),
+                wikitext,
+                user_id,
+            },
+        )
+        .await?;
+
+        Ok(CreateForumThreadOutput {
+            thread_id,
+            post_id: post.post_id,
+        })
+    }
+
+    /// Gets the discussion thread for a page, creating it if needed.
+    ///
+    /// Page discussions are created lazily, when the first comment is posted.
+    /// The thread is attached to the page by ID, so it stays with the page
+    /// if it is moved.
+    pub async fn get_or_create_for_page(
+        ctx: &ServiceContext<'_>,
+        page: &PageModel,
+        title: &str,
+        user_id: i64,
+    ) -> Result<ForumThreadModel> {
+        let txn = ctx.transaction();
+        let site_id = page.site_id;
+
+        if let Some(thread_id) = page.discussion_thread_id {
+            return Self::get(ctx, site_id, thread_id).await;
+        }
+
+        tide::log::info!("Creating discussion thread for page ID {}", page.page_id);
+
+        let category =
+            ForumCategoryService::get_or_create_discussion(ctx, site_id).await?;
+        let model = forum_thread::ActiveModel {
+            user_id: Set(Some(user_id)),
+            category_id: Set(Some(category.category_id)),
+            title: Set(Some(str!(title))),
+            number_posts: Set(0),
+            date_started: Set(Some(Utc::now())),
+            site_id: Set(Some(site_id)),
+            page_id: Set(Some(page.page_id)),
+            sticky: Set(false),
+            blocked: Set(false),
+            archived: Set(false),
+            ..Default::default()
+        };
+
+        let thread = model.insert(txn).await?;
+        ForumCategoryService::add_counts(ctx, category.category_id, 1, 0, None).await?;
+
+        // Link the page to its new thread
+        let model = page::ActiveModel {
+            page_id: Set(page.page_id),
+            discussion_thread_id: Set(Some(thread.thread_id)),
+            ..Default::default()
+        };
+
+        model.update(txn).await?;
+        Ok(thread)
+    }
+
+    /// Archives or unarchives a page's discussion thread.
+    ///
+    /// Archived threads cannot be posted in. This is tracked separately
+    /// from `blocked`, so restoring a page keeps any moderator lock.
+    /// This is used when pages are deleted and restored.
+    pub async fn set_page_archived(
+        ctx: &ServiceContext<'_>,
+        discussion_thread_id: Option<i64>,
+        archived: bool,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+
+        if let Some(thread_id) = discussion_thread_id {
+            let model = forum_thread::ActiveModel {
+                thread_id: Set(thread_id),
+                archived: Set(archived),
+                ..Default::default()
+            };
+
+            model.update(txn).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn update(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        thread_id: i64,
+        UpdateForumThread {
+            title,
+            description,
+            sticky,
+            blocked,
+            user_id,
+        }: UpdateForumThread,
+    ) -> Result<ForumThreadModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Moderate).await?;
+
+        // Ensure the thread is in this site
+        Self::get(ctx, site_id, thread_id).await?;
+
+        let mut model = forum_thread::ActiveModel {
+            thread_id: Set(thread_id),
+            ..Default::default()
+        };
+
+        if let ProvidedValue::Set(title) = title {
+            model.title = Set(Some(title));
+        }
+
+        if let ProvidedValue::Set(description) = description {
+            model.description = Set(description);
+        }
+
+        if let ProvidedValue::Set(sticky) = sticky {
+            model.sticky = Set(sticky);
+        }
+
+        if let ProvidedValue::Set(blocked) = blocked {
+            model.blocked = Set(blocked);
+        }
+
+        let thread = model.update(txn).await?;
+        Ok(thread)
+    }
+
+    /// Soft-deletes a thread, removing it from its category's totals.
+    ///
+    /// If this was a page's discussion thread, the page no longer refers to it.
+    pub async fn delete(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        thread_id: i64,
+        DeleteForumThread { user_id }: DeleteForumThread,
+    ) -> Result<ForumThreadModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Moderate).await?;
+        let thread = Self::get(ctx, site_id, thread_id).await?;
+
+        let model = forum_thread::ActiveModel {
+            thread_id: Set(thread_id),
+            deleted_at: Set(Some(now())),
+            ..Default::default()
+        };
+
+        let thread_after = model.update(txn).await?;
+
+        if let Some(category_id) = thread.category_id {
+            ForumCategoryService::add_counts(
+                ctx,
+                category_id,
+                -1,
+                -thread.number_posts,
+                None,
+            )
+            .await?;
+        }
+
+        // Detach it from its page, so the next comment starts a new discussion
+        if let Some(page_id) = thread.page_id {
+            Page::update_many()
+                .col_expr(page::Column::DiscussionThreadId, Expr::value(None::<i64>))
+                .filter(
+                    Condition::all()
+                        .add(page::Column::PageId.eq(page_id))
+                        .add(page::Column::DiscussionThreadId.eq(thread_id)),
+                )
+                .exec(txn)
+                .await?;
+        }
+
+        Ok(thread_after)
+    }
+
+    pub async fn get_optional(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        thread_id: i64,
+    ) -> Result<Option<ForumThreadModel>> {
+        let txn = ctx.transaction();
+        let thread = ForumThread::find()
+            .filter(
+                Condition::all()
+                    .add(forum_thread::Column::ThreadId.eq(thread_id))
+                    .add(forum_thread::Column::SiteId.eq(site_id))
+                    .add(forum_thread::Column::DeletedAt.is_null()),
+            )
+            .one(txn)
+            .await?;
+
+        Ok(thread)
+    }
+
+    #[inline]
+    pub async fn get(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        thread_id: i64,
+    ) -> Result<ForumThreadModel> {
+        match Self::get_optional(ctx, site_id, thread_id).await? {
+            Some(thread) => Ok(thread),
+            None => Err(Error::NotFound),
+        }
+    }
+
+    /// Gets the threads in a category, with sticky threads listed first.
+    pub async fn get_all(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        category_id: i64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<ForumThreadModel>> {
+        let txn = ctx.transaction();
+        let threads = ForumThread::find()
+            .filter(
+                Condition::all()
+                    .add(forum_thread::Column::SiteId.eq(site_id))
+                    .add(forum_thread::Column::CategoryId.eq(category_id))
+                    .add(forum_thread::Column::DeletedAt.is_null()),
+            )
+            .order_by_desc(forum_thread::Column::Sticky)
+            .order_by_desc(forum_thread::Column::LastPostId)
+            .order_by_desc(forum_thread::Column::ThreadId)
+            .offset(offset)
+            .limit(limit)
+            .all(txn)
+            .await?;
+
+        Ok(threads)
+    }
+
+    /// Updates the running totals for a thread.
+    ///
+    /// If `last_post_id` is set, then it replaces the thread's latest post.
+    pub async fn add_counts(
+        ctx: &ServiceContext<'_>,
+        thread: &ForumThreadModel,
+        posts: i32,
+        last_post_id: Option<i64>,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+
+        // Updated in place, so concurrent posts don't overwrite each other's totals
+        let mut update = ForumThread::update_many().col_expr(
+            forum_thread::Column::NumberPosts,
+            Expr::col(forum_thread::Column::NumberPosts).add(posts),
+        );
+
+        if let Some(post_id) = last_post_id {
+            update =
+                update.col_expr(forum_thread::Column::LastPostId, Expr::value(post_id));
+        }
+
+        update
+            .filter(forum_thread::Column::ThreadId.eq(thread.thread_id))
+            .exec(txn)
+            .await?;
+
+        if let Some(category_id) = thread.category_id {
+            ForumCategoryService::add_counts(ctx, category_id, 0, posts, last_post_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Recalculates a thread's latest post, such as after one is deleted.
+    ///
+    /// The category's latest post is also recalculated.
+    pub async fn refresh_last_post(
+        ctx: &ServiceContext<'_>,
+        thread: &ForumThreadModel,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let last_post = ForumPost::find()
+            .filter(
+                Condition::all()
+                    .add(forum_post::Column::ThreadId.eq(thread.thread_id))
+                    .add(forum_post::Column::DeletedAt.is_null()),
+            )
+            .order_by_desc(forum_post::Column::PostId)
+            .one(txn)
+            .await?;
+
+        let model = forum_thread::ActiveModel {
+            thread_id: Set(thread.thread_id),
+            last_post_id: Set(last_post.map(|post| post.post_id)),
+            ..Default::default()
+        };
+
+        model.update(txn).await?;
+
+        if let Some(category_id) = thread.category_id {
+            ForumCategoryService::refresh_last_post(ctx, category_id).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+/*
+ * services/forum_thread/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateForumThread {
+    pub category_id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub wikitext: String,
+    pub user_id: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateForumThreadOutput {
+    pub thread_id: i64,
+    pub post_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateForumThread {
+    #[serde(default)]
+    pub title: ProvidedValue<String>,
+
+    #[serde(default)]
+    pub description: ProvidedValue<Option<String>>,
+
+    #[serde(default)]
+    pub sticky: ProvidedValue<bool>,
+
+    #[serde(alias = "locked", default)]
+    pub blocked: ProvidedValue<bool>,
+
+    pub user_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteForumThread {
+    pub user_id: i64,
+}
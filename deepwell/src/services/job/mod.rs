@@ -22,9 +22,15 @@
 //!
 //! At present we do not use a separate service which stores jobs durably. This
 //! can mean that if this DEEPWELL node fails, the queued jobs will not be run.
+//! Vote analysis reports are stored in the database, so any which are still
+//! pending are queued again when the job runner starts.
 //!
 //! The following kinds of jobs are available:
 //! * Rerendering a page
+//! * Analyzing votes for suspicious patterns
+//!
+//! It also contains the rerender sweep, which queues rerender jobs for every
+//! page last rendered by a different version of ftml.
 
 mod prelude {
     pub use super::super::prelude::*;
@@ -33,6 +39,8 @@ mod prelude {
 
 mod service;
 mod structs;
+mod sweep;
 
 pub use self::service::{JobRunner, JobService};
 pub use self::structs::*;
+pub use self::sweep::RerenderSweep;
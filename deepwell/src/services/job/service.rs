@@ -20,7 +20,7 @@
 
 use super::prelude::*;
 use crate::api::ApiServerState;
-use crate::services::RevisionService;
+use crate::services::{RevisionService, VoteAnalysisService};
 use async_std::task;
 use crossfire::mpsc;
 use sea_orm::TransactionTrait;
@@ -61,6 +61,12 @@ impl JobService {
 
         Self::queue_job(Job::RerenderPageId { site_id, page_id });
     }
+
+    pub fn queue_vote_analysis(report_id: i64) {
+        tide::log::debug!("Queueing vote analysis report ID {report_id}");
+
+        Self::queue_job(Job::AnalyzeVotes { report_id });
+    }
 }
 
 #[derive(Debug)]
@@ -80,6 +86,11 @@ impl JobRunner {
 
         tide::log::info!("Starting job runner");
 
+        // Jobs are only kept in memory, so pick up any left over from before
+        if let Err(error) = self.requeue_pending().await {
+            tide::log::error!("Error requeueing pending jobs: {error}");
+        }
+
         loop {
             tide::log::trace!("Waiting for next job on queue...");
             let job = source!()
@@ -99,6 +110,24 @@ impl JobRunner {
         }
     }
 
+    /// Queues vote analysis reports which were never completed.
+    async fn requeue_pending(&self) -> Result<()> {
+        let txn = self.state.database.begin().await?;
+        let ctx = &ServiceContext::from_raw(&self.state, &txn);
+        let report_ids = VoteAnalysisService::get_pending_ids(ctx).await?;
+        txn.commit().await?;
+
+        if !report_ids.is_empty() {
+            tide::log::info!("Requeueing {} pending vote analyses", report_ids.len());
+        }
+
+        for report_id in report_ids {
+            JobService::queue_vote_analysis(report_id);
+        }
+
+        Ok(())
+    }
+
     async fn process_job(&mut self, job: Job) -> Result<()> {
         let txn = self.state.database.begin().await?;
         let ctx = &ServiceContext::from_raw(&self.state, &txn);
@@ -107,6 +136,9 @@ impl JobRunner {
             Job::RerenderPageId { site_id, page_id } => {
                 RevisionService::rerender(ctx, site_id, page_id).await?;
             }
+            Job::AnalyzeVotes { report_id } => {
+                VoteAnalysisService::run(ctx, report_id).await?;
+            }
         }
 
         txn.commit().await?;
@@ -18,7 +18,35 @@
  * along with this program. If not, see <http://www.gnu.org/licenses/>.
  */
 
+use sea_orm::entity::prelude::DateTimeWithTimeZone;
+
 #[derive(Debug, Clone)]
 pub enum Job {
     RerenderPageId { site_id: i64, page_id: i64 },
+    AnalyzeVotes { report_id: i64 },
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RerenderSweepStatus {
+    /// Whether a sweep is currently enqueueing rerender jobs.
+    pub running: bool,
+
+    /// The ftml version that pages are being brought up to.
+    pub generator: String,
+
+    pub started_at: Option<DateTimeWithTimeZone>,
+    pub finished_at: Option<DateTimeWithTimeZone>,
+
+    /// How many pages were out of date when the sweep started.
+    pub total: u64,
+
+    /// How many pages have been put on the job queue so far.
+    pub queued: u64,
+
+    /// How many pages are still out of date.
+    ///
+    /// This is read from the database when the status is requested,
+    /// so it reflects jobs which have actually finished.
+    pub remaining: u64,
 }
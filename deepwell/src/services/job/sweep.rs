@@ -0,0 +1,195 @@
+/*
+ * services/job/sweep.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Finds pages rendered by an older version of ftml and queues them for rerendering.
+//!
+//! A page is considered stale if the `compiled_generator` of its latest revision
+//! does not match the current ftml version. Because rerendering updates that column,
+//! the sweep keeps no state of its own in the database: if the server restarts
+//! partway through, the next sweep simply picks up the pages which are still stale.
+
+use super::prelude::*;
+use super::JobService;
+use crate::api::ApiServerState;
+use async_std::task;
+use ftml::info::VERSION;
+use sea_orm::{DatabaseBackend, DatabaseConnection, FromQueryResult, Statement};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How many stale pages to fetch from the database at once.
+const BATCH_SIZE: i64 = 100;
+
+lazy_static! {
+    static ref STATUS: Mutex<RerenderSweepStatus> =
+        Mutex::new(RerenderSweepStatus::default());
+}
+
+#[derive(FromQueryResult, Debug)]
+struct StalePage {
+    site_id: i64,
+    page_id: i64,
+}
+
+#[derive(FromQueryResult, Debug)]
+struct StaleCount {
+    count: i64,
+}
+
+#[derive(Debug)]
+pub struct RerenderSweep {
+    state: ApiServerState,
+}
+
+impl RerenderSweep {
+    /// Starts a new sweep in the background.
+    ///
+    /// Returns `false` if a sweep is already running.
+    pub fn start(state: &ApiServerState) -> bool {
+        {
+            let mut status = STATUS.lock().expect("Sweep status lock poisoned");
+            if status.running {
+                return false;
+            }
+
+            *status = RerenderSweepStatus {
+                running: true,
+                generator: VERSION.clone(),
+                started_at: Some(now()),
+                ..Default::default()
+            };
+        }
+
+        let state = Arc::clone(state);
+        let sweep = RerenderSweep { state };
+        task::spawn(sweep.main());
+        true
+    }
+
+    /// Gets the progress of the current or most recent sweep.
+    pub async fn status(state: &ApiServerState) -> Result<RerenderSweepStatus> {
+        let remaining = count_stale(&state.database).await?;
+        let mut status = STATUS.lock().expect("Sweep status lock poisoned").clone();
+        status.remaining = remaining;
+        Ok(status)
+    }
+
+    async fn main(self) {
+        tide::log::info!("Starting rerender sweep for ftml {}", *VERSION);
+
+        match self.run().await {
+            Ok(()) => tide::log::info!("Finished queueing rerender sweep"),
+            Err(error) => tide::log::error!("Error during rerender sweep: {error}"),
+        }
+
+        let mut status = STATUS.lock().expect("Sweep status lock poisoned");
+        status.running = false;
+        status.finished_at = Some(now());
+    }
+
+    async fn run(&self) -> Result<()> {
+        let database = &self.state.database;
+        let delay = Duration::from_secs(1) / self.state.config.rerender_sweep_rate.get();
+
+        let total = count_stale(database).await?;
+        tide::log::info!("Found {total} pages to rerender");
+        STATUS.lock().expect("Sweep status lock poisoned").total = total;
+
+        // Walk through page IDs in order, so that pages which are still
+        // queued from an earlier batch are not fetched a second time.
+        let mut last_page_id = 0;
+
+        loop {
+            let pages = find_stale(database, last_page_id).await?;
+            if pages.is_empty() {
+                break;
+            }
+
+            for StalePage { site_id, page_id } in pages {
+                JobService::queue_rerender_page(site_id, page_id);
+                last_page_id = page_id;
+
+                STATUS.lock().expect("Sweep status lock poisoned").queued += 1;
+                task::sleep(delay).await;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn find_stale(
+    database: &DatabaseConnection,
+    after_page_id: i64,
+) -> Result<Vec<StalePage>> {
+    let pages = StalePage::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r"
+        SELECT page.site_id, page.page_id
+        FROM page
+        JOIN LATERAL (
+            SELECT compiled_generator
+            FROM page_revision
+            WHERE page_revision.page_id = page.page_id
+            ORDER BY revision_number DESC
+            LIMIT 1
+        ) AS latest ON true
+        WHERE page.deleted_at IS NULL
+            AND page.page_id > $1
+            AND latest.compiled_generator != $2
+        ORDER BY page.page_id
+        LIMIT $3
+        ",
+        vec![
+            after_page_id.into(),
+            VERSION.as_str().into(),
+            BATCH_SIZE.into(),
+        ],
+    ))
+    .all(database)
+    .await?;
+
+    Ok(pages)
+}
+
+async fn count_stale(database: &DatabaseConnection) -> Result<u64> {
+    let result = StaleCount::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r"
+        SELECT COUNT(*) AS count
+        FROM page
+        JOIN LATERAL (
+            SELECT compiled_generator
+            FROM page_revision
+            WHERE page_revision.page_id = page.page_id
+            ORDER BY revision_number DESC
+            LIMIT 1
+        ) AS latest ON true
+        WHERE page.deleted_at IS NULL
+            AND latest.compiled_generator != $1
+        ",
+        vec![VERSION.as_str().into()],
+    ))
+    .one(database)
+    .await?;
+
+    let count = result.map(|row| row.count).unwrap_or(0);
+    Ok(count.try_into().unwrap_or(0))
+}
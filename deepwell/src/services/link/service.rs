@@ -19,13 +19,14 @@
  */
 
 use super::prelude::*;
-use crate::models::page;
+use crate::models::page::{self, Model as PageModel};
 use crate::models::page_connection::{self, Entity as PageConnection};
 use crate::models::page_connection_missing::{self, Entity as PageConnectionMissing};
 use crate::models::page_link::{self, Entity as PageLink, Model as PageLinkModel};
 use crate::services::{PageService, SiteService};
 use crate::web::ConnectionType;
 use ftml::data::{Backlinks, PageRef};
+use sea_orm::{DatabaseBackend, FromQueryResult, Statement};
 use std::collections::HashMap;
 
 /// Forms an optional `Condition` from a list of connection types.
@@ -141,7 +142,8 @@ impl LinkService {
 
         // Ensure the page doesn't actually exist
         if let Some(page) =
-            PageService::get_optional(ctx, site_id, Reference::from(page_slug)).await?
+            PageService::get_resolved_optional(ctx, site_id, Reference::from(page_slug))
+                .await?
         {
             tide::log::warn!(
                 "Requesting missing page connections for page that exists (site id {}, page id {})",
@@ -222,6 +224,155 @@ impl LinkService {
         Ok(GetLinksExternalToOutput { links })
     }
 
+    /// Gets the missing pages on a site which are linked to most often.
+    ///
+    /// Slots are ranked by how many pages link to them, and only
+    /// connections from extant pages are counted.
+    pub async fn get_wanted(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        LinkReportQuery { category, offset }: LinkReportQuery,
+        limit: u64,
+    ) -> Result<Vec<WantedPage>> {
+        let txn = ctx.transaction();
+        let pages = WantedPage::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r"
+            SELECT
+                missing.to_page_slug AS slug,
+                COUNT(DISTINCT missing.from_page_id) AS page_count,
+                SUM(missing.count)::BIGINT AS link_count
+            FROM page_connection_missing AS missing
+            JOIN page AS from_page
+                ON from_page.page_id = missing.from_page_id
+            WHERE missing.to_site_id = $1
+                AND from_page.deleted_at IS NULL
+                AND (
+                    $2::TEXT IS NULL OR
+                    CASE
+                        WHEN position(':' IN missing.to_page_slug) > 0
+                        THEN split_part(missing.to_page_slug, ':', 1)
+                        ELSE '_default'
+                    END = $2
+                )
+                AND NOT EXISTS (
+                    SELECT 1 FROM page
+                    WHERE page.site_id = missing.to_site_id
+                        AND page.slug = missing.to_page_slug
+                        AND page.deleted_at IS NULL
+                )
+            GROUP BY missing.to_page_slug
+            ORDER BY page_count DESC, link_count DESC, slug
+            LIMIT $3 OFFSET $4
+            ",
+            vec![
+                site_id.into(),
+                category.into(),
+                (limit as i64).into(),
+                (offset as i64).into(),
+            ],
+        ))
+        .all(txn)
+        .await?;
+
+        Ok(pages)
+    }
+
+    /// Gets all pages on a site which nothing else links to or includes.
+    ///
+    /// Connections from deleted pages, or from the page itself, are ignored.
+    pub async fn get_orphaned(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        LinkReportQuery { category, offset }: LinkReportQuery,
+        limit: u64,
+    ) -> Result<Vec<PageModel>> {
+        let txn = ctx.transaction();
+        let pages = PageModel::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r"
+            SELECT page.*
+            FROM page
+            JOIN page_category
+                ON page_category.category_id = page.page_category_id
+            WHERE page.site_id = $1
+                AND page.deleted_at IS NULL
+                AND ($2::TEXT IS NULL OR page_category.slug = $2)
+                AND NOT EXISTS (
+                    SELECT 1
+                    FROM page_connection AS connection
+                    JOIN page AS from_page
+                        ON from_page.page_id = connection.from_page_id
+                    WHERE connection.to_page_id = page.page_id
+                        AND connection.from_page_id != page.page_id
+                        AND from_page.deleted_at IS NULL
+                )
+            ORDER BY page.slug
+            LIMIT $3 OFFSET $4
+            ",
+            vec![
+                site_id.into(),
+                category.into(),
+                (limit as i64).into(),
+                (offset as i64).into(),
+            ],
+        ))
+        .all(txn)
+        .await?;
+
+        Ok(pages)
+    }
+
+    /// Gets all pages on a site which do not link to any other page.
+    ///
+    /// Links to missing pages count, since they still lead somewhere.
+    /// Includes and external links do not.
+    pub async fn get_dead_ends(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        LinkReportQuery { category, offset }: LinkReportQuery,
+        limit: u64,
+    ) -> Result<Vec<PageModel>> {
+        let txn = ctx.transaction();
+        let link_type = ConnectionType::Link.name();
+        let pages = PageModel::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r"
+            SELECT page.*
+            FROM page
+            JOIN page_category
+                ON page_category.category_id = page.page_category_id
+            WHERE page.site_id = $1
+                AND page.deleted_at IS NULL
+                AND ($2::TEXT IS NULL OR page_category.slug = $2)
+                AND NOT EXISTS (
+                    SELECT 1 FROM page_connection AS connection
+                    WHERE connection.from_page_id = page.page_id
+                        AND connection.to_page_id != page.page_id
+                        AND connection.connection_type = $5
+                )
+                AND NOT EXISTS (
+                    SELECT 1 FROM page_connection_missing AS missing
+                    WHERE missing.from_page_id = page.page_id
+                        AND missing.connection_type = $5
+                )
+            ORDER BY page.slug
+            LIMIT $3 OFFSET $4
+            ",
+            vec![
+                site_id.into(),
+                category.into(),
+                (limit as i64).into(),
+                (offset as i64).into(),
+                link_type.into(),
+            ],
+        ))
+        .all(txn)
+        .await?;
+
+        Ok(pages)
+    }
+
     pub async fn update(
         ctx: &ServiceContext<'_>,
         site_id: i64,
@@ -482,7 +633,8 @@ async fn count_connections(
     };
 
     let page =
-        PageService::get_optional(ctx, to_site_id, Reference::Slug(page_slug)).await?;
+        PageService::get_resolved_optional(ctx, to_site_id, Reference::Slug(page_slug))
+            .await?;
 
     match page {
         Some(to_page) => {
@@ -22,6 +22,7 @@ use crate::models::page_connection::Model as PageConnectionModel;
 use crate::models::page_connection_missing::Model as PageConnectionMissingModel;
 use crate::models::page_link::Model as PageLinkModel;
 use sea_orm::entity::prelude::DateTimeWithTimeZone;
+use sea_orm::FromQueryResult;
 
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -70,3 +71,23 @@ pub struct ToExternalLink {
     pub page_id: i64,
     pub count: i32,
 }
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LinkReportQuery {
+    /// Only include pages in the category with this slug.
+    pub category: Option<String>,
+    pub offset: u64,
+}
+
+#[derive(Serialize, FromQueryResult, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WantedPage {
+    pub slug: String,
+
+    /// How many distinct pages link to this slot.
+    pub page_count: i64,
+
+    /// The total number of links to this slot, across all pages.
+    pub link_count: i64,
+}
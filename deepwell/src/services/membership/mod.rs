@@ -0,0 +1,40 @@
+/*
+ * services/membership/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Site membership, by password, application, or invitation.
+//!
+//! Being a member of a site means holding a role in it other than guest
+//! (see `RoleService`). Joining gives the user the built-in member role.
+//!
+//! Depending on the site's settings, users may join directly by giving the
+//! membership password, or apply with a message which staff then accept or
+//! reject. Staff may also invite users, as can members if the site allows it.
+//! This backs the `[[module Join]]` button.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::MembershipService;
+pub use self::structs::*;
@@ -0,0 +1,481 @@
+/*
+ * services/membership/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::site_application::{
+    self, Entity as SiteApplication, Model as SiteApplicationModel,
+};
+use crate::models::site_invitation::{
+    self, Entity as SiteInvitation, Model as SiteInvitationModel,
+};
+use crate::models::site_role::Entity as SiteRole;
+use crate::models::site_user_role::{self, Entity as SiteUserRole};
+use crate::services::permission::{Action, PermissionService};
+use crate::services::role::BuiltinRole;
+use crate::services::{BlockService, RoleService, SiteSettingsService};
+use sea_orm::sea_query::Expr;
+use subtle::ConstantTimeEq;
+
+#[derive(Debug)]
+pub struct MembershipService;
+
+impl MembershipService {
+    /// Gets the ways a user may become a member of a site.
+    ///
+    /// This is used by the `[[module Join]]` button to decide what to show.
+    pub async fn get_join_options(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+    ) -> Result<JoinOptionsOutput> {
+        let (member, settings, application, invitation) = try_join!(
+            Self::is_member(ctx, site_id, user_id),
+            SiteSettingsService::get(ctx, site_id),
+            Self::get_pending_application(ctx, site_id, user_id),
+            Self::get_pending_invitation(ctx, site_id, user_id),
+        )?;
+
+        Ok(JoinOptionsOutput {
+            member,
+            join_by_password: settings.allow_membership_by_password,
+            join_by_apply: settings.allow_membership_by_apply,
+            pending_application_id: application.map(|model| model.application_id),
+            pending_invitation_id: invitation.map(|model| model.invitation_id),
+        })
+    }
+
+    /// Joins a site directly using its membership password.
+    pub async fn join(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        JoinSite { password, user_id }: JoinSite,
+    ) -> Result<()> {
+        Self::check_can_join(ctx, site_id, user_id).await?;
+
+        let settings = SiteSettingsService::get(ctx, site_id).await?;
+        if !settings.allow_membership_by_password {
+            tide::log::warn!("Site ID {site_id} does not allow joining by password");
+            return Err(Error::MembershipClosed);
+        }
+
+        let expected = settings.membership_password.unwrap_or_default();
+        let matches: bool = password.as_bytes().ct_eq(expected.as_bytes()).into();
+        if expected.is_empty() || !matches {
+            tide::log::warn!(
+                "User ID {user_id} gave an incorrect membership password for site ID {site_id}",
+            );
+            return Err(Error::IncorrectMembershipPassword);
+        }
+
+        RoleService::assign_builtin(ctx, site_id, user_id, BuiltinRole::Member, None)
+            .await
+    }
+
+    /// Applies to become a member of a site.
+    ///
+    /// The application stays pending until it is reviewed by staff.
+    pub async fn apply(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        ApplyToSite { message, user_id }: ApplyToSite,
+    ) -> Result<SiteApplicationModel> {
+        let txn = ctx.transaction();
+        Self::check_can_join(ctx, site_id, user_id).await?;
+
+        let settings = SiteSettingsService::get(ctx, site_id).await?;
+        if !settings.allow_membership_by_apply {
+            tide::log::warn!("Site ID {site_id} does not accept applications");
+            return Err(Error::MembershipClosed);
+        }
+
+        if let Some(application) =
+            Self::get_pending_application(ctx, site_id, user_id).await?
+        {
+            tide::log::error!(
+                "User ID {user_id} already has pending application ID {} for site ID {site_id}",
+                application.application_id,
+            );
+            return Err(Error::Conflict);
+        }
+
+        let model = site_application::ActiveModel {
+            site_id: Set(site_id),
+            user_id: Set(user_id),
+            message: Set(message),
+            status: Set(str!(ApplicationStatus::Pending.name())),
+            created_at: Set(now()),
+            ..Default::default()
+        };
+
+        let application = model.insert(txn).await?;
+        Ok(application)
+    }
+
+    /// Gets the pending applications for a site, oldest first.
+    pub async fn get_applications(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        ApplicationsQuery { user_id }: ApplicationsQuery,
+    ) -> Result<Vec<SiteApplicationModel>> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Moderate).await?;
+
+        let applications = SiteApplication::find()
+            .filter(
+                Condition::all()
+                    .add(site_application::Column::SiteId.eq(site_id))
+                    .add(
+                        site_application::Column::Status
+                            .eq(ApplicationStatus::Pending.name()),
+                    ),
+            )
+            .order_by_asc(site_application::Column::ApplicationId)
+            .all(txn)
+            .await?;
+
+        Ok(applications)
+    }
+
+    /// Accepts or rejects a pending application.
+    ///
+    /// Accepting makes the applicant a member, unless they
+    /// have since gained a role in the site some other way.
+    pub async fn review(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        application_id: i64,
+        ReviewApplication {
+            accept,
+            reply,
+            user_id,
+        }: ReviewApplication,
+    ) -> Result<SiteApplicationModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Moderate).await?;
+
+        let status = if accept {
+            ApplicationStatus::Accepted
+        } else {
+            ApplicationStatus::Rejected
+        };
+
+        // Only pending applications can be reviewed, and only once
+        let result = SiteApplication::update_many()
+            .col_expr(site_application::Column::Status, Expr::value(status.name()))
+            .col_expr(site_application::Column::Reply, Expr::value(reply))
+            .col_expr(site_application::Column::ReviewedAt, Expr::value(now()))
+            .col_expr(site_application::Column::ReviewedBy, Expr::value(user_id))
+            .filter(
+                Condition::all()
+                    .add(site_application::Column::ApplicationId.eq(application_id))
+                    .add(site_application::Column::SiteId.eq(site_id))
+                    .add(
+                        site_application::Column::Status
+                            .eq(ApplicationStatus::Pending.name()),
+                    ),
+            )
+            .exec(txn)
+            .await?;
+
+        if result.rows_affected == 0 {
+            tide::log::error!(
+                "No pending application ID {application_id} in site ID {site_id}",
+            );
+            return Err(Error::NotFound);
+        }
+
+        let application = SiteApplication::find_by_id(application_id)
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        if accept {
+            Self::add_member(ctx, site_id, application.user_id, Some(user_id)).await?;
+        }
+
+        Ok(application)
+    }
+
+    /// Invites a user to become a member of a site.
+    ///
+    /// Staff may always invite users. Members may only do
+    /// so if the site's settings allow it.
+    pub async fn invite(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        InviteUser {
+            user_id,
+            message,
+            invited_by,
+        }: InviteUser,
+    ) -> Result<SiteInvitationModel> {
+        let txn = ctx.transaction();
+
+        let settings = SiteSettingsService::get(ctx, site_id).await?;
+        if settings.allow_members_invite
+            && Self::is_member(ctx, site_id, invited_by).await?
+        {
+            BlockService::check_banned(ctx, site_id, invited_by).await?;
+        } else {
+            PermissionService::check_site(ctx, site_id, invited_by, Action::Moderate)
+                .await?;
+        }
+
+        if Self::is_member(ctx, site_id, user_id).await? {
+            tide::log::error!(
+                "User ID {user_id} is already a member of site ID {site_id}"
+            );
+            return Err(Error::Conflict);
+        }
+
+        if let Some(invitation) =
+            Self::get_pending_invitation(ctx, site_id, user_id).await?
+        {
+            tide::log::error!(
+                "User ID {user_id} already has pending invitation ID {} for site ID {site_id}",
+                invitation.invitation_id,
+            );
+            return Err(Error::Conflict);
+        }
+
+        let model = site_invitation::ActiveModel {
+            site_id: Set(site_id),
+            user_id: Set(user_id),
+            invited_by: Set(invited_by),
+            message: Set(message),
+            status: Set(str!(InvitationStatus::Pending.name())),
+            created_at: Set(now()),
+            ..Default::default()
+        };
+
+        let invitation = model.insert(txn).await?;
+        Ok(invitation)
+    }
+
+    /// Gets the pending invitations sent to a user, oldest first.
+    pub async fn get_invitations(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+    ) -> Result<Vec<SiteInvitationModel>> {
+        let txn = ctx.transaction();
+        let invitations = SiteInvitation::find()
+            .filter(
+                Condition::all()
+                    .add(site_invitation::Column::UserId.eq(user_id))
+                    .add(
+                        site_invitation::Column::Status
+                            .eq(InvitationStatus::Pending.name()),
+                    ),
+            )
+            .order_by_asc(site_invitation::Column::InvitationId)
+            .all(txn)
+            .await?;
+
+        Ok(invitations)
+    }
+
+    /// Accepts or declines an invitation, on behalf of the invited user.
+    pub async fn respond(
+        ctx: &ServiceContext<'_>,
+        invitation_id: i64,
+        RespondToInvitation { accept, user_id }: RespondToInvitation,
+    ) -> Result<SiteInvitationModel> {
+        let txn = ctx.transaction();
+        let status = if accept {
+            InvitationStatus::Accepted
+        } else {
+            InvitationStatus::Declined
+        };
+
+        // Only the invited user may respond, and only once
+        let result = SiteInvitation::update_many()
+            .col_expr(site_invitation::Column::Status, Expr::value(status.name()))
+            .col_expr(site_invitation::Column::RespondedAt, Expr::value(now()))
+            .filter(
+                Condition::all()
+                    .add(site_invitation::Column::InvitationId.eq(invitation_id))
+                    .add(site_invitation::Column::UserId.eq(user_id))
+                    .add(
+                        site_invitation::Column::Status
+                            .eq(InvitationStatus::Pending.name()),
+                    ),
+            )
+            .exec(txn)
+            .await?;
+
+        if result.rows_affected == 0 {
+            tide::log::error!(
+                "No pending invitation ID {invitation_id} for user ID {user_id}",
+            );
+            return Err(Error::NotFound);
+        }
+
+        let invitation = SiteInvitation::find_by_id(invitation_id)
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        if accept {
+            let site_id = invitation.site_id;
+            BlockService::check_banned(ctx, site_id, user_id).await?;
+            Self::add_member(ctx, site_id, user_id, Some(invitation.invited_by)).await?;
+        }
+
+        Ok(invitation)
+    }
+
+    /// Leaves a site, giving up whatever role the user held in it.
+    pub async fn leave(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        LeaveSite { user_id }: LeaveSite,
+    ) -> Result<()> {
+        if !Self::is_member(ctx, site_id, user_id).await? {
+            tide::log::error!("User ID {user_id} is not a member of site ID {site_id}");
+            return Err(Error::NotFound);
+        }
+
+        RoleService::unassign(ctx, site_id, user_id, user_id).await
+    }
+
+    /// Gets the members of a site, in the order they joined.
+    pub async fn get_members(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+    ) -> Result<Vec<SiteMemberOutput>> {
+        let txn = ctx.transaction();
+        let members = SiteUserRole::find()
+            .find_also_related(SiteRole)
+            .filter(site_user_role::Column::SiteId.eq(site_id))
+            .order_by_asc(site_user_role::Column::CreatedAt)
+            .all(txn)
+            .await?
+            .into_iter()
+            .filter_map(|(assignment, role)| {
+                let role = role?;
+                if role.slug == BuiltinRole::Guest.slug() {
+                    return None;
+                }
+
+                Some(SiteMemberOutput {
+                    user_id: assignment.user_id,
+                    role_id: role.role_id,
+                    role: role.slug,
+                    joined_at: assignment.created_at,
+                })
+            })
+            .collect();
+
+        Ok(members)
+    }
+
+    /// Determines if the user holds any role in the site other than guest.
+    pub async fn is_member(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+    ) -> Result<bool> {
+        let role = RoleService::get_user_role(ctx, site_id, user_id).await?;
+        Ok(role.slug != BuiltinRole::Guest.slug())
+    }
+
+    /// Ensures the user is allowed to become a member of the site.
+    async fn check_can_join(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+    ) -> Result<()> {
+        BlockService::check_banned(ctx, site_id, user_id).await?;
+
+        if Self::is_member(ctx, site_id, user_id).await? {
+            tide::log::error!(
+                "User ID {user_id} is already a member of site ID {site_id}"
+            );
+            return Err(Error::Conflict);
+        }
+
+        Ok(())
+    }
+
+    /// Makes the user a member, unless they already hold a role.
+    ///
+    /// This avoids replacing a higher role, such as moderator,
+    /// with the member role.
+    async fn add_member(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+        added_by: Option<i64>,
+    ) -> Result<()> {
+        if Self::is_member(ctx, site_id, user_id).await? {
+            tide::log::info!(
+                "User ID {user_id} is already a member of site ID {site_id}"
+            );
+            return Ok(());
+        }
+
+        RoleService::assign_builtin(ctx, site_id, user_id, BuiltinRole::Member, added_by)
+            .await
+    }
+
+    async fn get_pending_application(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+    ) -> Result<Option<SiteApplicationModel>> {
+        let txn = ctx.transaction();
+        let application = SiteApplication::find()
+            .filter(
+                Condition::all()
+                    .add(site_application::Column::SiteId.eq(site_id))
+                    .add(site_application::Column::UserId.eq(user_id))
+                    .add(
+                        site_application::Column::Status
+                            .eq(ApplicationStatus::Pending.name()),
+                    ),
+            )
+            .one(txn)
+            .await?;
+
+        Ok(application)
+    }
+
+    async fn get_pending_invitation(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+    ) -> Result<Option<SiteInvitationModel>> {
+        let txn = ctx.transaction();
+        let invitation = SiteInvitation::find()
+            .filter(
+                Condition::all()
+                    .add(site_invitation::Column::SiteId.eq(site_id))
+                    .add(site_invitation::Column::UserId.eq(user_id))
+                    .add(
+                        site_invitation::Column::Status
+                            .eq(InvitationStatus::Pending.name()),
+                    ),
+            )
+            .one(txn)
+            .await?;
+
+        Ok(invitation)
+    }
+}
@@ -0,0 +1,39 @@
+/*
+ * services/message/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Private messages sent directly between users.
+//!
+//! Sending a message stores two copies of it: one in the recipient's inbox,
+//! and one in the sender's outbox, marked with the "sent" flag. This way each
+//! user can mark, archive, or delete their own copy without affecting the other.
+//!
+//! The state of each copy is stored as a bitmask in `flags`, using the same
+//! values as the `UserMessage` model in the web application.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::MessageService;
+pub use self::structs::*;
@@ -0,0 +1,327 @@
+/*
+ * services/message/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::user_messages::{
+    self, Entity as UserMessage, Model as UserMessageModel,
+};
+use crate::services::render::RenderOutput;
+use crate::services::{BlockService, RenderService, TextService, UserService};
+use ftml::data::PageInfo;
+use ftml::settings::{WikitextMode, WikitextSettings};
+use sea_orm::prelude::Uuid;
+use sea_orm::{DatabaseBackend, FromQueryResult, Statement};
+
+#[derive(Debug)]
+pub struct MessageService;
+
+impl MessageService {
+    /// Sends a message, delivering it to the recipient's inbox and the sender's outbox.
+    pub async fn send(
+        ctx: &ServiceContext<'_>,
+        SendMessage {
+            from_user_id,
+            to_user_id,
+            subject,
+            wikitext,
+        }: SendMessage,
+    ) -> Result<SendMessageOutput> {
+        let txn = ctx.transaction();
+
+        // Ensure both users exist, and neither blocks the other
+        let (_, recipient) = try_join!(
+            UserService::get(ctx, Reference::from(from_user_id)),
+            UserService::get(ctx, Reference::from(to_user_id)),
+        )?;
+
+        BlockService::check_user_blocked(ctx, from_user_id, to_user_id).await?;
+
+        // Render message body
+        let language = recipient.language.as_deref().unwrap_or("en");
+        let RenderOutput {
+            compiled_hash,
+            compiled_generator,
+            ..
+        } = Self::render(ctx, subject.as_deref(), language, wikitext.clone()).await?;
+
+        // Insert a copy for each user
+        let model = user_messages::ActiveModel {
+            from_user_id: Set(from_user_id),
+            to_user_id: Set(Some(to_user_id)),
+            subject: Set(subject),
+            body: Set(wikitext),
+            flags: Set(0),
+            created_at: Set(Some(Utc::now())),
+            compiled_hash: Set(Some(compiled_hash.to_vec())),
+            compiled_at: Set(Some(now())),
+            compiled_generator: Set(Some(compiled_generator)),
+            ..Default::default()
+        };
+
+        let mut sent_model = model.clone();
+        sent_model.flags = Set(MESSAGE_SENT);
+
+        let received = model.insert(txn).await?;
+        let sent = sent_model.insert(txn).await?;
+
+        Ok(SendMessageOutput { received, sent })
+    }
+
+    /// Gets one of the user's messages, including its rendered body.
+    pub async fn get(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        message_id: Uuid,
+    ) -> Result<MessageOutput> {
+        let message = Self::get_own(ctx, user_id, message_id).await?;
+        let compiled_html = match message.compiled_hash {
+            Some(ref hash) => TextService::get_optional(ctx, hash).await?,
+            None => None,
+        };
+
+        Ok(MessageOutput {
+            message,
+            compiled_html,
+        })
+    }
+
+    /// Gets the messages in one of the user's folders, newest first.
+    pub async fn get_folder(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        folder: MessageFolder,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<MessageOutput>> {
+        let txn = ctx.transaction();
+
+        // Which side of the message the user is on, and which
+        // flags must be set or unset for a message to be listed.
+        let (user_column, flags_set, flags_unset) = match folder {
+            MessageFolder::Inbox => (
+                "to_user_id",
+                0,
+                MESSAGE_SENT | MESSAGE_DRAFT | MESSAGE_ARCHIVED,
+            ),
+            MessageFolder::Archive => {
+                ("to_user_id", MESSAGE_ARCHIVED, MESSAGE_SENT | MESSAGE_DRAFT)
+            }
+            MessageFolder::Starred => {
+                ("to_user_id", MESSAGE_STARRED, MESSAGE_SENT | MESSAGE_DRAFT)
+            }
+            MessageFolder::Sent => ("from_user_id", MESSAGE_SENT, 0),
+        };
+
+        let messages =
+            UserMessageModel::find_by_statement(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                &format!(
+                    r"
+                SELECT * FROM user_messages
+                WHERE {user_column} = $1
+                    AND flags & $2 = $2
+                    AND flags & $3 = 0
+                    AND deleted_at IS NULL
+                ORDER BY created_at DESC
+                LIMIT $4 OFFSET $5
+                ",
+                ),
+                vec![
+                    user_id.into(),
+                    flags_set.into(),
+                    flags_unset.into(),
+                    (limit as i64).into(),
+                    (offset as i64).into(),
+                ],
+            ))
+            .all(txn)
+            .await?;
+
+        // Add compiled HTML
+        let hashes: Vec<Vec<u8>> = messages
+            .iter()
+            .filter_map(|message| message.compiled_hash.clone())
+            .collect();
+
+        let mut compiled = TextService::get_many(ctx, &hashes).await?;
+        let output = messages
+            .into_iter()
+            .map(|message| {
+                let compiled_html = message
+                    .compiled_hash
+                    .as_ref()
+                    .and_then(|hash| compiled.remove(hash));
+
+                MessageOutput {
+                    message,
+                    compiled_html,
+                }
+            })
+            .collect();
+
+        Ok(output)
+    }
+
+    /// Marks the user's copy of a message as read, starred, or archived.
+    pub async fn update_flags(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        message_id: Uuid,
+        UpdateMessageFlags {
+            read,
+            starred,
+            archived,
+        }: UpdateMessageFlags,
+    ) -> Result<UserMessageModel> {
+        let txn = ctx.transaction();
+        let message = Self::get_own(ctx, user_id, message_id).await?;
+        let mut flags = message.flags;
+
+        if let ProvidedValue::Set(value) = read {
+            flags = set_flag(flags, MESSAGE_READ, value);
+        }
+
+        if let ProvidedValue::Set(value) = starred {
+            flags = set_flag(flags, MESSAGE_STARRED, value);
+        }
+
+        if let ProvidedValue::Set(value) = archived {
+            flags = set_flag(flags, MESSAGE_ARCHIVED, value);
+        }
+
+        let model = user_messages::ActiveModel {
+            id: Set(message_id),
+            flags: Set(flags),
+            updated_at: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+
+        let message = model.update(txn).await?;
+        Ok(message)
+    }
+
+    /// Deletes the user's copy of a message.
+    ///
+    /// The other user's copy is left as-is.
+    pub async fn delete(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        message_id: Uuid,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+
+        // Ensure the message belongs to this user
+        Self::get_own(ctx, user_id, message_id).await?;
+
+        let model = user_messages::ActiveModel {
+            id: Set(message_id),
+            deleted_at: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+
+        model.update(txn).await?;
+        Ok(())
+    }
+
+    /// Gets a message, so long as it is the given user's copy.
+    async fn get_own(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        message_id: Uuid,
+    ) -> Result<UserMessageModel> {
+        let txn = ctx.transaction();
+        let message = UserMessage::find()
+            .filter(
+                Condition::all()
+                    .add(user_messages::Column::Id.eq(message_id))
+                    .add(user_messages::Column::DeletedAt.is_null()),
+            )
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        if owner_id(&message) != Some(user_id) {
+            tide::log::error!(
+                "Message {message_id} does not belong to user ID {user_id}"
+            );
+            return Err(Error::NotFound);
+        }
+
+        Ok(message)
+    }
+
+    async fn render(
+        ctx: &ServiceContext<'_>,
+        subject: Option<&str>,
+        language: &str,
+        wikitext: String,
+    ) -> Result<RenderOutput> {
+        // Messages don't belong to any site,
+        // so they are rendered as if on the main one.
+        let settings = WikitextSettings::from_mode(WikitextMode::DirectMessage);
+        let page_info = PageInfo {
+            page: cow!("message"),
+            category: None,
+            site: cow!("www"),
+            title: cow!(subject.unwrap_or("")),
+            alt_title: None,
+            rating: 0.0,
+            tags: vec![],
+            language: cow!(language),
+        };
+
+        RenderService::render(ctx, wikitext, &page_info, &settings).await
+    }
+}
+
+/// Gets the ID of the user this copy of the message belongs to.
+///
+/// Sent copies belong to the sender, and all others to the recipient.
+fn owner_id(message: &UserMessageModel) -> Option<i64> {
+    if message.flags & MESSAGE_SENT != 0 {
+        Some(message.from_user_id)
+    } else {
+        message.to_user_id
+    }
+}
+
+fn set_flag(flags: i16, flag: i16, value: bool) -> i16 {
+    if value {
+        flags | flag
+    } else {
+        flags & !flag
+    }
+}
+
+#[test]
+fn flags() {
+    assert_eq!(set_flag(0, MESSAGE_READ, true), MESSAGE_READ);
+    assert_eq!(set_flag(MESSAGE_READ, MESSAGE_READ, true), MESSAGE_READ);
+    assert_eq!(set_flag(MESSAGE_READ, MESSAGE_READ, false), 0);
+    assert_eq!(set_flag(0, MESSAGE_STARRED, false), 0);
+    assert_eq!(
+        set_flag(MESSAGE_SENT | MESSAGE_READ, MESSAGE_ARCHIVED, true),
+        MESSAGE_SENT | MESSAGE_READ | MESSAGE_ARCHIVED,
+    );
+    assert_eq!(
+        set_flag(MESSAGE_SENT | MESSAGE_STARRED, MESSAGE_STARRED, false),
+        MESSAGE_SENT,
+    );
+}
@@ -0,0 +1,102 @@
+/*
+ * services/message/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::user_messages::Model as UserMessageModel;
+use std::str::FromStr;
+
+pub const MESSAGE_READ: i16 = 1;
+pub const MESSAGE_DRAFT: i16 = 2;
+pub const MESSAGE_STARRED: i16 = 4;
+pub const MESSAGE_ARCHIVED: i16 = 8;
+pub const MESSAGE_SENT: i16 = 16;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SendMessage {
+    pub from_user_id: i64,
+    pub to_user_id: i64,
+    pub subject: Option<String>,
+    pub wikitext: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SendMessageOutput {
+    pub received: UserMessageModel,
+    pub sent: UserMessageModel,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdateMessageFlags {
+    pub read: ProvidedValue<bool>,
+    pub starred: ProvidedValue<bool>,
+    pub archived: ProvidedValue<bool>,
+}
+
+/// Which set of a user's messages to list.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum MessageFolder {
+    /// Received messages which have not been archived.
+    Inbox,
+
+    /// Received messages which have been archived.
+    Archive,
+
+    /// Received messages which have been starred.
+    Starred,
+
+    /// Messages sent by this user.
+    Sent,
+}
+
+impl MessageFolder {
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageFolder::Inbox => "inbox",
+            MessageFolder::Archive => "archive",
+            MessageFolder::Starred => "starred",
+            MessageFolder::Sent => "sent",
+        }
+    }
+}
+
+impl FromStr for MessageFolder {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<MessageFolder> {
+        match value {
+            "inbox" => Ok(MessageFolder::Inbox),
+            "archive" => Ok(MessageFolder::Archive),
+            "starred" => Ok(MessageFolder::Starred),
+            "sent" => Ok(MessageFolder::Sent),
+            _ => Err(Error::InvalidEnumValue),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageOutput {
+    #[serde(flatten)]
+    pub message: UserMessageModel,
+    pub compiled_html: Option<String>,
+}
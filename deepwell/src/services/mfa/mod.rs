@@ -0,0 +1,42 @@
+/*
+ * services/mfa/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Multi-factor authentication using TOTP and recovery codes.
+//!
+//! Enrollment happens in two steps. First a secret is generated and returned
+//! (with an `otpauth://` URI for authenticator apps), but is not saved. Then the
+//! user submits a code generated from it, and only if it is correct is the secret
+//! saved and a set of recovery codes issued.
+//!
+//! TOTP codes follow RFC 6238, and each may only be used once. Recovery codes
+//! are stored hashed, and each is removed after being used.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod recovery;
+mod service;
+mod structs;
+mod totp;
+
+pub use self::service::MfaService;
+pub use self::structs::*;
@@ -0,0 +1,134 @@
+/*
+ * services/mfa/recovery.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Generation and checking of single-use recovery codes.
+//!
+//! Codes have enough entropy that a fast hash is sufficient, so unlike
+//! passwords they are stored as SHA-256 digests. The stored form is the
+//! hex digests of the remaining codes, separated by newlines.
+
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// How many recovery codes are issued at once.
+pub const RECOVERY_CODE_COUNT: usize = 12;
+
+/// How many characters are in each half of a recovery code.
+const RECOVERY_CODE_PART_LENGTH: usize = 5;
+
+/// Characters used in recovery codes, excluding ones which are easily confused.
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// Generates a new set of recovery codes.
+///
+/// Returns the codes to show the user, and the value to store.
+pub fn generate_codes() -> (Vec<String>, String) {
+    let codes = (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_code())
+        .collect::<Vec<_>>();
+
+    let hashes = codes
+        .iter()
+        .map(|code| hash_code(code))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    (codes, hashes)
+}
+
+/// Checks a submitted code against the stored hashes.
+///
+/// If it matches, returns the stored value with that code removed.
+pub fn consume_code(stored: &str, code: &str) -> Option<String> {
+    let hash = hash_code(code);
+    let mut found = false;
+    let mut remaining = Vec::new();
+
+    // Check every hash, so timing doesn't depend on the position of the match
+    for stored_hash in stored.lines() {
+        if bool::from(stored_hash.as_bytes().ct_eq(hash.as_bytes())) && !found {
+            found = true;
+        } else {
+            remaining.push(stored_hash);
+        }
+    }
+
+    if found {
+        Some(remaining.join("\n"))
+    } else {
+        None
+    }
+}
+
+fn generate_code() -> String {
+    let mut code = String::with_capacity(RECOVERY_CODE_PART_LENGTH * 2 + 1);
+
+    for i in 0..RECOVERY_CODE_PART_LENGTH * 2 {
+        if i == RECOVERY_CODE_PART_LENGTH {
+            code.push('-');
+        }
+
+        let index = OsRng.next_u32() as usize % RECOVERY_CODE_ALPHABET.len();
+        code.push(char::from(RECOVERY_CODE_ALPHABET[index]));
+    }
+
+    code
+}
+
+/// Hashes a code, ignoring case, whitespace, and dashes.
+fn hash_code(code: &str) -> String {
+    let normalized = code
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect::<String>();
+
+    let digest = Sha256::digest(normalized.as_bytes());
+    hex::encode(digest)
+}
+
+#[test]
+fn recovery_codes() {
+    let (codes, stored) = generate_codes();
+    assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+    assert_eq!(stored.lines().count(), RECOVERY_CODE_COUNT);
+
+    for code in &codes {
+        assert_eq!(code.len(), RECOVERY_CODE_PART_LENGTH * 2 + 1);
+        assert!(
+            !stored.contains(code.as_str()),
+            "Recovery code stored in plain text"
+        );
+    }
+
+    // Each code works once
+    let stored = consume_code(&stored, &codes[0]).expect("Recovery code not accepted");
+    assert_eq!(stored.lines().count(), RECOVERY_CODE_COUNT - 1);
+    assert_eq!(consume_code(&stored, &codes[0]), None);
+
+    // Formatting is ignored
+    let code = codes[1].to_uppercase().replace('-', " ");
+    let stored = consume_code(&stored, &code).expect("Reformatted code not accepted");
+    assert_eq!(stored.lines().count(), RECOVERY_CODE_COUNT - 2);
+
+    assert_eq!(consume_code(&stored, "aaaaa-aaaaa"), None);
+    assert_eq!(consume_code("", &codes[2]), None);
+}
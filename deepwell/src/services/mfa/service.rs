@@ -0,0 +1,261 @@
+/*
+ * services/mfa/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use super::{recovery, totp};
+use crate::models::users::{self, Entity as User, Model as UserModel};
+use crate::services::{PasswordService, UserService};
+use sea_orm::sea_query::Expr;
+
+/// The issuer name shown in authenticator apps.
+const TOTP_ISSUER: &str = "Wikijump";
+
+#[derive(Debug)]
+pub struct MfaService;
+
+impl MfaService {
+    /// Generates a new TOTP secret for a user to add to their authenticator.
+    ///
+    /// Nothing is saved until the user confirms it with `enable()`.
+    pub async fn setup(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+    ) -> Result<MultiFactorSetupOutput> {
+        let user = UserService::get(ctx, Reference::Id(user_id)).await?;
+        if user.multi_factor_secret.is_some() {
+            tide::log::error!("User ID {user_id} already has MFA enabled");
+            return Err(Error::Conflict);
+        }
+
+        let secret = totp::generate_secret();
+        let totp_uri = totp::build_uri(TOTP_ISSUER, &user.slug, &secret);
+        Ok(MultiFactorSetupOutput { secret, totp_uri })
+    }
+
+    /// Enables MFA for a user, if the code matches the given secret.
+    ///
+    /// Returns the initial set of recovery codes, which are not retrievable later.
+    pub async fn enable(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        EnableMultiFactor { secret, totp }: EnableMultiFactor,
+    ) -> Result<RecoveryCodesOutput> {
+        let txn = ctx.transaction();
+        let user = UserService::get(ctx, Reference::Id(user_id)).await?;
+        if user.multi_factor_secret.is_some() {
+            tide::log::error!("User ID {user_id} already has MFA enabled");
+            return Err(Error::Conflict);
+        }
+
+        let secret_bytes = totp::decode_secret(&secret).ok_or(Error::BadRequest)?;
+        let step = match totp::verify_code(&secret_bytes, &totp, Utc::now().timestamp()) {
+            Some(step) => step,
+            None => {
+                tide::log::warn!(
+                    "Incorrect TOTP code enabling MFA for user ID {user_id}"
+                );
+                return Err(Error::InvalidAuthentication);
+            }
+        };
+
+        let (recovery_codes, stored_codes) = recovery::generate_codes();
+        let model = users::ActiveModel {
+            id: Set(user.id),
+            multi_factor_secret: Set(Some(secret)),
+            multi_factor_recovery_codes: Set(Some(stored_codes)),
+            multi_factor_last_step: Set(Some(step)),
+            updated_at: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+
+        model.update(txn).await?;
+        Ok(RecoveryCodesOutput { recovery_codes })
+    }
+
+    /// Checks the second factor for a user who has MFA enabled.
+    ///
+    /// TOTP codes cannot be reused, and recovery codes are consumed.
+    /// Fails with `Error::InvalidAuthentication` if the code is incorrect.
+    pub async fn verify(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        code: MultiFactorCode,
+    ) -> Result<()> {
+        let user = UserService::get(ctx, Reference::Id(user_id)).await?;
+        Self::verify_user(ctx, &user, code).await
+    }
+
+    /// Replaces a user's recovery codes with a new set.
+    ///
+    /// Requires a TOTP code, so that a leaked recovery code
+    /// cannot be used to issue new ones.
+    pub async fn reset_recovery_codes(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        totp: String,
+    ) -> Result<RecoveryCodesOutput> {
+        let txn = ctx.transaction();
+        let user = UserService::get(ctx, Reference::Id(user_id)).await?;
+        let code = MultiFactorCode {
+            totp: Some(totp),
+            recovery_code: None,
+        };
+
+        Self::verify_user(ctx, &user, code).await?;
+
+        let (recovery_codes, stored_codes) = recovery::generate_codes();
+        let model = users::ActiveModel {
+            id: Set(user.id),
+            multi_factor_recovery_codes: Set(Some(stored_codes)),
+            updated_at: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+
+        model.update(txn).await?;
+        Ok(RecoveryCodesOutput { recovery_codes })
+    }
+
+    /// Disables MFA for a user.
+    ///
+    /// Both the user's password and a second factor must be given.
+    pub async fn disable(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        DisableMultiFactor { password, code }: DisableMultiFactor,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let user = UserService::get(ctx, Reference::Id(user_id)).await?;
+
+        let verification =
+            PasswordService::verify(ctx, &password, &user.password).await?;
+        if !verification.is_valid() {
+            tide::log::warn!("Incorrect password disabling MFA for user ID {user_id}");
+            return Err(Error::InvalidAuthentication);
+        }
+
+        Self::verify_user(ctx, &user, code).await?;
+
+        let model = users::ActiveModel {
+            id: Set(user.id),
+            multi_factor_secret: Set(None),
+            multi_factor_recovery_codes: Set(None),
+            multi_factor_last_step: Set(None),
+            updated_at: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+
+        model.update(txn).await?;
+        Ok(())
+    }
+
+    async fn verify_user(
+        ctx: &ServiceContext<'_>,
+        user: &UserModel,
+        code: MultiFactorCode,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let secret = match user.multi_factor_secret {
+            Some(ref secret) => secret,
+            None => {
+                tide::log::error!("User ID {} does not have MFA enabled", user.id);
+                return Err(Error::BadRequest);
+            }
+        };
+
+        match code {
+            MultiFactorCode {
+                totp: Some(code),
+                recovery_code: None,
+            } => {
+                let secret = totp::decode_secret(secret).ok_or(Error::BadRequest)?;
+                let step = match totp::verify_code(&secret, &code, Utc::now().timestamp())
+                {
+                    Some(step) => step,
+                    None => {
+                        tide::log::warn!("Incorrect TOTP code for user ID {}", user.id);
+                        return Err(Error::InvalidAuthentication);
+                    }
+                };
+
+                // Only succeeds if no code for this or a later step was used,
+                // checked in the same statement so concurrent requests can't race.
+                let result = User::update_many()
+                    .col_expr(users::Column::MultiFactorLastStep, Expr::value(step))
+                    .filter(
+                        Condition::all().add(users::Column::Id.eq(user.id)).add(
+                            Condition::any()
+                                .add(users::Column::MultiFactorLastStep.is_null())
+                                .add(users::Column::MultiFactorLastStep.lt(step)),
+                        ),
+                    )
+                    .exec(txn)
+                    .await?;
+
+                if result.rows_affected == 0 {
+                    tide::log::warn!("Reused TOTP code for user ID {}", user.id);
+                    return Err(Error::InvalidAuthentication);
+                }
+            }
+            MultiFactorCode {
+                totp: None,
+                recovery_code: Some(code),
+            } => {
+                let stored = user.multi_factor_recovery_codes.as_deref().unwrap_or("");
+                let remaining = match recovery::consume_code(stored, &code) {
+                    Some(remaining) => remaining,
+                    None => {
+                        tide::log::warn!(
+                            "Incorrect recovery code for user ID {}",
+                            user.id
+                        );
+                        return Err(Error::InvalidAuthentication);
+                    }
+                };
+
+                // Likewise, only succeeds if the codes haven't changed since being read
+                let result = User::update_many()
+                    .col_expr(
+                        users::Column::MultiFactorRecoveryCodes,
+                        Expr::value(remaining),
+                    )
+                    .filter(
+                        Condition::all()
+                            .add(users::Column::Id.eq(user.id))
+                            .add(users::Column::MultiFactorRecoveryCodes.eq(stored)),
+                    )
+                    .exec(txn)
+                    .await?;
+
+                if result.rows_affected == 0 {
+                    tide::log::warn!("Reused recovery code for user ID {}", user.id);
+                    return Err(Error::InvalidAuthentication);
+                }
+
+                tide::log::info!("Recovery code used for user ID {}", user.id);
+            }
+            _ => {
+                tide::log::error!("Exactly one of TOTP or recovery code must be given");
+                return Err(Error::BadRequest);
+            }
+        }
+
+        Ok(())
+    }
+}
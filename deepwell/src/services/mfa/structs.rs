@@ -0,0 +1,63 @@
+/*
+ * services/mfa/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::services::password::Password;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiFactorSetupOutput {
+    pub secret: String,
+    pub totp_uri: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EnableMultiFactor {
+    /// The secret returned by `MfaService::setup()`.
+    pub secret: String,
+
+    /// A code generated from the secret, to show it was added correctly.
+    pub totp: String,
+}
+
+/// A second factor submitted by the user.
+///
+/// Exactly one of these should be present.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MultiFactorCode {
+    pub totp: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DisableMultiFactor {
+    pub password: Password,
+
+    #[serde(flatten)]
+    pub code: MultiFactorCode,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodesOutput {
+    pub recovery_codes: Vec<String>,
+}
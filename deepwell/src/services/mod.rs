@@ -50,44 +50,79 @@ mod prelude {
 mod context;
 mod error;
 
+pub mod audit;
+pub mod authentication;
 pub mod blob;
+pub mod block;
 pub mod category;
 pub mod file;
 pub mod file_revision;
+pub mod forum_category;
+pub mod forum_post;
+pub mod forum_thread;
 pub mod job;
 pub mod link;
+pub mod membership;
+pub mod message;
+pub mod mfa;
 pub mod outdate;
 pub mod page;
 pub mod parent;
+pub mod password;
+pub mod permission;
+pub mod redirect;
 pub mod render;
 pub mod revision;
+pub mod role;
 pub mod score;
+pub mod search;
 pub mod site;
+pub mod site_settings;
+pub mod tag;
 pub mod text;
+pub mod token;
 pub mod user;
 pub mod vote;
+pub mod vote_analysis;
 
 use crate::api::ApiRequest;
 use sea_orm::DatabaseConnection;
 
+pub use self::audit::AuditService;
+pub use self::authentication::AuthenticationService;
 pub use self::blob::BlobService;
+pub use self::block::BlockService;
 pub use self::category::CategoryService;
 pub use self::context::ServiceContext;
 pub use self::error::*;
 pub use self::file::FileService;
 pub use self::file_revision::FileRevisionService;
+pub use self::forum_category::ForumCategoryService;
+pub use self::forum_post::ForumPostService;
+pub use self::forum_thread::ForumThreadService;
 pub use self::job::JobService;
 pub use self::link::LinkService;
+pub use self::membership::MembershipService;
+pub use self::message::MessageService;
+pub use self::mfa::MfaService;
 pub use self::outdate::OutdateService;
 pub use self::page::PageService;
 pub use self::parent::ParentService;
+pub use self::password::PasswordService;
+pub use self::redirect::RedirectService;
 pub use self::render::RenderService;
 pub use self::revision::RevisionService;
+pub use self::role::RoleService;
 pub use self::score::ScoreService;
+pub use self::search::SearchService;
 pub use self::site::SiteService;
+pub use self::site_settings::SiteSettingsService;
+pub use self::tag::TagService;
 pub use self::text::TextService;
+pub use self::token::TokenService;
 pub use self::user::UserService;
 pub use self::vote::VoteService;
+pub use self::vote_analysis::VoteAnalysisService;
 
 /// Extension trait to retrieve service objects from an `ApiRequest`.
 pub trait RequestFetchService {
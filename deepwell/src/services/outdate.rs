@@ -19,6 +19,7 @@
  */
 
 use super::prelude::*;
+use crate::models::page_connection_missing::{self, Entity as PageConnectionMissing};
 use crate::services::{JobService, LinkService, PageService};
 use crate::web::{split_category_name, ConnectionType};
 
@@ -104,6 +105,32 @@ impl OutdateService {
         Ok(())
     }
 
+    /// Queues pages which have a missing connection to this slug.
+    ///
+    /// This is used when a slot is filled by something other than a page
+    /// being created there, such as an alias.
+    pub async fn outdate_missing_links(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        slug: &str,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let ids = PageConnectionMissing::find()
+            .filter(
+                Condition::all()
+                    .add(page_connection_missing::Column::ToSiteId.eq(site_id))
+                    .add(page_connection_missing::Column::ToPageSlug.eq(slug)),
+            )
+            .all(txn)
+            .await?
+            .into_iter()
+            .map(|connection| (site_id, connection.from_page_id))
+            .collect::<Vec<_>>();
+
+        Self::outdate(ids);
+        Ok(())
+    }
+
     pub async fn outdate_outgoing_includes(
         ctx: &ServiceContext<'_>,
         site_id: i64,
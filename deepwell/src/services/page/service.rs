@@ -22,11 +22,15 @@ use super::prelude::*;
 use crate::json_utils::json_to_string_list;
 use crate::models::page::{self, Entity as Page, Model as PageModel};
 use crate::models::page_category::Model as PageCategoryModel;
+use crate::services::permission::{Action, PermissionService};
 use crate::services::revision::{
     CreateFirstRevision, CreateFirstRevisionOutput, CreateResurrectionRevision,
     CreateRevision, CreateRevisionBody, CreateRevisionOutput, CreateTombstoneRevision,
 };
-use crate::services::{CategoryService, RevisionService, TextService};
+use crate::services::{
+    CategoryService, ForumThreadService, RedirectService, RevisionService, SiteService,
+    SiteSettingsService, TagService, TextService,
+};
 use crate::web::{get_category_name, trim_default};
 use wikidot_normalize::normalize;
 
@@ -51,10 +55,14 @@ impl PageService {
         normalize(&mut slug);
         Self::check_conflicts(ctx, site_id, &slug, "create").await?;
 
+        // Check before creating the category, so it cannot be created without access
+        let category_name = get_category_name(&slug);
+        Self::check_target_category(ctx, site_id, category_name, user_id, Action::Create)
+            .await?;
+
         // Create category if not already present
         let PageCategoryModel { category_id, .. } =
-            CategoryService::get_or_create(ctx, site_id, get_category_name(&slug))
-                .await?;
+            CategoryService::get_or_create(ctx, site_id, category_name).await?;
 
         // Insert page
         let model = page::ActiveModel {
@@ -65,6 +73,9 @@ impl PageService {
         };
         let page = model.insert(txn).await?;
 
+        // A page here takes precedence over any redirect
+        RedirectService::remove(ctx, site_id, &slug).await?;
+
         // Commit first revision
         let revision_input = CreateFirstRevision {
             user_id,
@@ -104,7 +115,25 @@ impl PageService {
         }: EditPage,
     ) -> Result<Option<EditPageOutput>> {
         let txn = ctx.transaction();
-        let PageModel { page_id, .. } = Self::get(ctx, site_id, reference).await?;
+        let PageModel {
+            page_id,
+            page_category_id,
+            ..
+        } = Self::get(ctx, site_id, reference).await?;
+
+        PermissionService::check_category(
+            ctx,
+            site_id,
+            page_category_id,
+            user_id,
+            Action::Edit,
+        )
+        .await?;
+
+        // Check tags against the site's allowed list
+        if let ProvidedValue::Set(ref tags) = tags {
+            TagService::validate(ctx, site_id, tags).await?;
+        }
 
         // Get latest revision
         let last_revision = RevisionService::get_latest(ctx, site_id, page_id).await?;
@@ -158,13 +187,22 @@ impl PageService {
         mut new_slug: String,
     ) -> Result<MovePageOutput> {
         let txn = ctx.transaction();
-
         let PageModel {
             page_id,
+            page_category_id: old_category_id,
             slug: old_slug,
             ..
         } = Self::get(ctx, site_id, reference).await?;
 
+        PermissionService::check_category(
+            ctx,
+            site_id,
+            old_category_id,
+            user_id,
+            Action::Move,
+        )
+        .await?;
+
         // Check that a move is actually taking place,
         // and that a page with that slug doesn't already exist.
         normalize(&mut new_slug);
@@ -173,12 +211,17 @@ impl PageService {
             return Err(Error::BadRequest);
         }
 
+        Self::check_landing_page(ctx, site_id, &old_slug, "move").await?;
         Self::check_conflicts(ctx, site_id, &new_slug, "move").await?;
 
+        // Moving into a category must be permitted there too
+        let category_name = get_category_name(&new_slug);
+        Self::check_target_category(ctx, site_id, category_name, user_id, Action::Move)
+            .await?;
+
         // Create category if not already present
         let PageCategoryModel { category_id, .. } =
-            CategoryService::get_or_create(ctx, site_id, get_category_name(&new_slug))
-                .await?;
+            CategoryService::get_or_create(ctx, site_id, category_name).await?;
 
         // Get latest revision
         let last_revision = RevisionService::get_latest(ctx, site_id, page_id).await?;
@@ -211,6 +254,13 @@ impl PageService {
 
         model.update(txn).await?;
 
+        // Leave a redirect behind at the old slug, and
+        // remove any redirect where the page now is.
+        try_join!(
+            RedirectService::remove(ctx, site_id, &new_slug),
+            RedirectService::create_for_move(ctx, site_id, page_id, &old_slug),
+        )?;
+
         // Build and return
 
         match revision_output {
@@ -242,7 +292,23 @@ impl PageService {
         }: DeletePage,
     ) -> Result<DeletePageOutput> {
         let txn = ctx.transaction();
-        let PageModel { page_id, .. } = Self::get(ctx, site_id, reference).await?;
+        let PageModel {
+            page_id,
+            page_category_id,
+            slug,
+            discussion_thread_id,
+            ..
+        } = Self::get(ctx, site_id, reference).await?;
+
+        PermissionService::check_category(
+            ctx,
+            site_id,
+            page_category_id,
+            user_id,
+            Action::Delete,
+        )
+        .await?;
+        Self::check_landing_page(ctx, site_id, &slug, "delete").await?;
 
         // Get latest revision
         let last_revision = RevisionService::get_latest(ctx, site_id, page_id).await?;
@@ -268,8 +334,11 @@ impl PageService {
             ..Default::default()
         };
 
-        // Update and return
         model.update(txn).await?;
+
+        // Archive the page's discussion
+        ForumThreadService::set_page_archived(ctx, discussion_thread_id, true).await?;
+
         Ok((output, page_id).into())
     }
 
@@ -303,6 +372,15 @@ impl PageService {
             return Err(Error::BadRequest);
         }
 
+        PermissionService::check_category(
+            ctx,
+            site_id,
+            page.page_category_id,
+            user_id,
+            Action::Delete,
+        )
+        .await?;
+
         Self::check_conflicts(ctx, site_id, &slug, "restore").await?;
 
         // Create category if not already present
@@ -336,8 +414,16 @@ impl PageService {
             ..Default::default()
         };
 
-        // Update and return
         model.update(txn).await?;
+
+        // A page here takes precedence over any redirect
+        RedirectService::remove(ctx, site_id, &slug).await?;
+
+        // Reopen the page's discussion
+        ForumThreadService::set_page_archived(ctx, page.discussion_thread_id, false)
+            .await?;
+
+        // Build and return
         Ok((output, slug).into())
     }
 
@@ -359,6 +445,20 @@ impl PageService {
         }: RollbackPage,
     ) -> Result<Option<EditPageOutput>> {
         let txn = ctx.transaction();
+        let page = Self::get_direct(ctx, page_id).await?;
+        if page.site_id != site_id {
+            tide::log::warn!("Page's site ID and passed site ID do not match");
+            return Err(Error::NotFound);
+        }
+
+        PermissionService::check_category(
+            ctx,
+            site_id,
+            page.page_category_id,
+            user_id,
+            Action::Edit,
+        )
+        .await?;
 
         // Get target revision and latest revision
         let (target_revision, last_revision) = try_join!(
@@ -478,6 +578,63 @@ impl PageService {
         Ok(page)
     }
 
+    /// Gets a page, following any redirect or alias if there is no page at this slug.
+    ///
+    /// This is for reading pages. Changes should use `get()`, which only
+    /// matches the exact slug, so they never act on a page found by redirect.
+    pub async fn get_resolved(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        reference: Reference<'_>,
+    ) -> Result<PageModel> {
+        match Self::get_resolved_optional(ctx, site_id, reference).await? {
+            Some(page) => Ok(page),
+            None => Err(Error::NotFound),
+        }
+    }
+
+    /// Like `get_resolved()`, but returns `None` if the page is missing.
+    ///
+    /// Callers can tell a redirect was followed since the
+    /// returned page's slug will differ from the one requested.
+    pub async fn get_resolved_optional(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        reference: Reference<'_>,
+    ) -> Result<Option<PageModel>> {
+        match (
+            Self::get_optional(ctx, site_id, reference).await?,
+            reference,
+        ) {
+            (None, Reference::Slug(slug)) => {
+                RedirectService::resolve(ctx, site_id, trim_default(slug)).await
+            }
+            (page, _) => Ok(page),
+        }
+    }
+
+    /// Gets all extant pages in a site with one of the given slugs.
+    ///
+    /// Slugs which do not correspond to a page are ignored.
+    pub async fn get_many(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        slugs: &[String],
+    ) -> Result<Vec<PageModel>> {
+        let txn = ctx.transaction();
+        let pages = Page::find()
+            .filter(
+                Condition::all()
+                    .add(page::Column::Slug.is_in(slugs.iter().map(|s| trim_default(s))))
+                    .add(page::Column::SiteId.eq(site_id))
+                    .add(page::Column::DeletedAt.is_null()),
+            )
+            .all(txn)
+            .await?;
+
+        Ok(pages)
+    }
+
     #[inline]
     pub async fn exists_direct(ctx: &ServiceContext<'_>, page_id: i64) -> Result<bool> {
         Self::get_direct_optional(ctx, page_id)
@@ -550,6 +707,32 @@ impl PageService {
         Ok(pages)
     }
 
+    /// Gets extant pages in a site, in order of their IDs.
+    ///
+    /// The `start_id` argument gives the ID to start after, exclusive.
+    /// If `0`, then it starts from the first page.
+    pub async fn get_range(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        start_id: i64,
+        limit: u64,
+    ) -> Result<Vec<PageModel>> {
+        let txn = ctx.transaction();
+        let pages = Page::find()
+            .filter(
+                Condition::all()
+                    .add(page::Column::SiteId.eq(site_id))
+                    .add(page::Column::PageId.gt(start_id))
+                    .add(page::Column::DeletedAt.is_null()),
+            )
+            .order_by_asc(page::Column::PageId)
+            .limit(limit)
+            .all(txn)
+            .await?;
+
+        Ok(pages)
+    }
+
     /// Checks to see if a page already exists at the slug specified.
     ///
     /// If so, this method fails with `Error::Conflict`. Otherwise it returns nothing.
@@ -586,4 +769,63 @@ impl PageService {
             }
         }
     }
+
+    /// Checks that the user may perform this action on a page in the given category.
+    ///
+    /// If the category does not exist yet, it has no overrides,
+    /// so the user's site-wide permissions apply.
+    async fn check_target_category(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        category_slug: &str,
+        user_id: i64,
+        action: Action,
+    ) -> Result<()> {
+        match CategoryService::get_optional(ctx, site_id, Reference::from(category_slug))
+            .await?
+        {
+            Some(category) => {
+                PermissionService::check_category(
+                    ctx,
+                    site_id,
+                    category.category_id,
+                    user_id,
+                    action,
+                )
+                .await
+            }
+            None => PermissionService::check_site(ctx, site_id, user_id, action).await,
+        }
+    }
+
+    /// Checks that the page at this slug is not the site's private landing page.
+    ///
+    /// On private sites, visitors without access are sent to the landing page
+    /// given in the site settings, so it cannot be moved away or deleted.
+    /// If so, this method fails with `Error::Conflict`.
+    async fn check_landing_page(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        slug: &str,
+        action: &str,
+    ) -> Result<()> {
+        let site = SiteService::get(ctx, Reference::Id(site_id)).await?;
+        if !site.private {
+            return Ok(());
+        }
+
+        let settings = SiteSettingsService::get(ctx, site_id).await?;
+        if trim_default(slug) == trim_default(&settings.private_landing_page) {
+            tide::log::error!(
+                "Page with slug '{}' is the private landing page for site ID {}, cannot {}",
+                slug,
+                site_id,
+                action,
+            );
+
+            return Err(Error::Conflict);
+        }
+
+        Ok(())
+    }
 }
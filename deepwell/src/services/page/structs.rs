@@ -21,6 +21,7 @@
 use super::prelude::*;
 use crate::models::sea_orm_active_enums::PageRevisionType;
 use crate::services::revision::CreateRevisionOutput;
+use crate::services::score::{ScoreType, VoteType};
 use ftml::parsing::ParseWarning;
 use sea_orm::entity::prelude::DateTimeWithTimeZone;
 use serde_json::Value as JsonValue;
@@ -56,7 +57,9 @@ pub struct GetPageOutput<'a> {
     pub site_id: i64,
     pub page_category_id: i64,
     pub page_category_slug: &'a str,
+    pub redirected_from: Option<&'a str>,
     pub discussion_thread_id: Option<i64>,
+    pub discussion_post_count: i32,
     pub revision_id: i64,
     pub revision_type: PageRevisionType,
     pub revision_created_at: DateTimeWithTimeZone,
@@ -64,6 +67,8 @@ pub struct GetPageOutput<'a> {
     pub revision_user_id: i64,
     pub wikitext: Option<String>,
     pub compiled_html: Option<String>,
+    pub compiled_styles: Option<&'a JsonValue>,
+    pub compiled_meta: Option<&'a JsonValue>,
     pub compiled_at: DateTimeWithTimeZone,
     pub compiled_generator: &'a str,
     pub revision_comments: &'a str,
@@ -73,6 +78,8 @@ pub struct GetPageOutput<'a> {
     pub slug: &'a str,
     pub tags: &'a JsonValue, // TODO: replace with &[&str]
     pub rating: f64,
+    pub score_type: ScoreType,
+    pub vote_type: VoteType,
 }
 
 #[derive(Deserialize, Debug, Default)]
@@ -19,8 +19,17 @@
  */
 
 use super::prelude::*;
+use crate::models::page::{self, Entity as Page, Model as PageModel};
 use crate::models::page_parent::{self, Entity as PageParent, Model as PageParentModel};
+use crate::services::permission::{Action, PermissionService};
 use crate::services::PageService;
+use sea_orm::{DatabaseBackend, FromQueryResult, QueryTrait, Statement};
+
+/// The maximum number of levels to descend when fetching a page tree.
+///
+/// This applies even if no depth limit is requested,
+/// so that a deep hierarchy cannot produce an unbounded query.
+const MAX_TREE_DEPTH: u32 = 32;
 
 #[derive(Debug)]
 pub struct ParentService;
@@ -38,6 +47,7 @@ impl ParentService {
         site_id: i64,
         parent_page_ref: Reference<'_>,
         child_page_ref: Reference<'_>,
+        EditParent { user_id }: EditParent,
     ) -> Result<bool> {
         let txn = ctx.transaction();
 
@@ -46,11 +56,32 @@ impl ParentService {
             PageService::get(ctx, site_id, child_page_ref),
         )?;
 
+        PermissionService::check_category(
+            ctx,
+            site_id,
+            child_page.page_category_id,
+            user_id,
+            Action::Edit,
+        )
+        .await?;
+
         // Check if the two pages are the same
         if parent_page.page_id == child_page.page_id {
             return Err(Error::Conflict);
         }
 
+        // Check if this relationship would create a cycle,
+        // that is, if the parent is already under the child
+        if Self::is_descendant(ctx, child_page.page_id, parent_page.page_id).await? {
+            tide::log::error!(
+                "Page ID {} is already a descendant of page ID {}, cannot make it a parent",
+                parent_page.page_id,
+                child_page.page_id,
+            );
+
+            return Err(Error::Conflict);
+        }
+
         // Check if this relationship already exists
         let relationship =
             PageParent::find_by_id((parent_page.page_id, child_page.page_id))
@@ -85,6 +116,7 @@ impl ParentService {
         site_id: i64,
         parent_page_ref: Reference<'_>,
         child_page_ref: Reference<'_>,
+        EditParent { user_id }: EditParent,
     ) -> Result<bool> {
         let txn = ctx.transaction();
 
@@ -93,6 +125,15 @@ impl ParentService {
             PageService::get(ctx, site_id, child_page_ref),
         )?;
 
+        PermissionService::check_category(
+            ctx,
+            site_id,
+            child_page.page_category_id,
+            user_id,
+            Action::Edit,
+        )
+        .await?;
+
         let rows_deleted =
             PageParent::delete_by_id((parent_page.page_id, child_page.page_id))
                 .exec(txn)
@@ -155,7 +196,7 @@ impl ParentService {
         relationship_type: ParentalRelationshipType,
     ) -> Result<Vec<PageParentModel>> {
         let txn = ctx.transaction();
-        let page = PageService::get(ctx, site_id, reference).await?;
+        let page = PageService::get_resolved(ctx, site_id, reference).await?;
         let column = match relationship_type {
             ParentalRelationshipType::Parent => page_parent::Column::ParentPageId,
             ParentalRelationshipType::Child => page_parent::Column::ChildPageId,
@@ -169,6 +210,190 @@ impl ParentService {
         Ok(models)
     }
 
+    /// Gets the chain of ancestors above this page, for use in breadcrumbs.
+    ///
+    /// Where a page has multiple parents, the oldest relationship is followed.
+    /// The results are ordered from the top of the hierarchy down to the
+    /// page's immediate parent, so the page itself is not included.
+    /// Deleted pages are skipped, but the chain continues above them.
+    pub async fn get_ancestors(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        reference: Reference<'_>,
+    ) -> Result<Vec<PageTreeNode>> {
+        let txn = ctx.transaction();
+        let page = PageService::get_resolved(ctx, site_id, reference).await?;
+        let mut ancestors =
+            PageTreeNode::find_by_statement(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                r"
+                WITH RECURSIVE chain AS (
+                    SELECT
+                        $1::BIGINT AS page_id,
+                        0 AS depth,
+                        ARRAY[$1::BIGINT] AS path
+                    UNION ALL
+                    SELECT
+                        parent.parent_page_id,
+                        chain.depth + 1,
+                        chain.path || parent.parent_page_id
+                    FROM chain
+                    CROSS JOIN LATERAL (
+                        SELECT page_parent.parent_page_id
+                        FROM page_parent
+                        WHERE page_parent.child_page_id = chain.page_id
+                        ORDER BY page_parent.created_at, page_parent.parent_page_id
+                        LIMIT 1
+                    ) AS parent
+                    WHERE NOT parent.parent_page_id = ANY(chain.path)
+                      AND chain.depth < $2
+                )
+                SELECT
+                    chain.page_id,
+                    LEAD(chain.page_id) OVER (ORDER BY chain.depth) AS parent_page_id,
+                    page.slug,
+                    chain.depth
+                FROM chain
+                JOIN page ON page.page_id = chain.page_id
+                WHERE page.deleted_at IS NULL
+                ORDER BY chain.depth DESC
+                ",
+                vec![page.page_id.into(), (MAX_TREE_DEPTH as i32).into()],
+            ))
+            .all(txn)
+            .await?;
+
+        // Remove the starting page, which is last
+        ancestors.pop();
+        Ok(ancestors)
+    }
+
+    /// Gets all the pages below this one in the hierarchy.
+    ///
+    /// If `depth` is set, then only that many levels are descended,
+    /// so a depth of `1` returns only the page's children.
+    /// Results are ordered by depth, then by slug.
+    pub async fn get_descendants(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        reference: Reference<'_>,
+        depth: Option<u32>,
+    ) -> Result<Vec<PageTreeNode>> {
+        let txn = ctx.transaction();
+        let page = PageService::get_resolved(ctx, site_id, reference).await?;
+        let depth = depth.unwrap_or(MAX_TREE_DEPTH).min(MAX_TREE_DEPTH);
+        let descendants =
+            PageTreeNode::find_by_statement(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                r"
+                WITH RECURSIVE tree AS (
+                    SELECT
+                        child_page_id AS page_id,
+                        parent_page_id,
+                        1 AS depth,
+                        ARRAY[parent_page_id, child_page_id] AS path
+                    FROM page_parent
+                    WHERE parent_page_id = $1
+                    UNION ALL
+                    SELECT
+                        page_parent.child_page_id,
+                        page_parent.parent_page_id,
+                        tree.depth + 1,
+                        tree.path || page_parent.child_page_id
+                    FROM page_parent
+                    JOIN tree ON page_parent.parent_page_id = tree.page_id
+                    WHERE NOT page_parent.child_page_id = ANY(tree.path)
+                      AND tree.depth < $2
+                )
+                SELECT
+                    tree.page_id,
+                    tree.parent_page_id,
+                    page.slug,
+                    tree.depth
+                FROM tree
+                JOIN page ON page.page_id = tree.page_id
+                WHERE page.deleted_at IS NULL
+                ORDER BY tree.depth, page.slug
+                ",
+                vec![page.page_id.into(), (depth as i32).into()],
+            ))
+            .all(txn)
+            .await?;
+
+        Ok(descendants)
+    }
+
+    /// Gets all pages which share at least one parent with this page.
+    ///
+    /// The page itself is not included.
+    pub async fn get_siblings(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        reference: Reference<'_>,
+    ) -> Result<Vec<PageModel>> {
+        let txn = ctx.transaction();
+        let page = PageService::get_resolved(ctx, site_id, reference).await?;
+
+        let parents = PageParent::find()
+            .select_only()
+            .column(page_parent::Column::ParentPageId)
+            .filter(page_parent::Column::ChildPageId.eq(page.page_id))
+            .into_query();
+
+        let siblings = PageParent::find()
+            .select_only()
+            .column(page_parent::Column::ChildPageId)
+            .filter(page_parent::Column::ParentPageId.in_subquery(parents))
+            .into_query();
+
+        let pages = Page::find()
+            .filter(
+                Condition::all()
+                    .add(page::Column::PageId.in_subquery(siblings))
+                    .add(page::Column::PageId.ne(page.page_id))
+                    .add(page::Column::DeletedAt.is_null()),
+            )
+            .order_by_asc(page::Column::Slug)
+            .all(txn)
+            .await?;
+
+        Ok(pages)
+    }
+
+    /// Determines if `page_id` is somewhere below `ancestor_page_id` in the hierarchy.
+    async fn is_descendant(
+        ctx: &ServiceContext<'_>,
+        ancestor_page_id: i64,
+        page_id: i64,
+    ) -> Result<bool> {
+        #[derive(FromQueryResult, Debug)]
+        struct Row {
+            found: bool,
+        }
+
+        let txn = ctx.transaction();
+        let row = Row::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r"
+            WITH RECURSIVE tree AS (
+                SELECT child_page_id AS page_id
+                FROM page_parent
+                WHERE parent_page_id = $1
+                UNION
+                SELECT page_parent.child_page_id
+                FROM page_parent
+                JOIN tree ON page_parent.parent_page_id = tree.page_id
+            )
+            SELECT EXISTS (SELECT 1 FROM tree WHERE page_id = $2) AS found
+            ",
+            vec![ancestor_page_id.into(), page_id.into()],
+        ))
+        .one(txn)
+        .await?;
+
+        Ok(matches!(row, Some(Row { found: true })))
+    }
+
     /// Removes all parent relationships involving this page.
     ///
     /// Whether this page is a parent or a child, this method
@@ -19,6 +19,7 @@
  */
 
 use crate::services::Error;
+use sea_orm::FromQueryResult;
 use std::str::FromStr;
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -47,3 +48,27 @@ impl FromStr for ParentalRelationshipType {
         }
     }
 }
+
+/// The user changing a parental relationship.
+///
+/// Changing a page's parents requires edit permission on the child page.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EditParent {
+    pub user_id: i64,
+}
+
+/// A page within a hierarchy, along with its position relative to another page.
+///
+/// For descendants, `depth` is how many levels below the root page this is,
+/// and `parent_page_id` is the parent through which it was reached.
+/// For ancestors, `depth` is how many levels above the starting page this is,
+/// and `parent_page_id` is the next page further up the chain, if any.
+#[derive(Serialize, FromQueryResult, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PageTreeNode {
+    pub page_id: i64,
+    pub parent_page_id: Option<i64>,
+    pub slug: String,
+    pub depth: i32,
+}
@@ -0,0 +1,40 @@
+/*
+ * services/password/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Hashing and verification of user passwords.
+//!
+//! New passwords are hashed with Argon2id, using the cost parameters set in
+//! the configuration. Hashes produced with older parameters, or bcrypt hashes
+//! imported from Wikidot, are still accepted, but verification reports that
+//! they should be replaced with a fresh hash.
+//!
+//! Hashing is deliberately expensive, so it is performed off of the async
+//! executor threads.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::PasswordService;
+pub use self::structs::*;
@@ -0,0 +1,185 @@
+/*
+ * services/password/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use argon2::password_hash::{
+    self, PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+};
+use argon2::{Algorithm, Argon2, Params, Version, ARGON2ID_IDENT};
+use async_std::task;
+use rand_core::OsRng;
+
+#[derive(Debug)]
+pub struct PasswordService;
+
+impl PasswordService {
+    /// Hashes a password using Argon2id and the configured parameters.
+    pub async fn hash(ctx: &ServiceContext<'_>, password: &Password) -> Result<String> {
+        let params = Self::params(ctx)?;
+        let password = password.clone();
+
+        task::spawn_blocking(move || hash_password(params, password.as_str())).await
+    }
+
+    /// Checks a password against the hash stored for a user.
+    ///
+    /// Both Argon2 and legacy bcrypt hashes are supported. The comparison
+    /// itself is performed in constant time by the respective libraries.
+    pub async fn verify(
+        ctx: &ServiceContext<'_>,
+        password: &Password,
+        hash: &str,
+    ) -> Result<PasswordVerification> {
+        let params = Self::params(ctx)?;
+        let password = password.clone();
+        let hash = str!(hash);
+
+        task::spawn_blocking(move || verify_password(params, password.as_str(), &hash))
+            .await
+    }
+
+    fn params(ctx: &ServiceContext<'_>) -> Result<Params> {
+        let config = ctx.config();
+        let params = Params::new(
+            config.password_memory_cost,
+            config.password_time_cost,
+            config.password_parallelism,
+            None,
+        )
+        .map_err(password_hash::Error::from)?;
+
+        Ok(params)
+    }
+}
+
+// Helpers
+
+fn hash_password(params: Params, password: &str) -> Result<String> {
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = argon2.hash_password(password.as_bytes(), &salt)?;
+    Ok(hash.to_string())
+}
+
+fn verify_password(
+    params: Params,
+    password: &str,
+    hash: &str,
+) -> Result<PasswordVerification> {
+    // Wikidot imports use bcrypt, which are always upgraded
+    if is_bcrypt(hash) {
+        let verification = match bcrypt::verify(password, hash) {
+            Ok(true) => PasswordVerification::ValidNeedsRehash,
+            Ok(false) => PasswordVerification::Invalid,
+            Err(error) => {
+                tide::log::warn!("Stored bcrypt password hash is malformed: {error}");
+                PasswordVerification::Invalid
+            }
+        };
+
+        return Ok(verification);
+    }
+
+    // A stored value which isn't a hash at all can never be verified against,
+    // such rows are converted to bcrypt hashes by a migration.
+    let parsed = match PasswordHash::new(hash) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            tide::log::warn!("Stored password hash cannot be parsed: {error}");
+            return Ok(PasswordVerification::Invalid);
+        }
+    };
+
+    // The verifier uses the parameters stored in the hash itself
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params.clone());
+    match argon2.verify_password(password.as_bytes(), &parsed) {
+        Ok(()) => (),
+        Err(password_hash::Error::Password) => return Ok(PasswordVerification::Invalid),
+        Err(error) => {
+            tide::log::warn!("Stored password hash cannot be verified: {error}");
+            return Ok(PasswordVerification::Invalid);
+        }
+    }
+
+    if needs_rehash(&params, &parsed) {
+        Ok(PasswordVerification::ValidNeedsRehash)
+    } else {
+        Ok(PasswordVerification::Valid)
+    }
+}
+
+fn needs_rehash(params: &Params, hash: &PasswordHash) -> bool {
+    if hash.algorithm != ARGON2ID_IDENT || hash.version != Some(Version::V0x13.into()) {
+        return true;
+    }
+
+    match Params::try_from(hash) {
+        Ok(current) => {
+            current.m_cost() != params.m_cost()
+                || current.t_cost() != params.t_cost()
+                || current.p_cost() != params.p_cost()
+        }
+        Err(_) => true,
+    }
+}
+
+#[inline]
+fn is_bcrypt(hash: &str) -> bool {
+    hash.starts_with("$2")
+}
+
+#[test]
+fn passwords() {
+    let params = Params::new(64, 1, 1, None).unwrap();
+    let hash = hash_password(params.clone(), "hunter2").expect("Unable to hash password");
+
+    macro_rules! check {
+        ($params:expr, $password:expr, $hash:expr, $expected:ident $(,)?) => {{
+            let verification = verify_password($params, $password, $hash)
+                .expect("Unable to verify password");
+
+            assert_eq!(
+                verification,
+                PasswordVerification::$expected,
+                "Actual password verification doesn't match expected",
+            );
+        }};
+    }
+
+    check!(params.clone(), "hunter2", &hash, Valid);
+    check!(params.clone(), "hunter3", &hash, Invalid);
+    check!(params.clone(), "", &hash, Invalid);
+
+    // Changed parameters
+    let stronger = Params::new(128, 2, 1, None).unwrap();
+    check!(stronger.clone(), "hunter2", &hash, ValidNeedsRehash);
+    check!(stronger, "hunter3", &hash, Invalid);
+
+    // Legacy bcrypt hashes
+    let hash = bcrypt::hash("hunter2", 4).expect("Unable to hash bcrypt password");
+    check!(params.clone(), "hunter2", &hash, ValidNeedsRehash);
+    check!(params.clone(), "hunter3", &hash, Invalid);
+
+    // Values which aren't valid hashes
+    check!(params.clone(), "hunter2", "hunter2", Invalid);
+    check!(params.clone(), "hunter2", "$2y$10$not-a-real-hash", Invalid);
+    check!(params.clone(), "hunter2", "$argon2id$v=19$garbage", Invalid);
+    check!(params, "", "", Invalid);
+}
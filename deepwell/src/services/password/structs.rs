@@ -0,0 +1,69 @@
+/*
+ * services/password/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::fmt::{self, Debug};
+
+/// A raw password, as submitted by a user.
+///
+/// The `Debug` implementation hides the contents, so that passwords
+/// are not written to logs when the input structure is printed.
+#[derive(Deserialize, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Password(String);
+
+impl Password {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Password(<redacted>)")
+    }
+}
+
+impl From<String> for Password {
+    #[inline]
+    fn from(password: String) -> Password {
+        Password(password)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PasswordVerification {
+    /// The password does not match the hash.
+    Invalid,
+
+    /// The password matches, and the hash is up-to-date.
+    Valid,
+
+    /// The password matches, but the hash uses an outdated
+    /// algorithm or parameters and should be replaced.
+    ValidNeedsRehash,
+}
+
+impl PasswordVerification {
+    #[inline]
+    pub fn is_valid(self) -> bool {
+        !matches!(self, PasswordVerification::Invalid)
+    }
+}
@@ -0,0 +1,39 @@
+/*
+ * services/permission/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Checks whether a user may perform an action within a site.
+//!
+//! A user's permissions come from the role they hold in the site (see
+//! `RoleService`). For actions on pages, a category may override the role's
+//! site-wide permission in either direction. Roles with the manage permission
+//! may do anything, regardless of overrides. Banned users may do nothing.
+//!
+//! A denied action fails with `Error::PermissionDenied`, naming the action.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::PermissionService;
+pub use self::structs::*;
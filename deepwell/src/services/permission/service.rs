@@ -0,0 +1,88 @@
+/*
+ * services/permission/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::{BlockService, RoleService};
+
+#[derive(Debug)]
+pub struct PermissionService;
+
+impl PermissionService {
+    /// Checks that the user may perform this action anywhere in the site.
+    #[inline]
+    pub async fn check_site(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+        action: Action,
+    ) -> Result<()> {
+        Self::check(ctx, site_id, None, user_id, action).await
+    }
+
+    /// Checks that the user may perform this action on a page in this category.
+    #[inline]
+    pub async fn check_category(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        category_id: i64,
+        user_id: i64,
+        action: Action,
+    ) -> Result<()> {
+        Self::check(ctx, site_id, Some(category_id), user_id, action).await
+    }
+
+    async fn check(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        category_id: Option<i64>,
+        user_id: i64,
+        action: Action,
+    ) -> Result<()> {
+        BlockService::check_banned(ctx, site_id, user_id).await?;
+
+        let role = RoleService::get_user_role(ctx, site_id, user_id).await?;
+        if role.can_manage {
+            return Ok(());
+        }
+
+        let mut allowed = action.allowed_by(&role);
+        if let Some(category_id) = category_id {
+            let permissions =
+                RoleService::get_category_override(ctx, category_id, role.role_id)
+                    .await?;
+
+            if let Some(value) = permissions.and_then(|p| action.overridden_by(&p)) {
+                allowed = value;
+            }
+        }
+
+        if !allowed {
+            tide::log::warn!(
+                "User ID {user_id} with role '{}' cannot {} in site ID {site_id}",
+                role.slug,
+                action.name(),
+            );
+
+            return Err(Error::PermissionDenied(action.name()));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+/*
+ * services/permission/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::models::page_category_permission::Model as PageCategoryPermissionModel;
+use crate::models::site_role::Model as SiteRoleModel;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum Action {
+    Create,
+    Edit,
+    Move,
+    Delete,
+    Vote,
+    Upload,
+    Post,
+    Moderate,
+    Manage,
+}
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Create => "create",
+            Action::Edit => "edit",
+            Action::Move => "move",
+            Action::Delete => "delete",
+            Action::Vote => "vote",
+            Action::Upload => "upload",
+            Action::Post => "post",
+            Action::Moderate => "moderate",
+            Action::Manage => "manage",
+        }
+    }
+
+    /// Whether the role permits this action, before any category overrides.
+    pub fn allowed_by(self, role: &SiteRoleModel) -> bool {
+        match self {
+            Action::Create => role.can_create,
+            Action::Edit => role.can_edit,
+            Action::Move => role.can_move,
+            Action::Delete => role.can_delete,
+            Action::Vote => role.can_vote,
+            Action::Upload => role.can_upload,
+            Action::Post => role.can_post,
+            Action::Moderate => role.can_moderate,
+            Action::Manage => role.can_manage,
+        }
+    }
+
+    /// The category's override for this action, if it has one.
+    ///
+    /// Only actions on pages can be overridden per category.
+    pub fn overridden_by(
+        self,
+        permissions: &PageCategoryPermissionModel,
+    ) -> Option<bool> {
+        match self {
+            Action::Create => permissions.can_create,
+            Action::Edit => permissions.can_edit,
+            Action::Move => permissions.can_move,
+            Action::Delete => permissions.can_delete,
+            Action::Vote => permissions.can_vote,
+            Action::Upload => permissions.can_upload,
+            Action::Post | Action::Moderate | Action::Manage => None,
+        }
+    }
+}
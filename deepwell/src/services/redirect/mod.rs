@@ -0,0 +1,40 @@
+/*
+ * services/redirect/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Redirects and aliases, which allow a page to be reached from other slugs.
+//!
+//! When a page is moved, a redirect is left at its old slug. Users can also
+//! add aliases explicitly. Both point to a page ID rather than to a slug,
+//! so a page which is moved several times has all of its old slugs lead
+//! directly to it, without any chains to follow.
+//!
+//! A page which actually exists at a slug always takes precedence, so any
+//! redirect there is removed when a page is created, moved, or restored to it.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::RedirectService;
+pub use self::structs::*;
@@ -0,0 +1,298 @@
+/*
+ * services/redirect/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::page::{self, Entity as Page, Model as PageModel};
+use crate::models::page_redirect::{
+    self, Entity as PageRedirect, Model as PageRedirectModel,
+};
+use crate::services::permission::{Action, PermissionService};
+use crate::services::{OutdateService, PageService};
+use crate::web::trim_default;
+use std::collections::HashMap;
+use wikidot_normalize::normalize;
+
+#[derive(Debug)]
+pub struct RedirectService;
+
+impl RedirectService {
+    /// Records that a page used to be at the given slug.
+    ///
+    /// Any existing redirect at that slug is replaced.
+    pub async fn create_for_move(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        page_id: i64,
+        old_slug: &str,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let slug = trim_default(old_slug);
+
+        Self::remove(ctx, site_id, slug).await?;
+
+        let model = page_redirect::ActiveModel {
+            site_id: Set(site_id),
+            slug: Set(str!(slug)),
+            page_id: Set(page_id),
+            is_alias: Set(false),
+            created_at: Set(now()),
+        };
+        model.insert(txn).await?;
+
+        Ok(())
+    }
+
+    /// Adds an alias, so the page can also be reached at this slug.
+    ///
+    /// This fails with `Error::Conflict` if there is a page at the slug,
+    /// or if the slug is already an alias for a different page.
+    /// A redirect left by a previous move is replaced.
+    pub async fn create_alias(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        reference: Reference<'_>,
+        CreateAlias { mut slug, user_id }: CreateAlias,
+    ) -> Result<PageRedirectModel> {
+        let txn = ctx.transaction();
+        let PageModel {
+            page_id,
+            page_category_id,
+            ..
+        } = PageService::get(ctx, site_id, reference).await?;
+
+        PermissionService::check_category(
+            ctx,
+            site_id,
+            page_category_id,
+            user_id,
+            Action::Move,
+        )
+        .await?;
+
+        normalize(&mut slug);
+        let slug = str!(trim_default(&slug));
+
+        // Check that no page is already here.
+        //
+        // We query directly, since PageService would follow redirects.
+        let existing_page = Page::find()
+            .filter(
+                Condition::all()
+                    .add(page::Column::SiteId.eq(site_id))
+                    .add(page::Column::Slug.eq(slug.as_str()))
+                    .add(page::Column::DeletedAt.is_null()),
+            )
+            .one(txn)
+            .await?;
+
+        if existing_page.is_some() {
+            tide::log::error!(
+                "Page with slug '{}' already exists on site ID {}, cannot alias",
+                slug,
+                site_id,
+            );
+
+            return Err(Error::Conflict);
+        }
+
+        // Check any existing redirect
+        if let Some(redirect) = Self::get_optional(ctx, site_id, &slug).await? {
+            if redirect.is_alias {
+                if redirect.page_id == page_id {
+                    return Ok(redirect);
+                }
+
+                tide::log::error!(
+                    "Slug '{}' is already an alias for page ID {} on site ID {}",
+                    slug,
+                    redirect.page_id,
+                    site_id,
+                );
+
+                return Err(Error::Conflict);
+            }
+
+            Self::remove(ctx, site_id, &slug).await?;
+        }
+
+        let model = page_redirect::ActiveModel {
+            site_id: Set(site_id),
+            slug: Set(slug.clone()),
+            page_id: Set(page_id),
+            is_alias: Set(true),
+            created_at: Set(now()),
+        };
+        let redirect = model.insert(txn).await?;
+
+        // Pages linking here no longer have a missing link
+        OutdateService::outdate_missing_links(ctx, site_id, &slug).await?;
+
+        Ok(redirect)
+    }
+
+    /// Deletes the redirect or alias at the given slug on behalf of a user.
+    ///
+    /// See `remove()` for more information.
+    pub async fn delete(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        slug: &str,
+        DeleteRedirect { user_id }: DeleteRedirect,
+    ) -> Result<bool> {
+        PermissionService::check_site(ctx, site_id, user_id, Action::Move).await?;
+        Self::remove(ctx, site_id, slug).await
+    }
+
+    /// Removes the redirect or alias at the given slug, if any.
+    ///
+    /// Pages which linked to the target through this slug are rerendered.
+    ///
+    /// # Returns
+    /// Returns `true` if a redirect was deleted, and
+    /// `false` if there wasn't one.
+    pub async fn remove(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        slug: &str,
+    ) -> Result<bool> {
+        let txn = ctx.transaction();
+        let redirect = match Self::get_optional(ctx, site_id, slug).await? {
+            Some(redirect) => redirect,
+            None => return Ok(false),
+        };
+
+        let page_id = redirect.page_id;
+        let model: page_redirect::ActiveModel = redirect.into();
+        model.delete(txn).await?;
+
+        OutdateService::outdate_incoming_links(ctx, site_id, page_id).await?;
+        Ok(true)
+    }
+
+    pub async fn get_optional(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        slug: &str,
+    ) -> Result<Option<PageRedirectModel>> {
+        let txn = ctx.transaction();
+        let redirect = PageRedirect::find_by_id((site_id, str!(trim_default(slug))))
+            .one(txn)
+            .await?;
+
+        Ok(redirect)
+    }
+
+    /// Gets all redirects and aliases which lead to the given page.
+    pub async fn get_all(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        reference: Reference<'_>,
+    ) -> Result<Vec<PageRedirectModel>> {
+        let txn = ctx.transaction();
+        let PageModel { page_id, .. } = PageService::get(ctx, site_id, reference).await?;
+        let redirects = PageRedirect::find()
+            .filter(page_redirect::Column::PageId.eq(page_id))
+            .order_by_asc(page_redirect::Column::Slug)
+            .all(txn)
+            .await?;
+
+        Ok(redirects)
+    }
+
+    /// Finds the page which the given slug redirects to.
+    ///
+    /// Redirects to deleted pages are not followed.
+    pub async fn resolve(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        slug: &str,
+    ) -> Result<Option<PageModel>> {
+        let txn = ctx.transaction();
+        let redirect = match Self::get_optional(ctx, site_id, slug).await? {
+            Some(redirect) => redirect,
+            None => return Ok(None),
+        };
+
+        let page = Page::find()
+            .filter(
+                Condition::all()
+                    .add(page::Column::PageId.eq(redirect.page_id))
+                    .add(page::Column::SiteId.eq(site_id))
+                    .add(page::Column::DeletedAt.is_null()),
+            )
+            .one(txn)
+            .await?;
+
+        Ok(page)
+    }
+
+    /// Finds the pages which each of the given slugs redirect to.
+    ///
+    /// Slugs without a redirect, or whose page was deleted, are absent from the result.
+    pub async fn resolve_many(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        slugs: &[String],
+    ) -> Result<HashMap<String, PageModel>> {
+        let txn = ctx.transaction();
+        let redirects = PageRedirect::find()
+            .filter(
+                Condition::all()
+                    .add(page_redirect::Column::SiteId.eq(site_id))
+                    .add(
+                        page_redirect::Column::Slug
+                            .is_in(slugs.iter().map(|s| trim_default(s))),
+                    ),
+            )
+            .all(txn)
+            .await?;
+
+        if redirects.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let pages = Page::find()
+            .filter(
+                Condition::all()
+                    .add(
+                        page::Column::PageId
+                            .is_in(redirects.iter().map(|redirect| redirect.page_id)),
+                    )
+                    .add(page::Column::SiteId.eq(site_id))
+                    .add(page::Column::DeletedAt.is_null()),
+            )
+            .all(txn)
+            .await?
+            .into_iter()
+            .map(|page| (page.page_id, page))
+            .collect::<HashMap<_, _>>();
+
+        let resolved = redirects
+            .into_iter()
+            .filter_map(|redirect| {
+                pages
+                    .get(&redirect.page_id)
+                    .map(|page| (redirect.slug, page.clone()))
+            })
+            .collect();
+
+        Ok(resolved)
+    }
+}
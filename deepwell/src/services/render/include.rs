@@ -0,0 +1,258 @@
+/*
+ * services/render/include.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Expansion of messy includes (`[[include-messy]]`) using pages from the database.
+//!
+//! ftml's `Includer` trait is synchronous, so pages cannot be fetched from within it.
+//! Instead the wikitext goes through ftml twice: the first pass only records which
+//! pages were requested, those are all fetched together, and then the second pass
+//! substitutes in their contents.
+
+use super::prelude::*;
+use crate::services::{
+    PageService, RedirectService, RevisionService, SiteService, TextService,
+};
+use crate::web::trim_default;
+use ftml::data::PageRef;
+use ftml::includes::{FetchedPage, IncludeRef, Includer};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use wikidot_normalize::normalize;
+
+/// The wikitext of fetched pages, keyed by site slug and page slug.
+type IncludedPages = HashMap<(String, String), String>;
+
+/// Replaces all messy includes in the wikitext with the contents of those pages.
+///
+/// Returns the new wikitext and the list of pages which were included.
+pub async fn expand_includes(
+    ctx: &ServiceContext<'_>,
+    wikitext: &str,
+    page_info: &PageInfo<'_>,
+    settings: &WikitextSettings,
+) -> Result<(String, Vec<PageRef<'static>>)> {
+    // Find which pages are being included
+    let mut collector = IncludeCollector::default();
+    ftml::include(wikitext, settings, &mut collector, || {
+        Error::IncludeMismatch
+    })?;
+
+    if collector.pages.is_empty() {
+        return Ok((str!(wikitext), vec![]));
+    }
+
+    // Fetch and substitute them
+    let pages = fetch_pages(ctx, &page_info.site, &collector.pages).await?;
+    let includer = PageIncluder {
+        site: &page_info.site,
+        pages: &pages,
+    };
+
+    let (output, included_pages) =
+        ftml::include(wikitext, settings, includer, || Error::IncludeMismatch)?;
+
+    let included_pages = included_pages
+        .iter()
+        .map(|page_ref| page_ref.to_owned())
+        .collect();
+
+    Ok((output, included_pages))
+}
+
+async fn fetch_pages(
+    ctx: &ServiceContext<'_>,
+    current_site: &str,
+    page_refs: &[PageRef<'_>],
+) -> Result<IncludedPages> {
+    // Group requested slugs by site, so each site is only queried once
+    let mut requested: HashMap<String, Vec<String>> = HashMap::new();
+    for page_ref in page_refs {
+        let (site_slug, page_slug) = include_key(current_site, page_ref);
+        requested.entry(site_slug).or_default().push(page_slug);
+    }
+
+    let mut included = HashMap::new();
+    for (site_slug, page_slugs) in requested {
+        // Pages on private sites can only be included within that site
+        let site_id =
+            match SiteService::get_optional(ctx, Reference::from(site_slug.as_str()))
+                .await?
+            {
+                Some(site) if site.deleted => continue,
+                Some(site) if site.private && site_slug != current_site => continue,
+                Some(site) => site.site_id,
+                None => continue,
+            };
+
+        let mut pages = PageService::get_many(ctx, site_id, &page_slugs)
+            .await?
+            .into_iter()
+            .map(|page| (page.slug.clone(), page))
+            .collect::<HashMap<_, _>>();
+
+        // Follow redirects and aliases for any slugs without a page
+        let missing = page_slugs
+            .iter()
+            .filter(|slug| !pages.contains_key(*slug))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            pages.extend(RedirectService::resolve_many(ctx, site_id, &missing).await?);
+        }
+
+        // Map each requested slug to its page
+        let targets = page_slugs
+            .into_iter()
+            .filter_map(|slug| pages.get(&slug).map(|page| (slug, page.clone())))
+            .collect::<Vec<_>>();
+
+        let mut page_ids = targets
+            .iter()
+            .map(|(_, page)| page.page_id)
+            .collect::<Vec<_>>();
+
+        page_ids.sort_unstable();
+        page_ids.dedup();
+
+        let revisions = RevisionService::get_latest_many(ctx, site_id, &page_ids).await?;
+        let hashes = revisions
+            .iter()
+            .map(|revision| revision.wikitext_hash.clone())
+            .collect::<Vec<_>>();
+
+        let wikitexts = TextService::get_many(ctx, &hashes).await?;
+        let revisions = revisions
+            .iter()
+            .map(|revision| (revision.page_id, &revision.wikitext_hash))
+            .collect::<HashMap<_, _>>();
+
+        // Several slugs may refer to the same page, so the wikitext is cloned
+        for (slug, page) in targets {
+            let wikitext = revisions
+                .get(&page.page_id)
+                .and_then(|hash| wikitexts.get(*hash));
+
+            if let Some(wikitext) = wikitext {
+                included.insert((site_slug.clone(), slug), wikitext.clone());
+            }
+        }
+    }
+
+    Ok(included)
+}
+
+/// Gets the normalized site and page slug for a page reference.
+fn include_key(current_site: &str, page_ref: &PageRef) -> (String, String) {
+    let site_slug = page_ref.site().unwrap_or(current_site);
+    let mut page_slug = str!(page_ref.page());
+    normalize(&mut page_slug);
+
+    (str!(site_slug), str!(trim_default(&page_slug)))
+}
+
+/// Records requested pages without substituting anything.
+#[derive(Debug, Default)]
+struct IncludeCollector {
+    pages: Vec<PageRef<'static>>,
+}
+
+impl<'t> Includer<'t> for &mut IncludeCollector {
+    type Error = Error;
+
+    fn include_pages(
+        &mut self,
+        includes: &[IncludeRef<'t>],
+    ) -> Result<Vec<FetchedPage<'t>>> {
+        let fetched = includes
+            .iter()
+            .map(|include| {
+                self.pages.push(include.page_ref().to_owned());
+
+                FetchedPage {
+                    page_ref: include.page_ref().clone(),
+                    content: Some(Cow::Borrowed("")),
+                }
+            })
+            .collect();
+
+        Ok(fetched)
+    }
+
+    fn no_such_include(&mut self, _: &PageRef<'t>) -> Result<Cow<'t, str>> {
+        Ok(Cow::Borrowed(""))
+    }
+}
+
+/// Substitutes pages which have already been fetched.
+#[derive(Debug)]
+struct PageIncluder<'a> {
+    site: &'a str,
+    pages: &'a IncludedPages,
+}
+
+impl<'t> Includer<'t> for PageIncluder<'_> {
+    type Error = Error;
+
+    fn include_pages(
+        &mut self,
+        includes: &[IncludeRef<'t>],
+    ) -> Result<Vec<FetchedPage<'t>>> {
+        let fetched = includes
+            .iter()
+            .map(|include| {
+                let page_ref = include.page_ref();
+                let content = self
+                    .pages
+                    .get(&include_key(self.site, page_ref))
+                    .map(|wikitext| Cow::Owned(wikitext.clone()));
+
+                FetchedPage {
+                    page_ref: page_ref.clone(),
+                    content,
+                }
+            })
+            .collect();
+
+        Ok(fetched)
+    }
+
+    fn no_such_include(&mut self, page_ref: &PageRef<'t>) -> Result<Cow<'t, str>> {
+        // TODO localize
+        Ok(Cow::Owned(format!(
+            "[[div class=\"error-block\"]]\nIncluded page \"{page_ref}\" does not exist.\n[[/div]]",
+        )))
+    }
+}
+
+#[test]
+fn keys() {
+    macro_rules! check {
+        ($page_ref:expr, $site_slug:expr, $page_slug:expr $(,)?) => {{
+            let page_ref = PageRef::parse($page_ref).expect("Invalid page reference");
+            let key = include_key("my-site", &page_ref);
+            assert_eq!(key, (str!($site_slug), str!($page_slug)));
+        }};
+    }
+
+    check!("component:Theme", "my-site", "component:theme");
+    check!("_default:Start", "my-site", "start");
+    check!(":other-site:Some Page", "other-site", "some-page");
+}
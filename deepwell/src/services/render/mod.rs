@@ -27,11 +27,13 @@ mod prelude {
         info::VERSION,
         parsing::ParseWarning,
         render::html::{HtmlOutput, HtmlRender},
+        render::text::TextRender,
         render::Render,
         settings::WikitextSettings,
     };
 }
 
+mod include;
 mod service;
 mod structs;
 
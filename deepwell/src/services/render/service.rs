@@ -18,6 +18,7 @@
  * along with this program. If not, see <http://www.gnu.org/licenses/>.
  */
 
+use super::include::expand_includes;
 use super::prelude::*;
 use crate::services::TextService;
 
@@ -27,29 +28,43 @@ pub struct RenderService;
 impl RenderService {
     pub async fn render(
         ctx: &ServiceContext<'_>,
-        mut wikitext: String,
+        wikitext: String,
         page_info: &PageInfo<'_>,
         settings: &WikitextSettings,
     ) -> Result<RenderOutput> {
         let compiled_generator = VERSION.clone();
 
+        // Substitute messy includes
+        let (mut wikitext, included_pages) =
+            expand_includes(ctx, &wikitext, page_info, settings).await?;
+
         // Run ftml to parse and render
-        // TODO include
         ftml::preprocess(&mut wikitext);
         let tokens = ftml::tokenize(&wikitext);
         let result = ftml::parse(&tokens, page_info, settings);
         let (tree, warnings) = result.into();
-        let html_output = HtmlRender.render(&tree, page_info, settings);
+        let mut html_output = HtmlRender.render(&tree, page_info, settings);
+        let text_output = TextRender.render(&tree, page_info, settings);
+
+        // Messy includes are gone by parse time, so add them to the backlinks here
+        html_output.backlinks.included_pages.extend(included_pages);
 
         // Insert compiled HTML into text table
         let compiled_hash = TextService::create(ctx, html_output.body.clone()).await?;
 
+        // Serialize page styles and meta tags, which are stored in the revision
+        let compiled_styles = serde_json::to_value(&html_output.styles)?;
+        let compiled_meta = serde_json::to_value(&html_output.meta)?;
+
         // Build and return
         Ok(RenderOutput {
             html_output,
+            text_output,
             warnings,
             compiled_hash,
             compiled_generator,
+            compiled_styles,
+            compiled_meta,
         })
     }
 }
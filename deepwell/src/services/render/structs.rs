@@ -20,11 +20,15 @@
 
 use super::prelude::*;
 use crate::hash::Hash;
+use serde_json::Value as JsonValue;
 
 #[derive(Debug)]
 pub struct RenderOutput {
     pub html_output: HtmlOutput,
+    pub text_output: String,
     pub warnings: Vec<ParseWarning>,
     pub compiled_hash: Hash,
     pub compiled_generator: String,
+    pub compiled_styles: JsonValue,
+    pub compiled_meta: JsonValue,
 }
@@ -26,25 +26,22 @@ use crate::models::page_revision::{
     self, Entity as PageRevision, Model as PageRevisionModel,
 };
 use crate::models::sea_orm_active_enums::PageRevisionType;
+use crate::services::audit::{AuditAction, AuditTarget, CreateAuditEntry};
+use crate::services::permission::{Action, PermissionService};
 use crate::services::render::RenderOutput;
+use crate::services::search::IndexPage;
 use crate::services::{
-    LinkService, OutdateService, ParentService, RenderService, ScoreService, SiteService,
-    TextService,
+    AuditService, LinkService, OutdateService, ParentService, RenderService,
+    ScoreService, SearchService, SiteService, TagService, TextService,
 };
 use crate::web::{split_category, split_category_name, FetchDirection};
 use ftml::data::PageInfo;
 use ftml::settings::{WikitextMode, WikitextSettings};
 use ref_map::*;
+use sea_orm::sea_query::Expr;
 use serde_json::json;
-use std::borrow::Cow;
 use std::num::NonZeroI32;
 
-macro_rules! cow {
-    ($s:expr) => {
-        Cow::Borrowed($s.as_ref())
-    };
-}
-
 macro_rules! cow_opt {
     ($s:expr) => {
         $s.ref_map(|s| cow!(s))
@@ -110,11 +107,14 @@ impl RevisionService {
         let mut parser_warnings = None;
         let mut old_slug = None;
         let mut changes = Vec::new();
+        let mut changed_tags = None;
         let PageRevisionModel {
             mut wikitext_hash,
             mut compiled_hash,
             mut compiled_at,
             mut compiled_generator,
+            mut compiled_styles,
+            mut compiled_meta,
             hidden,
             mut title,
             mut alt_title,
@@ -154,6 +154,7 @@ impl RevisionService {
             if !string_list_equals_json(&tags, &new_tags) {
                 changes.push("tags");
                 tags = string_list_to_json(&new_tags)?;
+                changed_tags = Some(new_tags);
             }
         }
 
@@ -207,8 +208,6 @@ impl RevisionService {
             //
             // Since outdating depends on scope (see RevisionTasks),
             // we don't do that right after here.
-            //
-            // TODO: use html_output
             let render_output = Self::render_and_update_links(
                 ctx,
                 site_id,
@@ -222,6 +221,8 @@ impl RevisionService {
             parser_warnings = Some(render_output.warnings);
             replace_hash(&mut compiled_hash, &render_output.compiled_hash);
             compiled_generator = render_output.compiled_generator;
+            compiled_styles = render_output.compiled_styles;
+            compiled_meta = render_output.compiled_meta;
             compiled_at = now();
         }
 
@@ -287,6 +288,8 @@ impl RevisionService {
             compiled_hash: Set(compiled_hash),
             compiled_at: Set(compiled_at),
             compiled_generator: Set(compiled_generator),
+            compiled_styles: Set(compiled_styles),
+            compiled_meta: Set(compiled_meta),
             comments: Set(comments),
             hidden: Set(hidden),
             title: Set(title),
@@ -297,6 +300,12 @@ impl RevisionService {
         };
 
         let PageRevisionModel { revision_id, .. } = model.insert(txn).await?;
+
+        // Keep the tag table in sync with the latest revision
+        if let Some(tags) = changed_tags {
+            TagService::update_page(ctx, site_id, page_id, &tags).await?;
+        }
+
         Ok(Some(CreateRevisionOutput {
             revision_id,
             revision_number,
@@ -342,11 +351,12 @@ impl RevisionService {
         };
 
         let RenderOutput {
-            // TODO: use html_output
-            html_output: _,
             warnings,
             compiled_hash,
             compiled_generator,
+            compiled_styles,
+            compiled_meta,
+            ..
         } = Self::render_and_update_links(ctx, site_id, page_id, wikitext, render_input)
             .await?;
 
@@ -369,6 +379,8 @@ impl RevisionService {
             compiled_hash: Set(compiled_hash.to_vec()),
             compiled_at: Set(now()),
             compiled_generator: Set(compiled_generator),
+            compiled_styles: Set(compiled_styles),
+            compiled_meta: Set(compiled_meta),
             comments: Set(comments),
             hidden: Set(json!([])),
             title: Set(title),
@@ -410,6 +422,8 @@ impl RevisionService {
             compiled_hash,
             compiled_at,
             compiled_generator,
+            compiled_styles,
+            compiled_meta,
             title,
             alt_title,
             slug,
@@ -435,6 +449,8 @@ impl RevisionService {
             compiled_hash: Set(compiled_hash),
             compiled_at: Set(compiled_at),
             compiled_generator: Set(compiled_generator),
+            compiled_styles: Set(compiled_styles),
+            compiled_meta: Set(compiled_meta),
             comments: Set(comments),
             hidden: Set(json!([])),
             title: Set(title),
@@ -513,11 +529,12 @@ impl RevisionService {
 
         let wikitext = TextService::get(ctx, &wikitext_hash).await?;
         let RenderOutput {
-            // TODO: use html_output
-            html_output: _,
             warnings,
             compiled_hash: new_compiled_hash,
             compiled_generator,
+            compiled_styles,
+            compiled_meta,
+            ..
         } = Self::render_and_update_links(ctx, site_id, page_id, wikitext, render_input)
             .await?;
 
@@ -539,6 +556,8 @@ impl RevisionService {
             compiled_hash: Set(compiled_hash),
             compiled_at: Set(now()),
             compiled_generator: Set(compiled_generator),
+            compiled_styles: Set(compiled_styles),
+            compiled_meta: Set(compiled_meta),
             comments: Set(comments),
             hidden: Set(hidden),
             title: Set(title),
@@ -597,6 +616,16 @@ impl RevisionService {
         // Update backlinks
         LinkService::update(ctx, site_id, page_id, &output.html_output.backlinks).await?;
 
+        // Update search index
+        let index_page = IndexPage {
+            title,
+            alt_title,
+            tags,
+            body: &output.text_output,
+        };
+
+        SearchService::update_index(ctx, page_id, index_page).await?;
+
         Ok(output)
     }
 
@@ -624,10 +653,11 @@ impl RevisionService {
             tags: &temp_tags,
         };
 
-        // TODO use html_output
         let RenderOutput {
             compiled_hash,
             compiled_generator,
+            compiled_styles,
+            compiled_meta,
             ..
         } = Self::render_and_update_links(ctx, site_id, page_id, wikitext, render_input)
             .await?;
@@ -639,6 +669,8 @@ impl RevisionService {
             revision_id: Set(revision.revision_id),
             compiled_hash: Set(compiled_hash.to_vec()),
             compiled_generator: Set(compiled_generator),
+            compiled_styles: Set(compiled_styles),
+            compiled_meta: Set(compiled_meta),
             ..Default::default()
         };
 
@@ -661,6 +693,7 @@ impl RevisionService {
         UpdateRevision { user_id, hidden }: UpdateRevision,
     ) -> Result<()> {
         let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Moderate).await?;
 
         // Unfortunately, we cannot do .contains() on Vec<String> because
         // it wans to compare with &String, not &str.
@@ -685,20 +718,43 @@ impl RevisionService {
             return Err(Error::CannotHideLatestRevision);
         }
 
-        // TODO: record revision edit in audit log
-        let _ = user_id;
+        let revision = PageRevision::find()
+            .filter(
+                Condition::all()
+                    .add(page_revision::Column::RevisionId.eq(revision_id))
+                    .add(page_revision::Column::SiteId.eq(site_id))
+                    .add(page_revision::Column::PageId.eq(page_id)),
+            )
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
 
         // Update the revision
 
         let hidden = string_list_to_json(&hidden)?;
         let model = page_revision::ActiveModel {
             revision_id: Set(revision_id),
-            hidden: Set(hidden),
+            hidden: Set(hidden.clone()),
             ..Default::default()
         };
 
-        // Update and return
         model.update(txn).await?;
+
+        // Record the change in the audit log
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(site_id),
+                user_id: Some(user_id),
+                action: AuditAction::RevisionUpdate,
+                target: AuditTarget::Revision(revision_id),
+                before: Some(json!({ "hidden": revision.hidden })),
+                after: Some(json!({ "hidden": hidden })),
+            },
+        )
+        .await?;
+
         Ok(())
     }
 
@@ -725,6 +781,32 @@ impl RevisionService {
         Ok(revision)
     }
 
+    /// Gets the latest revision for each of the given pages.
+    pub async fn get_latest_many(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        page_ids: &[i64],
+    ) -> Result<Vec<PageRevisionModel>> {
+        let txn = ctx.transaction();
+        let revisions = PageRevision::find()
+            .filter(
+                Condition::all()
+                    .add(page_revision::Column::SiteId.eq(site_id))
+                    .add(page_revision::Column::PageId.is_in(page_ids.iter().copied()))
+                    .add(Expr::cust(
+                        "revision_number = (
+                            SELECT MAX(latest.revision_number)
+                            FROM page_revision AS latest
+                            WHERE latest.page_id = page_revision.page_id
+                        )",
+                    )),
+            )
+            .all(txn)
+            .await?;
+
+        Ok(revisions)
+    }
+
     pub async fn get_optional(
         ctx: &ServiceContext<'_>,
         site_id: i64,
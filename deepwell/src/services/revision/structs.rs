@@ -22,6 +22,7 @@ use super::prelude::*;
 use crate::models::sea_orm_active_enums::PageRevisionType;
 use ftml::parsing::ParseWarning;
 use sea_orm::prelude::DateTimeWithTimeZone;
+use serde_json::Value as JsonValue;
 use std::num::NonZeroI32;
 
 #[derive(Deserialize, Debug)]
@@ -122,6 +123,8 @@ pub struct PageRevisionModelFiltered {
     pub changes: Vec<String>,
     pub wikitext: Option<String>,
     pub compiled_html: Option<String>,
+    pub compiled_styles: Option<JsonValue>,
+    pub compiled_meta: Option<JsonValue>,
     pub compiled_at: DateTimeWithTimeZone,
     pub compiled_generator: String,
     pub comments: Option<String>,
@@ -0,0 +1,39 @@
+/*
+ * services/role/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Roles within a site, and which users hold them.
+//!
+//! Every site has four built-in roles: guest, member, moderator, and admin.
+//! Sites may also define custom roles with their own set of permissions.
+//! A user holds at most one role per site, and users without one are guests.
+//!
+//! Page categories may override what a role is allowed to do with pages in them.
+//! See `PermissionService` for how these are combined when checking an action.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::RoleService;
+pub use self::structs::*;
@@ -0,0 +1,665 @@
+/*
+ * services/role/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::page_category_permission::{
+    self, Entity as PageCategoryPermission, Model as PageCategoryPermissionModel,
+};
+use crate::models::site_role::{self, Entity as SiteRole, Model as SiteRoleModel};
+use crate::models::site_user_role::{
+    self, Entity as SiteUserRole, Model as SiteUserRoleModel,
+};
+use crate::services::audit::{AuditAction, AuditTarget, CreateAuditEntry};
+use crate::services::permission::{Action, PermissionService};
+use crate::services::{AuditService, CategoryService};
+use wikidot_normalize::normalize;
+
+#[derive(Debug)]
+pub struct RoleService;
+
+impl RoleService {
+    /// Creates the built-in roles for a newly-created site.
+    pub async fn create_builtin(ctx: &ServiceContext<'_>, site_id: i64) -> Result<()> {
+        let txn = ctx.transaction();
+
+        for role in BuiltinRole::ALL {
+            let model = build_role(
+                site_id,
+                str!(role.slug()),
+                str!(role.name()),
+                true,
+                role.default_permissions(),
+            );
+
+            model.insert(txn).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates a custom role for a site.
+    pub async fn create(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        CreateRole {
+            mut slug,
+            name,
+            permissions,
+            user_id,
+        }: CreateRole,
+    ) -> Result<SiteRoleModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Manage).await?;
+
+        normalize(&mut slug);
+        if slug.is_empty() {
+            tide::log::error!("Cannot create role with empty slug");
+            return Err(Error::BadRequest);
+        }
+
+        let existing = SiteRole::find()
+            .filter(
+                Condition::all()
+                    .add(site_role::Column::SiteId.eq(site_id))
+                    .add(site_role::Column::Slug.eq(slug.as_str())),
+            )
+            .one(txn)
+            .await?;
+
+        if existing.is_some() {
+            tide::log::error!("Role '{slug}' already exists in site ID {site_id}");
+            return Err(Error::Conflict);
+        }
+
+        let model = build_role(site_id, slug, name, false, permissions);
+        let role = model.insert(txn).await?;
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(site_id),
+                user_id: Some(user_id),
+                action: AuditAction::RoleCreate,
+                target: AuditTarget::Role(role.role_id),
+                before: None,
+                after: Some(serde_json::to_value(&role)?),
+            },
+        )
+        .await?;
+
+        Ok(role)
+    }
+
+    pub async fn update(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        role_id: i64,
+        input: UpdateRole,
+    ) -> Result<SiteRoleModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, input.user_id, Action::Manage)
+            .await?;
+
+        let role = Self::get(ctx, site_id, role_id).await?;
+
+        // Don't allow a site to be left without anyone able to manage it
+        if role.builtin
+            && role.slug == BuiltinRole::Admin.slug()
+            && input.can_manage == ProvidedValue::Set(false)
+        {
+            tide::log::error!("Cannot remove manage permission from admin role");
+            return Err(Error::BadRequest);
+        }
+
+        if input.can_manage == ProvidedValue::Set(false) {
+            Self::check_last_manager_role(ctx, site_id, &role).await?;
+        }
+
+        let mut model = site_role::ActiveModel {
+            role_id: Set(role.role_id),
+            updated_at: Set(Some(now())),
+            ..Default::default()
+        };
+
+        macro_rules! apply {
+            ($field:ident) => {
+                if let ProvidedValue::Set(value) = input.$field {
+                    model.$field = Set(value);
+                }
+            };
+        }
+
+        apply!(name);
+        apply!(can_create);
+        apply!(can_edit);
+        apply!(can_move);
+        apply!(can_delete);
+        apply!(can_vote);
+        apply!(can_upload);
+        apply!(can_post);
+        apply!(can_moderate);
+        apply!(can_manage);
+
+        let before = serde_json::to_value(&role)?;
+        let role = model.update(txn).await?;
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(site_id),
+                user_id: Some(input.user_id),
+                action: AuditAction::RoleUpdate,
+                target: AuditTarget::Role(role.role_id),
+                before: Some(before),
+                after: Some(serde_json::to_value(&role)?),
+            },
+        )
+        .await?;
+
+        Ok(role)
+    }
+
+    /// Deletes a custom role.
+    ///
+    /// Users who held the role become guests.
+    pub async fn delete(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        role_id: i64,
+        DeleteRole { user_id }: DeleteRole,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, user_id, Action::Manage).await?;
+
+        let role = Self::get(ctx, site_id, role_id).await?;
+        if role.builtin {
+            tide::log::error!("Cannot delete built-in role '{}'", role.slug);
+            return Err(Error::BadRequest);
+        }
+
+        Self::check_last_manager_role(ctx, site_id, &role).await?;
+
+        SiteUserRole::delete_many()
+            .filter(site_user_role::Column::RoleId.eq(role_id))
+            .exec(txn)
+            .await?;
+
+        PageCategoryPermission::delete_many()
+            .filter(page_category_permission::Column::RoleId.eq(role_id))
+            .exec(txn)
+            .await?;
+
+        let before = serde_json::to_value(&role)?;
+        role.delete(txn).await?;
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(site_id),
+                user_id: Some(user_id),
+                action: AuditAction::RoleDelete,
+                target: AuditTarget::Role(role_id),
+                before: Some(before),
+                after: None,
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_optional(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        role_id: i64,
+    ) -> Result<Option<SiteRoleModel>> {
+        let txn = ctx.transaction();
+        let role = SiteRole::find()
+            .filter(
+                Condition::all()
+                    .add(site_role::Column::SiteId.eq(site_id))
+                    .add(site_role::Column::RoleId.eq(role_id)),
+            )
+            .one(txn)
+            .await?;
+
+        Ok(role)
+    }
+
+    pub async fn get(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        role_id: i64,
+    ) -> Result<SiteRoleModel> {
+        match Self::get_optional(ctx, site_id, role_id).await? {
+            Some(role) => Ok(role),
+            None => Err(Error::NotFound),
+        }
+    }
+
+    pub async fn get_builtin(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        role: BuiltinRole,
+    ) -> Result<SiteRoleModel> {
+        let txn = ctx.transaction();
+        let role = SiteRole::find()
+            .filter(
+                Condition::all()
+                    .add(site_role::Column::SiteId.eq(site_id))
+                    .add(site_role::Column::Slug.eq(role.slug()))
+                    .add(site_role::Column::Builtin.eq(true)),
+            )
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        Ok(role)
+    }
+
+    pub async fn get_all(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+    ) -> Result<Vec<SiteRoleModel>> {
+        let txn = ctx.transaction();
+        let roles = SiteRole::find()
+            .filter(site_role::Column::SiteId.eq(site_id))
+            .order_by_desc(site_role::Column::Builtin)
+            .order_by_asc(site_role::Column::RoleId)
+            .all(txn)
+            .await?;
+
+        Ok(roles)
+    }
+
+    /// Gets the role a user holds in a site, which is guest if they have none.
+    pub async fn get_user_role(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+    ) -> Result<SiteRoleModel> {
+        match get_assignment(ctx, site_id, user_id).await? {
+            Some(assignment) => Self::get(ctx, site_id, assignment.role_id).await,
+            None => Self::get_builtin(ctx, site_id, BuiltinRole::Guest).await,
+        }
+    }
+
+    /// Gives a user a role in a site, replacing any role they already had.
+    pub async fn assign(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+        AssignRole {
+            role_id,
+            assigned_by,
+        }: AssignRole,
+    ) -> Result<()> {
+        PermissionService::check_site(ctx, site_id, assigned_by, Action::Manage).await?;
+
+        let role = Self::get(ctx, site_id, role_id).await?;
+        Self::assign_direct(ctx, site_id, user_id, &role, Some(assigned_by)).await
+    }
+
+    /// Gives a user a built-in role, without any permission checks.
+    ///
+    /// This is for roles granted by the system, such as to a site's creator.
+    pub async fn assign_builtin(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+        role: BuiltinRole,
+        assigned_by: Option<i64>,
+    ) -> Result<()> {
+        let role = Self::get_builtin(ctx, site_id, role).await?;
+        Self::assign_direct(ctx, site_id, user_id, &role, assigned_by).await
+    }
+
+    /// Removes a user's role in a site, making them a guest.
+    pub async fn remove(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+        RemoveRole { removed_by }: RemoveRole,
+    ) -> Result<()> {
+        PermissionService::check_site(ctx, site_id, removed_by, Action::Manage).await?;
+        Self::unassign(ctx, site_id, user_id, removed_by).await
+    }
+
+    /// Removes a user's role in a site, without any permission checks.
+    ///
+    /// This is for users giving up their own role, such as when leaving a site.
+    pub async fn unassign(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+        removed_by: i64,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        Self::check_last_manager(ctx, site_id, user_id, None).await?;
+
+        let assignment = match get_assignment(ctx, site_id, user_id).await? {
+            Some(assignment) => assignment,
+            None => {
+                tide::log::error!("User ID {user_id} has no role in site ID {site_id}");
+                return Err(Error::NotFound);
+            }
+        };
+
+        let before = serde_json::to_value(&assignment)?;
+        assignment.delete(txn).await?;
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(site_id),
+                user_id: Some(removed_by),
+                action: AuditAction::RoleRemove,
+                target: AuditTarget::User(user_id),
+                before: Some(before),
+                after: None,
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sets the permission overrides for a role within a page category.
+    pub async fn set_category_permissions(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        category_id: i64,
+        input: SetCategoryPermissions,
+    ) -> Result<PageCategoryPermissionModel> {
+        let txn = ctx.transaction();
+        PermissionService::check_site(ctx, site_id, input.user_id, Action::Manage)
+            .await?;
+
+        // Ensure both belong to this site
+        let category =
+            CategoryService::get(ctx, site_id, Reference::Id(category_id)).await?;
+        let role = Self::get(ctx, site_id, input.role_id).await?;
+
+        let before =
+            match Self::get_category_override(ctx, category.category_id, role.role_id)
+                .await?
+            {
+                Some(permissions) => {
+                    let value = serde_json::to_value(&permissions)?;
+                    permissions.delete(txn).await?;
+                    Some(value)
+                }
+                None => None,
+            };
+
+        let model = page_category_permission::ActiveModel {
+            category_id: Set(category.category_id),
+            role_id: Set(role.role_id),
+            can_create: Set(input.can_create),
+            can_edit: Set(input.can_edit),
+            can_move: Set(input.can_move),
+            can_delete: Set(input.can_delete),
+            can_vote: Set(input.can_vote),
+            can_upload: Set(input.can_upload),
+        };
+
+        let permissions = model.insert(txn).await?;
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(site_id),
+                user_id: Some(input.user_id),
+                action: AuditAction::CategoryPermissionsSet,
+                target: AuditTarget::Category(category.category_id),
+                before,
+                after: Some(serde_json::to_value(&permissions)?),
+            },
+        )
+        .await?;
+
+        Ok(permissions)
+    }
+
+    pub async fn get_category_permissions(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        category_id: i64,
+    ) -> Result<Vec<PageCategoryPermissionModel>> {
+        let txn = ctx.transaction();
+
+        // Ensure the category is in this site
+        let category =
+            CategoryService::get(ctx, site_id, Reference::Id(category_id)).await?;
+
+        let permissions = PageCategoryPermission::find()
+            .filter(page_category_permission::Column::CategoryId.eq(category.category_id))
+            .order_by_asc(page_category_permission::Column::RoleId)
+            .all(txn)
+            .await?;
+
+        Ok(permissions)
+    }
+
+    pub async fn get_category_override(
+        ctx: &ServiceContext<'_>,
+        category_id: i64,
+        role_id: i64,
+    ) -> Result<Option<PageCategoryPermissionModel>> {
+        let txn = ctx.transaction();
+        let permissions = PageCategoryPermission::find()
+            .filter(
+                Condition::all()
+                    .add(page_category_permission::Column::CategoryId.eq(category_id))
+                    .add(page_category_permission::Column::RoleId.eq(role_id)),
+            )
+            .one(txn)
+            .await?;
+
+        Ok(permissions)
+    }
+
+    /// Ensures a site is not left without anyone able to manage it.
+    ///
+    /// This is checked before a user's role is removed, or replaced with `new_role`.
+    async fn check_last_manager(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+        new_role: Option<&SiteRoleModel>,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+
+        if matches!(new_role, Some(role) if role.can_manage) {
+            return Ok(());
+        }
+
+        let current = Self::get_user_role(ctx, site_id, user_id).await?;
+        if !current.can_manage {
+            return Ok(());
+        }
+
+        let managers = SiteUserRole::find()
+            .join(
+                JoinType::InnerJoin,
+                site_user_role::Relation::SiteRole.def(),
+            )
+            .filter(
+                Condition::all()
+                    .add(site_user_role::Column::SiteId.eq(site_id))
+                    .add(site_user_role::Column::UserId.ne(user_id))
+                    .add(site_role::Column::CanManage.eq(true)),
+            )
+            .count(txn)
+            .await?;
+
+        if managers == 0 {
+            tide::log::error!(
+                "User ID {user_id} is the last manager of site ID {site_id}, cannot remove",
+            );
+            return Err(Error::BadRequest);
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that a site would still have a manager without this role.
+    ///
+    /// This is checked before a role loses the ability to manage the site,
+    /// either by being updated or deleted.
+    async fn check_last_manager_role(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        role: &SiteRoleModel,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+
+        if !role.can_manage {
+            return Ok(());
+        }
+
+        let holders = SiteUserRole::find()
+            .filter(site_user_role::Column::RoleId.eq(role.role_id))
+            .count(txn)
+            .await?;
+
+        if holders == 0 {
+            return Ok(());
+        }
+
+        let managers = SiteUserRole::find()
+            .join(
+                JoinType::InnerJoin,
+                site_user_role::Relation::SiteRole.def(),
+            )
+            .filter(
+                Condition::all()
+                    .add(site_user_role::Column::SiteId.eq(site_id))
+                    .add(site_user_role::Column::RoleId.ne(role.role_id))
+                    .add(site_role::Column::CanManage.eq(true)),
+            )
+            .count(txn)
+            .await?;
+
+        if managers == 0 {
+            tide::log::error!(
+                "Role '{}' has the last managers of site ID {site_id}, cannot remove",
+                role.slug,
+            );
+            return Err(Error::BadRequest);
+        }
+
+        Ok(())
+    }
+
+    async fn assign_direct(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        user_id: i64,
+        role: &SiteRoleModel,
+        assigned_by: Option<i64>,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        Self::check_last_manager(ctx, site_id, user_id, Some(role)).await?;
+
+        let before = match get_assignment(ctx, site_id, user_id).await? {
+            Some(assignment) => {
+                let value = serde_json::to_value(&assignment)?;
+                assignment.delete(txn).await?;
+                Some(value)
+            }
+            None => None,
+        };
+
+        let model = site_user_role::ActiveModel {
+            site_id: Set(site_id),
+            user_id: Set(user_id),
+            role_id: Set(role.role_id),
+            created_at: Set(now()),
+            created_by: Set(assigned_by),
+        };
+
+        let assignment = model.insert(txn).await?;
+        tide::log::info!(
+            "Assigned role '{}' to user ID {user_id} in site ID {site_id}",
+            role.slug,
+        );
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(site_id),
+                user_id: assigned_by,
+                action: AuditAction::RoleAssign,
+                target: AuditTarget::User(user_id),
+                before,
+                after: Some(serde_json::to_value(&assignment)?),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+async fn get_assignment(
+    ctx: &ServiceContext<'_>,
+    site_id: i64,
+    user_id: i64,
+) -> Result<Option<SiteUserRoleModel>> {
+    let txn = ctx.transaction();
+    let assignment = SiteUserRole::find()
+        .filter(
+            Condition::all()
+                .add(site_user_role::Column::SiteId.eq(site_id))
+                .add(site_user_role::Column::UserId.eq(user_id)),
+        )
+        .one(txn)
+        .await?;
+
+    Ok(assignment)
+}
+
+fn build_role(
+    site_id: i64,
+    slug: String,
+    name: String,
+    builtin: bool,
+    permissions: RolePermissions,
+) -> site_role::ActiveModel {
+    site_role::ActiveModel {
+        site_id: Set(site_id),
+        slug: Set(slug),
+        name: Set(name),
+        builtin: Set(builtin),
+        can_create: Set(permissions.can_create),
+        can_edit: Set(permissions.can_edit),
+        can_move: Set(permissions.can_move),
+        can_delete: Set(permissions.can_delete),
+        can_vote: Set(permissions.can_vote),
+        can_upload: Set(permissions.can_upload),
+        can_post: Set(permissions.can_post),
+        can_moderate: Set(permissions.can_moderate),
+        can_manage: Set(permissions.can_manage),
+        created_at: Set(now()),
+        updated_at: Set(None),
+        ..Default::default()
+    }
+}
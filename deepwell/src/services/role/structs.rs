@@ -0,0 +1,212 @@
+/*
+ * services/role/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::site_role::Model as SiteRoleModel;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum BuiltinRole {
+    Guest,
+    Member,
+    Moderator,
+    Admin,
+}
+
+impl BuiltinRole {
+    pub const ALL: [BuiltinRole; 4] = [
+        BuiltinRole::Guest,
+        BuiltinRole::Member,
+        BuiltinRole::Moderator,
+        BuiltinRole::Admin,
+    ];
+
+    pub fn slug(self) -> &'static str {
+        match self {
+            BuiltinRole::Guest => "guest",
+            BuiltinRole::Member => "member",
+            BuiltinRole::Moderator => "moderator",
+            BuiltinRole::Admin => "admin",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BuiltinRole::Guest => "Guest",
+            BuiltinRole::Member => "Member",
+            BuiltinRole::Moderator => "Moderator",
+            BuiltinRole::Admin => "Administrator",
+        }
+    }
+
+    /// The permissions each built-in role has when a site is created.
+    pub fn default_permissions(self) -> RolePermissions {
+        let member = matches!(
+            self,
+            BuiltinRole::Member | BuiltinRole::Moderator | BuiltinRole::Admin,
+        );
+        let moderator = matches!(self, BuiltinRole::Moderator | BuiltinRole::Admin);
+        let admin = matches!(self, BuiltinRole::Admin);
+
+        RolePermissions {
+            can_create: member,
+            can_edit: member,
+            can_move: member,
+            can_delete: moderator,
+            can_vote: member,
+            can_upload: member,
+            can_post: true,
+            can_moderate: moderator,
+            can_manage: admin,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RolePermissions {
+    pub can_create: bool,
+    pub can_edit: bool,
+    pub can_move: bool,
+    pub can_delete: bool,
+    pub can_vote: bool,
+    pub can_upload: bool,
+    pub can_post: bool,
+    pub can_moderate: bool,
+    pub can_manage: bool,
+}
+
+impl From<&SiteRoleModel> for RolePermissions {
+    fn from(role: &SiteRoleModel) -> RolePermissions {
+        RolePermissions {
+            can_create: role.can_create,
+            can_edit: role.can_edit,
+            can_move: role.can_move,
+            can_delete: role.can_delete,
+            can_vote: role.can_vote,
+            can_upload: role.can_upload,
+            can_post: role.can_post,
+            can_moderate: role.can_moderate,
+            can_manage: role.can_manage,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRole {
+    pub slug: String,
+    pub name: String,
+
+    #[serde(flatten)]
+    pub permissions: RolePermissions,
+
+    /// The user creating the role.
+    pub user_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRole {
+    #[serde(default)]
+    pub name: ProvidedValue<String>,
+
+    #[serde(default)]
+    pub can_create: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub can_edit: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub can_move: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub can_delete: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub can_vote: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub can_upload: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub can_post: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub can_moderate: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub can_manage: ProvidedValue<bool>,
+
+    /// The user making the change.
+    pub user_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteRole {
+    /// The user deleting the role.
+    pub user_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignRole {
+    pub role_id: i64,
+
+    /// The user granting the role.
+    pub assigned_by: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveRole {
+    /// The user removing the role.
+    pub removed_by: i64,
+}
+
+/// Changes to what a role may do with pages in a category.
+///
+/// `None` means the role's site-wide permission applies.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCategoryPermissions {
+    pub role_id: i64,
+
+    #[serde(default)]
+    pub can_create: Option<bool>,
+
+    #[serde(default)]
+    pub can_edit: Option<bool>,
+
+    #[serde(default)]
+    pub can_move: Option<bool>,
+
+    #[serde(default)]
+    pub can_delete: Option<bool>,
+
+    #[serde(default)]
+    pub can_vote: Option<bool>,
+
+    #[serde(default)]
+    pub can_upload: Option<bool>,
+
+    /// The user making the change.
+    pub user_id: i64,
+}
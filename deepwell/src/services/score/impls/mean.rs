@@ -43,8 +43,8 @@ impl Scorer for MeanScorer {
     ) -> Result<f64> {
         #[derive(FromQueryResult, Debug)]
         struct MeanRow {
-            sum: u64,
-            count: u64,
+            sum: Option<i64>,
+            count: i64,
         }
 
         // Query for sum of all votes.
@@ -59,6 +59,7 @@ impl Scorer for MeanScorer {
         // GROUP BY value;
 
         let MeanRow { sum, count } = PageVote::find()
+            .select_only()
             .column_as(page_vote::Column::Value.sum(), "sum")
             .column_as(page_vote::Column::Value.count(), "count")
             .filter(condition)
@@ -67,10 +68,10 @@ impl Scorer for MeanScorer {
             .await?
             .expect("No results in aggregate query");
 
-        if count == 0 {
-            Ok(0.0)
-        } else {
-            Ok((sum / count) as f64)
+        // SUM() is NULL if there are no rows
+        match sum {
+            Some(sum) if count > 0 => Ok(sum as f64 / count as f64),
+            _ => Ok(0.0),
         }
     }
 }
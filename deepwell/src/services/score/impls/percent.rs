@@ -47,6 +47,10 @@ impl Scorer for PercentScorer {
         // may as well use the helper method.
         let votes = ScoreService::collect_votes(txn, condition).await?;
 
+        if votes.is_empty() {
+            return Ok(0.0);
+        }
+
         let upvotes = votes.get(1) as f64;
         let total = votes.count() as f64;
 
@@ -42,7 +42,7 @@ impl Scorer for SumScorer {
     ) -> Result<f64> {
         #[derive(FromQueryResult, Debug)]
         struct SumRow {
-            sum: u64,
+            sum: Option<i64>,
         }
 
         // Query for sum of all votes.
@@ -57,6 +57,7 @@ impl Scorer for SumScorer {
         // GROUP BY value;
 
         let result = PageVote::find()
+            .select_only()
             .column_as(page_vote::Column::Value.sum(), "sum")
             .filter(condition)
             .into_model::<SumRow>()
@@ -64,6 +65,7 @@ impl Scorer for SumScorer {
             .await?
             .expect("No results in aggregate query");
 
-        Ok(result.sum as f64)
+        // SUM() is NULL if there are no rows
+        Ok(result.sum.unwrap_or(0) as f64)
     }
 }
@@ -0,0 +1,95 @@
+/*
+ * services/score/impls/wilson.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::ScoreService;
+
+/// The z-score corresponding to a 95% confidence interval.
+const Z_SCORE: f64 = 1.96;
+
+/// Scores pages by the lower bound of the Wilson score interval.
+///
+/// This estimates the "true" proportion of upvotes a page would
+/// receive, while accounting for the uncertainty that comes from
+/// having few votes. A page with 4 upvotes and no downvotes will
+/// score lower than one with 80 upvotes and 5 downvotes.
+///
+/// The resultant score is within `0.0` and `1.0`.
+///
+/// See <https://www.evanmiller.org/how-not-to-sort-by-average-rating.html>.
+#[derive(Debug)]
+pub struct WilsonScorer;
+
+#[async_trait]
+impl Scorer for WilsonScorer {
+    #[inline]
+    fn score_type(&self) -> ScoreType {
+        ScoreType::Wilson
+    }
+
+    fn accepts_vote_type(&self, vote_type: VoteType) -> bool {
+        match vote_type {
+            VoteType::UpsDowns => true,
+            VoteType::FiveStar => false,
+        }
+    }
+
+    async fn score(
+        &self,
+        txn: &DatabaseTransaction,
+        condition: Condition,
+    ) -> Result<f64> {
+        let votes = ScoreService::collect_votes(txn, condition).await?;
+
+        let upvotes = votes.get(1) as f64;
+        let total = votes.count() as f64;
+
+        Ok(wilson_lower_bound(upvotes, total))
+    }
+}
+
+fn wilson_lower_bound(positive: f64, total: f64) -> f64 {
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    let z2 = Z_SCORE * Z_SCORE;
+    let p = positive / total;
+
+    let center = p + z2 / (2.0 * total);
+    let margin = Z_SCORE * ((p * (1.0 - p) + z2 / (4.0 * total)) / total).sqrt();
+
+    (center - margin) / (1.0 + z2 / total)
+}
+
+#[test]
+fn lower_bound() {
+    assert_eq!(wilson_lower_bound(0.0, 0.0), 0.0);
+    assert_eq!(wilson_lower_bound(0.0, 10.0), 0.0);
+
+    // Always below the observed proportion
+    let score = wilson_lower_bound(8.0, 10.0);
+    assert!(score > 0.0 && score < 0.8, "Score out of range: {score}");
+
+    // More votes with the same ratio means more confidence
+    let few = wilson_lower_bound(4.0, 4.0);
+    let many = wilson_lower_bound(80.0, 85.0);
+    assert!(few < many, "Fewer votes scored higher ({few} >= {many})");
+}
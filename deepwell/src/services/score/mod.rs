@@ -35,3 +35,4 @@ mod structs;
 pub use self::impls::*;
 pub use self::scorer::Scorer;
 pub use self::service::ScoreService;
+pub use self::structs::{ScoreSettings, ScoreType, VoteType};
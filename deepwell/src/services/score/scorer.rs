@@ -21,7 +21,7 @@
 use super::prelude::*;
 
 #[async_trait]
-pub trait Scorer {
+pub trait Scorer: Send + Sync {
     /// What kind of score this scorer evaluates.
     ///
     /// There should be a 1-to-1 mapping between `Scorer`
@@ -20,28 +20,118 @@
 
 use super::impls::*;
 use super::prelude::*;
+use crate::services::{CategoryService, PageService, SiteService};
 
 #[derive(Debug)]
 pub struct ScoreService;
 
 impl ScoreService {
     pub async fn score(ctx: &ServiceContext<'_>, page_id: i64) -> Result<f64> {
+        let scorer = Self::get_scorer(ctx, page_id).await?;
+        Self::score_with(ctx, scorer, page_id).await
+    }
+
+    /// Calculates the score for a page using the given `Scorer`.
+    ///
+    /// Use this if you already have retrieved the `Scorer` for this page,
+    /// for instance, because you also need to know its score type.
+    pub async fn score_with(
+        ctx: &ServiceContext<'_>,
+        scorer: &dyn Scorer,
+        page_id: i64,
+    ) -> Result<f64> {
         let txn = ctx.transaction();
         let condition = Self::build_condition(page_id);
-        let scorer = Self::get_scorer(ctx, page_id).await?;
         let score = scorer.score(txn, condition).await?;
         Ok(score)
     }
 
     /// Gets the correct `Scorer` implementation for this page.
     ///
-    /// Currently stubbed, will be implemented when relevant settings are added.
+    /// This is determined by the scoring settings for the page,
+    /// see `get_settings()`.
     pub async fn get_scorer(
-        _ctx: &ServiceContext<'_>,
-        _page_id: i64,
-    ) -> Result<&'static impl Scorer> {
-        // TODO
-        Ok(&NullScorer)
+        ctx: &ServiceContext<'_>,
+        page_id: i64,
+    ) -> Result<&'static dyn Scorer> {
+        let ScoreSettings { score_type, .. } = Self::get_settings(ctx, page_id).await?;
+        Ok(Self::scorer_for(score_type))
+    }
+
+    /// Gets the scoring and voting settings in effect for this page.
+    ///
+    /// If the page's category specifies a value, then that is used.
+    /// Otherwise it falls back to the value set for the site.
+    pub async fn get_settings(
+        ctx: &ServiceContext<'_>,
+        page_id: i64,
+    ) -> Result<ScoreSettings> {
+        let page = PageService::get_direct(ctx, page_id).await?;
+        let (category, site) = try_join!(
+            CategoryService::get_direct(ctx, page.page_category_id),
+            SiteService::get(ctx, Reference::from(page.site_id)),
+        )?;
+
+        Ok(ScoreSettings {
+            score_type: category.score_type.unwrap_or(site.score_type),
+            vote_type: category.vote_type.unwrap_or(site.vote_type),
+        })
+    }
+
+    /// Checks that these scoring and voting settings can be used together.
+    ///
+    /// For instance, a Wilson score is only defined for upvotes and downvotes.
+    pub fn check_settings(
+        ScoreSettings {
+            score_type,
+            vote_type,
+        }: ScoreSettings,
+    ) -> Result<()> {
+        if !Self::scorer_for(score_type).accepts_vote_type(vote_type) {
+            tide::log::error!(
+                "Score type {score_type:?} does not accept votes of type {vote_type:?}",
+            );
+
+            return Err(Error::BadRequest);
+        }
+
+        Ok(())
+    }
+
+    /// Checks new site-wide scoring and voting settings.
+    ///
+    /// Categories which only override one of the two values are combined with
+    /// the new settings, so those pairs must also be compatible.
+    pub async fn check_site_settings(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        settings: ScoreSettings,
+    ) -> Result<()> {
+        Self::check_settings(settings)?;
+
+        for category in CategoryService::get_all(ctx, site_id).await? {
+            Self::check_settings(ScoreSettings {
+                score_type: category.score_type.unwrap_or(settings.score_type),
+                vote_type: category.vote_type.unwrap_or(settings.vote_type),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the `Scorer` implementation for the given score type.
+    ///
+    /// There is a 1-to-1 mapping between the two,
+    /// see `Scorer::score_type()`.
+    pub fn scorer_for(score_type: ScoreType) -> &'static dyn Scorer {
+        match score_type {
+            ScoreType::Null => &NullScorer,
+            ScoreType::Sum => &SumScorer,
+            ScoreType::Mean => &MeanScorer,
+            ScoreType::Median => &MedianScorer,
+            ScoreType::Percent => &PercentScorer,
+            ScoreType::Wilson => &WilsonScorer,
+        }
     }
 
     /// Helper method for retrieving a `VoteMap` for a page.
@@ -66,10 +156,11 @@ impl ScoreService {
         #[derive(FromQueryResult, Debug)]
         struct VoteCountRow {
             value: VoteValue,
-            count: u64,
+            count: i64,
         }
 
         let counts = PageVote::find()
+            .select_only()
             .column(page_vote::Column::Value)
             .column_as(page_vote::Column::Value.count(), "count")
             .filter(condition)
@@ -82,7 +173,7 @@ impl ScoreService {
         let mut map = VoteMap::new();
 
         for VoteCountRow { value, count } in counts {
-            map.insert(value, count);
+            map.insert(value, count as u64);
         }
 
         Ok(map)
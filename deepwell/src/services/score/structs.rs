@@ -20,24 +20,31 @@
 
 use std::collections::BTreeMap;
 
+pub use crate::models::sea_orm_active_enums::{ScoreType, VoteType};
 pub use crate::services::vote::VoteValue;
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
-#[serde(rename_all = "camelCase")]
-#[non_exhaustive]
-pub enum VoteType {
-    UpsDowns,
-    FiveStar,
+impl VoteType {
+    /// Whether the given vote value is permitted for this kind of voting.
+    ///
+    /// * `UpsDowns` accepts `+1` and `-1`.
+    /// * `FiveStar` accepts values from `1` to `5` inclusive.
+    pub fn accepts_value(self, value: VoteValue) -> bool {
+        match self {
+            VoteType::UpsDowns => value == 1 || value == -1,
+            VoteType::FiveStar => (1..=5).contains(&value),
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+/// The scoring and voting settings in effect for a particular page.
+///
+/// These are determined by the page's category, falling
+/// back to the site's settings if the category has none.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub enum ScoreType {
-    Null,
-    Sum,
-    Mean,
-    Median,
-    Percent,
+pub struct ScoreSettings {
+    pub score_type: ScoreType,
+    pub vote_type: VoteType,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -67,7 +74,6 @@ impl VoteMap {
     }
 
     #[inline]
-    #[allow(dead_code)] // TEMP
     pub fn is_empty(&self) -> bool {
         self.count() == 0
     }
@@ -83,9 +89,69 @@ impl VoteMap {
         })
     }
 
+    /// Gets the median of all the votes in this map.
+    ///
+    /// If there are an even number of votes, then the mean
+    /// of the two middle values is returned.
+    /// If there are no votes, then this returns `0.0`.
+    pub fn median(&self) -> f64 {
+        let count = self.count();
+        if count == 0 {
+            return 0.0;
+        }
+
+        let lower = self.nth_value((count - 1) / 2);
+        let upper = self.nth_value(count / 2);
+
+        (f64::from(lower) + f64::from(upper)) / 2.0
+    }
+
+    /// Gets the value of the `n`th vote (zero-indexed), as if
+    /// all of the votes were laid out in ascending order.
+    fn nth_value(&self, index: u64) -> VoteValue {
+        let mut seen = 0;
+
+        for (value, count) in self.iter() {
+            seen += count;
+
+            if index < seen {
+                return value;
+            }
+        }
+
+        panic!("Vote index {index} out of range for map with {seen} votes");
+    }
+
     #[inline]
     pub fn iter(&self) -> impl Iterator<Item = (VoteValue, u64)> + '_ {
         // We can't quite use .copied() here because we need to copy the tuple too
         self.inner.iter().map(|(&value, &count)| (value, count))
     }
 }
+
+#[test]
+fn median() {
+    macro_rules! check {
+        ($votes:expr, $expected:expr $(,)?) => {{
+            let mut map = VoteMap::new();
+
+            for &(value, count) in &$votes {
+                map.insert(value, count);
+            }
+
+            assert_eq!(map.median(), $expected, "Median of vote map doesn't match");
+        }};
+    }
+
+    assert_eq!(
+        VoteMap::new().median(),
+        0.0,
+        "Median of empty vote map isn't zero"
+    );
+
+    check!([(1, 1)], 1.0);
+    check!([(-1, 1), (1, 1)], 0.0);
+    check!([(-1, 1), (1, 2)], 1.0);
+    check!([(1, 2), (2, 1), (5, 3)], 3.5);
+    check!([(1, 4), (4, 1), (5, 2)], 1.0);
+}
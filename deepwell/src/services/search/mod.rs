@@ -0,0 +1,39 @@
+/*
+ * services/search/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Full-text search over pages, using PostgreSQL's text search features.
+//!
+//! Each page has a row in the `page_search` table reflecting its latest
+//! revision, which is updated whenever the page is rendered. Titles are
+//! weighted above tags, which are weighted above the page body.
+//!
+//! The `simple` text search configuration is used, since sites may be
+//! in any language and stemming rules are language-specific.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::SearchService;
+pub use self::structs::*;
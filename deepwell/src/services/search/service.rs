@@ -0,0 +1,170 @@
+/*
+ * services/search/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::CategoryService;
+use sea_orm::{DatabaseBackend, FromQueryResult, Statement};
+
+#[derive(Debug)]
+pub struct SearchService;
+
+impl SearchService {
+    /// Updates the search index entry for a page.
+    ///
+    /// This is called whenever a page is rendered,
+    /// so the index always reflects the latest revision.
+    pub async fn update_index(
+        ctx: &ServiceContext<'_>,
+        page_id: i64,
+        IndexPage {
+            title,
+            alt_title,
+            tags,
+            body,
+        }: IndexPage<'_>,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let tags_json = serde_json::to_value(tags)?;
+        let tags_text = tags.join(" ");
+
+        txn.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r"
+            INSERT INTO page_search (
+                page_id,
+                updated_at,
+                title,
+                alt_title,
+                tags,
+                body,
+                search_vector
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6,
+                setweight(to_tsvector('simple', $3), 'A') ||
+                setweight(to_tsvector('simple', coalesce($4, '')), 'A') ||
+                setweight(to_tsvector('simple', $7), 'B') ||
+                setweight(to_tsvector('simple', $6), 'C')
+            )
+            ON CONFLICT (page_id) DO UPDATE SET
+                updated_at = EXCLUDED.updated_at,
+                title = EXCLUDED.title,
+                alt_title = EXCLUDED.alt_title,
+                tags = EXCLUDED.tags,
+                body = EXCLUDED.body,
+                search_vector = EXCLUDED.search_vector
+            ",
+            vec![
+                page_id.into(),
+                now().into(),
+                title.into(),
+                alt_title.map(String::from).into(),
+                tags_json.into(),
+                body.into(),
+                tags_text.into(),
+            ],
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    /// Searches for extant pages in a site, ordered by relevance.
+    ///
+    /// The query uses web search syntax, so quoted phrases,
+    /// `or`, and `-` to exclude terms are all supported.
+    pub async fn search(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        SearchPages {
+            query,
+            category,
+            tags,
+            offset,
+        }: SearchPages,
+        limit: u64,
+    ) -> Result<Vec<SearchResult>> {
+        let txn = ctx.transaction();
+
+        if query.trim().is_empty() {
+            return Ok(vec![]);
+        }
+
+        let category_id = match category {
+            None => None,
+            Some(slug) => {
+                let category =
+                    CategoryService::get(ctx, site_id, Reference::from(slug.as_str()))
+                        .await?;
+
+                Some(category.category_id)
+            }
+        };
+
+        let tags = if tags.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_value(&tags)?)
+        };
+
+        // The body is escaped before highlighting, since
+        // ts_headline() does not escape its output.
+        let results = SearchResult::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r"
+            SELECT
+                page.page_id,
+                page.slug,
+                page_search.title,
+                page_search.alt_title,
+                ts_rank(page_search.search_vector, query) AS rank,
+                ts_headline(
+                    'simple',
+                    replace(replace(replace(page_search.body, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'),
+                    query,
+                    'StartSel=<mark>, StopSel=</mark>, MaxFragments=2'
+                ) AS snippet
+            FROM page_search
+            JOIN page ON page.page_id = page_search.page_id
+            CROSS JOIN websearch_to_tsquery('simple', $2) AS query
+            WHERE page.site_id = $1
+                AND page.deleted_at IS NULL
+                AND page_search.search_vector @@ query
+                AND ($3::bigint IS NULL OR page.page_category_id = $3)
+                AND ($4::jsonb IS NULL OR page_search.tags @> $4)
+            ORDER BY rank DESC, page.page_id
+            LIMIT $5
+            OFFSET $6
+            ",
+            vec![
+                site_id.into(),
+                query.into(),
+                category_id.into(),
+                tags.into(),
+                (limit as i64).into(),
+                (offset as i64).into(),
+            ],
+        ))
+        .all(txn)
+        .await?;
+
+        Ok(results)
+    }
+}
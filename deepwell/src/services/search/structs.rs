@@ -0,0 +1,61 @@
+/*
+ * services/search/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use sea_orm::FromQueryResult;
+
+#[derive(Debug)]
+pub struct IndexPage<'a> {
+    pub title: &'a str,
+    pub alt_title: Option<&'a str>,
+    pub tags: &'a [String],
+    pub body: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchPages {
+    pub query: String,
+
+    /// Only return pages in the category with this slug.
+    #[serde(default)]
+    pub category: Option<String>,
+
+    /// Only return pages which have all of these tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    #[serde(default)]
+    pub offset: u64,
+}
+
+#[derive(Serialize, FromQueryResult, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub page_id: i64,
+    pub slug: String,
+    pub title: String,
+    pub alt_title: Option<String>,
+    pub rank: f32,
+
+    /// An excerpt of the page body, with matching terms wrapped in `<mark>`.
+    ///
+    /// The text is HTML-escaped, so it is safe to insert directly.
+    pub snippet: String,
+}
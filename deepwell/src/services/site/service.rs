@@ -20,11 +20,110 @@
 
 use super::prelude::*;
 use crate::models::site::{self, Entity as Site, Model as SiteModel};
+use crate::services::page::{CreatePage, CreatePageOutput};
+use crate::services::role::BuiltinRole;
+use crate::services::{CategoryService, PageService, RoleService, SiteSettingsService};
+use wikidot_normalize::normalize;
+
+/// The wikitext for the start page of a newly-created site.
+const START_PAGE_WIKITEXT: &str = "\
+This is the start page of your new site.
+
+You can edit this page to change what visitors see first.";
 
 #[derive(Debug)]
 pub struct SiteService;
 
 impl SiteService {
+    /// Creates a new site.
+    ///
+    /// This provisions everything a site needs to be usable:
+    /// its settings, the `_default` category, the built-in roles,
+    /// and the start page. The creating user is made an admin.
+    pub async fn create(
+        ctx: &ServiceContext<'_>,
+        CreateSite {
+            mut slug,
+            name,
+            subtitle,
+            description,
+            language,
+            user_id,
+        }: CreateSite,
+    ) -> Result<CreateSiteOutput> {
+        let txn = ctx.transaction();
+
+        normalize(&mut slug);
+        if slug.is_empty() {
+            tide::log::error!("Cannot create site with empty slug");
+            return Err(Error::BadRequest);
+        }
+
+        // Check for conflicts.
+        //
+        // Deleted sites are included, since slugs must be unique.
+        let existing = Site::find()
+            .filter(site::Column::Slug.eq(slug.as_str()))
+            .one(txn)
+            .await?;
+
+        if let Some(site) = existing {
+            tide::log::error!(
+                "Site {} with slug '{}' already exists, cannot create",
+                site.site_id,
+                slug,
+            );
+
+            return Err(Error::Conflict);
+        }
+
+        // Insert site
+        //
+        // Other fields, such as the default page, use the database defaults.
+        let model = site::ActiveModel {
+            name: Set(Some(name.clone())),
+            subtitle: Set(subtitle),
+            slug: Set(slug.clone()),
+            description: Set(description),
+            language: Set(language),
+            date_created: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+        let site = model.insert(txn).await?;
+
+        // Provision settings, default category, and roles
+        try_join!(
+            SiteSettingsService::create(ctx, site.site_id),
+            CategoryService::get_or_create(ctx, site.site_id, "_default"),
+            RoleService::create_builtin(ctx, site.site_id),
+        )?;
+
+        // The creator administers the new site
+        RoleService::assign_builtin(ctx, site.site_id, user_id, BuiltinRole::Admin, None)
+            .await?;
+
+        // Create start page
+        let CreatePageOutput { page_id, .. } = PageService::create(
+            ctx,
+            site.site_id,
+            CreatePage {
+                wikitext: str!(START_PAGE_WIKITEXT),
+                title: name,
+                alt_title: None,
+                slug: site.default_page.clone(),
+                revision_comments: str!("Created site"),
+                user_id,
+            },
+        )
+        .await?;
+
+        Ok(CreateSiteOutput {
+            site_id: site.site_id,
+            slug,
+            start_page_id: page_id,
+        })
+    }
+
     #[inline]
     pub async fn exists(
         ctx: &ServiceContext<'_>,
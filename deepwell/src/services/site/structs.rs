@@ -18,4 +18,29 @@
  * along with this program. If not, see <http://www.gnu.org/licenses/>.
  */
 
-// TODO
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSite {
+    pub slug: String,
+    pub name: String,
+    pub subtitle: Option<String>,
+    pub description: Option<String>,
+
+    #[serde(default = "default_language")]
+    pub language: String,
+
+    /// The user creating the site, who is the author of its start page.
+    pub user_id: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSiteOutput {
+    pub site_id: i64,
+    pub slug: String,
+    pub start_page_id: i64,
+}
+
+fn default_language() -> String {
+    str!("en")
+}
@@ -0,0 +1,408 @@
+/*
+ * services/site_settings/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::site;
+use crate::models::site_settings::{
+    self, Entity as SiteSettings, Model as SiteSettingsModel,
+};
+use crate::services::audit::{AuditAction, AuditTarget, CreateAuditEntry};
+use crate::services::permission::{Action, PermissionService};
+use crate::services::score::ScoreSettings;
+use crate::services::{AuditService, ScoreService, SiteService};
+use sea_orm::{DatabaseBackend, FromQueryResult, Statement};
+use serde_json::{json, Value as JsonValue};
+use wikidot_normalize::normalize;
+
+/// The accepted values for the `ssl_mode` setting, if one is set.
+const SSL_MODES: [&str; 2] = ["ssl", "ssl_only"];
+
+#[derive(Debug)]
+pub struct SiteSettingsService;
+
+impl SiteSettingsService {
+    /// Creates the settings for a new site, using the defaults.
+    pub async fn create(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+    ) -> Result<SiteSettingsModel> {
+        let txn = ctx.transaction();
+
+        // All other columns have defaults in the database
+        let model = site_settings::ActiveModel {
+            site_id: Set(settings_id(site_id)?),
+            ..Default::default()
+        };
+
+        let settings = model.insert(txn).await?;
+        Ok(settings)
+    }
+
+    /// Gets the settings for a site.
+    ///
+    /// Older sites may not have a settings row, in which case
+    /// one is created with the default values.
+    pub async fn get(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+    ) -> Result<SiteSettingsModel> {
+        let txn = ctx.transaction();
+        let settings = SiteSettings::find_by_id(settings_id(site_id)?)
+            .one(txn)
+            .await?;
+
+        match settings {
+            Some(settings) => Ok(settings),
+            None => Self::create(ctx, site_id).await,
+        }
+    }
+
+    pub async fn update(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        input: UpdateSiteSettings,
+    ) -> Result<SiteSettingsModel> {
+        let txn = ctx.transaction();
+        let user_id = input.user_id;
+        PermissionService::check_site(ctx, site_id, user_id, Action::Manage).await?;
+        let mut settings = Self::get(ctx, site_id).await?;
+        let site = SiteService::get(ctx, Reference::from(site_id)).await?;
+        let mut score_settings = ScoreSettings {
+            score_type: site.score_type,
+            vote_type: site.vote_type,
+        };
+        let before = audit_value(&settings, score_settings)?;
+
+        apply_update(&mut settings, &mut score_settings, input);
+        validate(&settings)?;
+
+        // Scoring settings are stored on the site, not in site_settings
+        if score_settings.score_type != site.score_type
+            || score_settings.vote_type != site.vote_type
+        {
+            ScoreService::check_site_settings(ctx, site_id, score_settings).await?;
+
+            let model = site::ActiveModel {
+                site_id: Set(site_id),
+                score_type: Set(score_settings.score_type),
+                vote_type: Set(score_settings.vote_type),
+                ..Default::default()
+            };
+
+            model.update(txn).await?;
+        }
+
+        let SiteSettingsModel {
+            site_id,
+            allow_membership_by_apply,
+            allow_membership_by_password,
+            membership_password,
+            file_storage_size,
+            use_ganalytics,
+            private_landing_page,
+            max_private_members,
+            max_private_viewers,
+            hide_navigation_unauthorized,
+            ssl_mode,
+            allow_members_invite,
+            max_upload_file_size,
+        } = settings;
+
+        let model = site_settings::ActiveModel {
+            site_id: Set(site_id),
+            allow_membership_by_apply: Set(allow_membership_by_apply),
+            allow_membership_by_password: Set(allow_membership_by_password),
+            membership_password: Set(membership_password),
+            file_storage_size: Set(file_storage_size),
+            use_ganalytics: Set(use_ganalytics),
+            private_landing_page: Set(private_landing_page),
+            max_private_members: Set(max_private_members),
+            max_private_viewers: Set(max_private_viewers),
+            hide_navigation_unauthorized: Set(hide_navigation_unauthorized),
+            ssl_mode: Set(ssl_mode),
+            allow_members_invite: Set(allow_members_invite),
+            max_upload_file_size: Set(max_upload_file_size),
+        };
+
+        let settings = model.update(txn).await?;
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(i64::from(site_id)),
+                user_id: Some(user_id),
+                action: AuditAction::SiteSettingsUpdate,
+                target: AuditTarget::Site(i64::from(site_id)),
+                before: Some(before),
+                after: Some(audit_value(&settings, score_settings)?),
+            },
+        )
+        .await?;
+
+        Ok(settings)
+    }
+
+    /// Checks that an upload of the given size is permitted on the site.
+    ///
+    /// This enforces both the maximum size of an individual file, and the
+    /// total storage across all extant files in the site. The `replacing`
+    /// argument is the size of any existing data this upload replaces,
+    /// which no longer counts towards the total.
+    pub async fn check_upload(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        size: i64,
+        replacing: i64,
+    ) -> Result<()> {
+        let settings = Self::get(ctx, site_id).await?;
+
+        if size > i64::from(settings.max_upload_file_size) {
+            tide::log::error!(
+                "File of {} bytes exceeds the upload limit of {} bytes for site ID {}",
+                size,
+                settings.max_upload_file_size,
+                site_id,
+            );
+
+            return Err(Error::FileTooLarge);
+        }
+
+        let used = Self::get_storage_used(ctx, site_id).await?;
+        if used - replacing + size > i64::from(settings.file_storage_size) {
+            tide::log::error!(
+                "Upload of {} bytes would exceed the storage limit of {} bytes for site ID {} ({} used)",
+                size,
+                settings.file_storage_size,
+                site_id,
+                used,
+            );
+
+            return Err(Error::SiteStorageFull);
+        }
+
+        Ok(())
+    }
+
+    /// Gets the total size of the latest versions of all extant files in a site.
+    pub async fn get_storage_used(ctx: &ServiceContext<'_>, site_id: i64) -> Result<i64> {
+        #[derive(FromQueryResult, Debug)]
+        struct Row {
+            used: i64,
+        }
+
+        let txn = ctx.transaction();
+        let row = Row::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r"
+            SELECT COALESCE(SUM(revision.size_hint), 0)::BIGINT AS used
+            FROM file
+            JOIN page ON page.page_id = file.page_id
+            CROSS JOIN LATERAL (
+                SELECT size_hint
+                FROM file_revision
+                WHERE file_revision.file_id = file.file_id
+                ORDER BY revision_number DESC
+                LIMIT 1
+            ) AS revision
+            WHERE page.site_id = $1
+                AND file.deleted_at IS NULL
+            ",
+            vec![site_id.into()],
+        ))
+        .one(txn)
+        .await?;
+
+        Ok(row.map(|row| row.used).unwrap_or(0))
+    }
+}
+
+/// Converts a site ID for use with the `site_settings` table.
+///
+/// This table predates the move to 64-bit IDs, so its key is still 32-bit.
+fn settings_id(site_id: i64) -> Result<i32> {
+    i32::try_from(site_id).map_err(|_| {
+        tide::log::error!("Site ID {} is too large for site settings", site_id);
+        Error::BadRequest
+    })
+}
+
+fn apply_update(
+    settings: &mut SiteSettingsModel,
+    score_settings: &mut ScoreSettings,
+    UpdateSiteSettings {
+        allow_membership_by_apply,
+        allow_membership_by_password,
+        membership_password,
+        file_storage_size,
+        use_ganalytics,
+        private_landing_page,
+        max_private_members,
+        max_private_viewers,
+        hide_navigation_unauthorized,
+        ssl_mode,
+        allow_members_invite,
+        max_upload_file_size,
+        score_type,
+        vote_type,
+        user_id: _,
+    }: UpdateSiteSettings,
+) {
+    macro_rules! apply {
+        ($field:ident) => {
+            if let ProvidedValue::Set(value) = $field {
+                settings.$field = value;
+            }
+        };
+    }
+
+    apply!(allow_membership_by_apply);
+    apply!(allow_membership_by_password);
+    apply!(membership_password);
+    apply!(file_storage_size);
+    apply!(use_ganalytics);
+    apply!(private_landing_page);
+    apply!(max_private_members);
+    apply!(max_private_viewers);
+    apply!(hide_navigation_unauthorized);
+    apply!(ssl_mode);
+    apply!(allow_members_invite);
+    apply!(max_upload_file_size);
+
+    if let ProvidedValue::Set(value) = score_type {
+        score_settings.score_type = value;
+    }
+
+    if let ProvidedValue::Set(value) = vote_type {
+        score_settings.vote_type = value;
+    }
+
+    normalize(&mut settings.private_landing_page);
+}
+
+fn validate(settings: &SiteSettingsModel) -> Result<()> {
+    macro_rules! check {
+        ($condition:expr, $message:expr $(,)?) => {
+            if !$condition {
+                tide::log::error!("Invalid site settings: {}", $message);
+                return Err(Error::BadRequest);
+            }
+        };
+    }
+
+    check!(
+        settings.file_storage_size >= 0,
+        "file storage size cannot be negative",
+    );
+    check!(
+        settings.max_upload_file_size > 0,
+        "maximum upload size must be positive",
+    );
+    check!(
+        settings.max_upload_file_size <= settings.file_storage_size,
+        "maximum upload size cannot exceed file storage size",
+    );
+    check!(
+        settings.max_private_members >= 0 && settings.max_private_viewers >= 0,
+        "private member and viewer limits cannot be negative",
+    );
+    check!(
+        !settings.private_landing_page.is_empty(),
+        "private landing page cannot be empty",
+    );
+    check!(
+        !settings.allow_membership_by_password
+            || matches!(settings.membership_password, Some(ref password) if !password.is_empty()),
+        "membership by password requires a password",
+    );
+    check!(
+        match settings.ssl_mode {
+            Some(ref mode) => SSL_MODES.contains(&mode.as_str()),
+            None => true,
+        },
+        "unknown SSL mode",
+    );
+
+    Ok(())
+}
+
+/// Converts settings into a value for the audit log.
+///
+/// The membership password itself is not recorded, only whether one is set.
+fn audit_value(
+    settings: &SiteSettingsModel,
+    score_settings: ScoreSettings,
+) -> Result<JsonValue> {
+    let mut value = serde_json::to_value(settings)?;
+    value["score_type"] = serde_json::to_value(score_settings.score_type)?;
+    value["vote_type"] = serde_json::to_value(score_settings.vote_type)?;
+    if settings.membership_password.is_some() {
+        value["membership_password"] = json!("[redacted]");
+    }
+
+    Ok(value)
+}
+
+#[test]
+fn validate_settings() {
+    let settings = SiteSettingsModel {
+        site_id: 1,
+        allow_membership_by_apply: true,
+        allow_membership_by_password: false,
+        membership_password: None,
+        file_storage_size: 314572800,
+        use_ganalytics: false,
+        private_landing_page: str!("system:join"),
+        max_private_members: 50,
+        max_private_viewers: 20,
+        hide_navigation_unauthorized: true,
+        ssl_mode: None,
+        allow_members_invite: false,
+        max_upload_file_size: 10485760,
+    };
+
+    macro_rules! check {
+        ($valid:expr, $($field:ident: $value:expr),* $(,)?) => {{
+            let settings = SiteSettingsModel {
+                $($field: $value,)*
+                ..settings.clone()
+            };
+
+            assert_eq!(
+                validate(&settings).is_ok(),
+                $valid,
+                "Settings validation result didn't match expected",
+            );
+        }};
+    }
+
+    check!(true,);
+    check!(true, ssl_mode: Some(str!("ssl_only")));
+    check!(false, ssl_mode: Some(str!("always")));
+    check!(false, max_upload_file_size: 0);
+    check!(false, max_upload_file_size: 314572801);
+    check!(false, file_storage_size: -1);
+    check!(false, private_landing_page: String::new());
+    check!(false, allow_membership_by_password: true);
+    check!(
+        true,
+        allow_membership_by_password: true,
+        membership_password: Some(str!("hunter2")),
+    );
+}
@@ -0,0 +1,131 @@
+/*
+ * services/site_settings/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::models::sea_orm_active_enums::{ScoreType, VoteType};
+use crate::models::site_settings::Model as SiteSettingsModel;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSiteSettings {
+    #[serde(default)]
+    pub allow_membership_by_apply: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub allow_membership_by_password: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub membership_password: ProvidedValue<Option<String>>,
+
+    #[serde(default)]
+    pub file_storage_size: ProvidedValue<i32>,
+
+    #[serde(default)]
+    pub use_ganalytics: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub private_landing_page: ProvidedValue<String>,
+
+    #[serde(default)]
+    pub max_private_members: ProvidedValue<i32>,
+
+    #[serde(default)]
+    pub max_private_viewers: ProvidedValue<i32>,
+
+    #[serde(default)]
+    pub hide_navigation_unauthorized: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub ssl_mode: ProvidedValue<Option<String>>,
+
+    #[serde(default)]
+    pub allow_members_invite: ProvidedValue<bool>,
+
+    #[serde(default)]
+    pub max_upload_file_size: ProvidedValue<i32>,
+
+    /// The site's default score type, which is stored on the site itself.
+    #[serde(default)]
+    pub score_type: ProvidedValue<ScoreType>,
+
+    /// The site's default vote type, which is stored on the site itself.
+    #[serde(default)]
+    pub vote_type: ProvidedValue<VoteType>,
+
+    pub user_id: i64,
+}
+
+/// Site settings as returned by the API.
+///
+/// The membership password is never returned, only whether one is set.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteSettingsOutput {
+    site_id: i64,
+    allow_membership_by_apply: bool,
+    allow_membership_by_password: bool,
+    has_membership_password: bool,
+    file_storage_size: i32,
+    use_ganalytics: bool,
+    private_landing_page: String,
+    max_private_members: i32,
+    max_private_viewers: i32,
+    hide_navigation_unauthorized: bool,
+    ssl_mode: Option<String>,
+    allow_members_invite: bool,
+    max_upload_file_size: i32,
+}
+
+impl From<SiteSettingsModel> for SiteSettingsOutput {
+    #[inline]
+    fn from(model: SiteSettingsModel) -> SiteSettingsOutput {
+        let SiteSettingsModel {
+            site_id,
+            allow_membership_by_apply,
+            allow_membership_by_password,
+            membership_password,
+            file_storage_size,
+            use_ganalytics,
+            private_landing_page,
+            max_private_members,
+            max_private_viewers,
+            hide_navigation_unauthorized,
+            ssl_mode,
+            allow_members_invite,
+            max_upload_file_size,
+        } = model;
+
+        SiteSettingsOutput {
+            site_id: i64::from(site_id),
+            allow_membership_by_apply,
+            allow_membership_by_password,
+            has_membership_password: membership_password.is_some(),
+            file_storage_size,
+            use_ganalytics,
+            private_landing_page,
+            max_private_members,
+            max_private_viewers,
+            hide_navigation_unauthorized,
+            ssl_mode,
+            allow_members_invite,
+            max_upload_file_size,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+/*
+ * services/tag/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Management of page tags across a site.
+//!
+//! The tags on each page's latest revision are mirrored into the `page_tag`
+//! table, which is kept up to date whenever a revision changes a page's tags.
+//! This allows listing and filtering pages by tag without scanning revisions.
+//!
+//! Sites may also restrict which tags can be used by populating the
+//! `site_allowed_tag` table. If a site has no allowed tags, any tag may be used.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::TagService;
+pub use self::structs::*;
@@ -0,0 +1,383 @@
+/*
+ * services/tag/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::json_utils::json_to_string_list;
+use crate::models::page::{self, Entity as Page, Model as PageModel};
+use crate::models::page_revision::Model as PageRevisionModel;
+use crate::models::page_tag::{self, Entity as PageTag};
+use crate::models::site_allowed_tag::{self, Entity as SiteAllowedTag};
+use crate::services::page::EditPage;
+use crate::services::permission::{Action, PermissionService};
+use crate::services::{PageService, RevisionService};
+use sea_orm::{QueryTrait, Select};
+
+#[derive(Debug)]
+pub struct TagService;
+
+impl TagService {
+    /// Replaces the stored tags for a page.
+    ///
+    /// This is called whenever a new revision changes a page's tags,
+    /// it does not create a revision itself.
+    pub async fn update_page(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        page_id: i64,
+        tags: &[String],
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+
+        PageTag::delete_many()
+            .filter(page_tag::Column::PageId.eq(page_id))
+            .exec(txn)
+            .await?;
+
+        let models = dedup_tags(tags.to_vec())
+            .into_iter()
+            .map(|tag| page_tag::ActiveModel {
+                page_id: Set(page_id),
+                site_id: Set(site_id),
+                tag: Set(tag),
+            })
+            .collect::<Vec<_>>();
+
+        if !models.is_empty() {
+            PageTag::insert_many(models).exec(txn).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets all tags used on a site, along with how many pages have each.
+    ///
+    /// Deleted pages are not counted.
+    pub async fn get_all(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+    ) -> Result<Vec<TagCount>> {
+        let txn = ctx.transaction();
+        let tags = tag_count_query(site_id)
+            .order_by_asc(page_tag::Column::Tag)
+            .into_model::<TagCount>()
+            .all(txn)
+            .await?;
+
+        Ok(tags)
+    }
+
+    /// Gets tags used on a site in alphabetical order, with how many pages have each.
+    ///
+    /// The `start_tag` argument gives the tag to start after, exclusive.
+    /// If empty, then it starts from the first tag.
+    pub async fn get_range(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        start_tag: &str,
+        limit: u64,
+    ) -> Result<Vec<TagCount>> {
+        let txn = ctx.transaction();
+        let tags = tag_count_query(site_id)
+            .filter(page_tag::Column::Tag.gt(start_tag))
+            .order_by_asc(page_tag::Column::Tag)
+            .limit(limit)
+            .into_model::<TagCount>()
+            .all(txn)
+            .await?;
+
+        Ok(tags)
+    }
+
+    /// Gets all extant pages on a site which match the given tag query.
+    pub async fn get_pages(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        TagQuery { all, any, none }: TagQuery,
+        limit: u64,
+    ) -> Result<Vec<PageModel>> {
+        let txn = ctx.transaction();
+
+        // Selects the IDs of pages which have any of the given tags.
+        let pages_with_tags = |tags: Vec<String>| {
+            PageTag::find()
+                .select_only()
+                .column(page_tag::Column::PageId)
+                .filter(page_tag::Column::Tag.is_in(tags))
+                .into_query()
+        };
+
+        let mut condition = Condition::all()
+            .add(page::Column::SiteId.eq(site_id))
+            .add(page::Column::DeletedAt.is_null());
+
+        for tag in all {
+            condition = condition
+                .add(page::Column::PageId.in_subquery(pages_with_tags(vec![tag])));
+        }
+
+        if !any.is_empty() {
+            condition =
+                condition.add(page::Column::PageId.in_subquery(pages_with_tags(any)));
+        }
+
+        if !none.is_empty() {
+            condition = condition
+                .add(page::Column::PageId.not_in_subquery(pages_with_tags(none)));
+        }
+
+        let pages = Page::find()
+            .filter(condition)
+            .order_by_asc(page::Column::Slug)
+            .limit(limit)
+            .all(txn)
+            .await?;
+
+        Ok(pages)
+    }
+
+    /// Renames a tag on every page in a site which has it.
+    ///
+    /// Each affected page gets a new revision with the updated tags.
+    /// If a page already has the new tag, the two are merged.
+    /// The site's list of allowed tags is updated to match.
+    pub async fn rename(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        RenameTag {
+            old_tag,
+            new_tag,
+            user_id,
+        }: RenameTag,
+    ) -> Result<RenameTagOutput> {
+        tide::log::info!(
+            "Renaming tag '{}' to '{}' in site ID {}",
+            old_tag,
+            new_tag,
+            site_id,
+        );
+
+        PermissionService::check_site(ctx, site_id, user_id, Action::Moderate).await?;
+
+        // Update the allowed list first, so the page edits pass validation
+        let mut allowed = Self::get_allowed(ctx, site_id).await?;
+        if allowed.contains(&old_tag) {
+            for tag in &mut allowed {
+                if tag == &old_tag {
+                    *tag = new_tag.clone();
+                }
+            }
+
+            Self::replace_allowed(ctx, site_id, allowed).await?;
+        }
+
+        // Find all pages with this tag
+        let page_ids = PageTag::find()
+            .join(JoinType::InnerJoin, page_tag::Relation::Page.def())
+            .filter(
+                Condition::all()
+                    .add(page_tag::Column::SiteId.eq(site_id))
+                    .add(page_tag::Column::Tag.eq(old_tag.as_str()))
+                    .add(page::Column::DeletedAt.is_null()),
+            )
+            .all(ctx.transaction())
+            .await?
+            .into_iter()
+            .map(|model| model.page_id)
+            .collect::<Vec<_>>();
+
+        let revisions = RevisionService::get_latest_many(ctx, site_id, &page_ids).await?;
+        let revision_comments = format!("Renamed tag '{old_tag}' to '{new_tag}'");
+
+        // Create a new revision for each page
+        //
+        // Pages with other tags which are no longer allowed are skipped
+        // and reported, rather than failing the whole rename. Tags are
+        // validated before anything is written, so nothing needs undoing.
+        let mut page_ids = Vec::new();
+        let mut skipped_page_ids = Vec::new();
+        for PageRevisionModel { page_id, tags, .. } in revisions {
+            let tags = replace_tag(json_to_string_list(tags)?, &old_tag, &new_tag);
+            let result = PageService::edit(
+                ctx,
+                site_id,
+                Reference::Id(page_id),
+                EditPage {
+                    tags: ProvidedValue::Set(tags),
+                    revision_comments: revision_comments.clone(),
+                    user_id,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            match result {
+                Ok(Some(_)) => page_ids.push(page_id),
+                Ok(None) => (),
+                Err(Error::TagNotAllowed) => {
+                    tide::log::warn!(
+                        "Skipping page ID {page_id}, its tags are not allowed"
+                    );
+                    skipped_page_ids.push(page_id);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(RenameTagOutput {
+            page_ids,
+            skipped_page_ids,
+        })
+    }
+
+    /// Gets the tags allowed on a site.
+    ///
+    /// An empty list means that any tag is allowed.
+    pub async fn get_allowed(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+    ) -> Result<Vec<String>> {
+        let txn = ctx.transaction();
+        let tags = SiteAllowedTag::find()
+            .filter(site_allowed_tag::Column::SiteId.eq(site_id))
+            .order_by_asc(site_allowed_tag::Column::Tag)
+            .all(txn)
+            .await?
+            .into_iter()
+            .map(|model| model.tag)
+            .collect();
+
+        Ok(tags)
+    }
+
+    /// Replaces the tags allowed on a site.
+    ///
+    /// Passing an empty list removes the restriction.
+    /// Pages which already have tags not in the list are not changed.
+    pub async fn set_allowed(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        SetAllowedTags { tags, user_id }: SetAllowedTags,
+    ) -> Result<()> {
+        PermissionService::check_site(ctx, site_id, user_id, Action::Manage).await?;
+        Self::replace_allowed(ctx, site_id, tags).await
+    }
+
+    async fn replace_allowed(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+
+        SiteAllowedTag::delete_many()
+            .filter(site_allowed_tag::Column::SiteId.eq(site_id))
+            .exec(txn)
+            .await?;
+
+        let models = dedup_tags(tags)
+            .into_iter()
+            .map(|tag| site_allowed_tag::ActiveModel {
+                site_id: Set(site_id),
+                tag: Set(tag),
+            })
+            .collect::<Vec<_>>();
+
+        if !models.is_empty() {
+            SiteAllowedTag::insert_many(models).exec(txn).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks that all of the given tags are permitted on the site.
+    ///
+    /// If any are not, this fails with `Error::TagNotAllowed`.
+    pub async fn validate(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        tags: &[String],
+    ) -> Result<()> {
+        let allowed = Self::get_allowed(ctx, site_id).await?;
+        if allowed.is_empty() {
+            return Ok(());
+        }
+
+        match tags.iter().find(|tag| !allowed.contains(tag)) {
+            None => Ok(()),
+            Some(tag) => {
+                tide::log::warn!("Tag '{}' is not allowed in site ID {}", tag, site_id);
+                Err(Error::TagNotAllowed)
+            }
+        }
+    }
+}
+
+fn dedup_tags(mut tags: Vec<String>) -> Vec<String> {
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+fn replace_tag(tags: Vec<String>, old_tag: &str, new_tag: &str) -> Vec<String> {
+    let tags = tags
+        .into_iter()
+        .map(|tag| if tag == old_tag { str!(new_tag) } else { tag })
+        .collect();
+
+    dedup_tags(tags)
+}
+
+/// Builds a query counting the extant pages with each tag on a site.
+fn tag_count_query(site_id: i64) -> Select<PageTag> {
+    PageTag::find()
+        .select_only()
+        .column(page_tag::Column::Tag)
+        .column_as(page_tag::Column::Tag.count(), "count")
+        .join(JoinType::InnerJoin, page_tag::Relation::Page.def())
+        .filter(
+            Condition::all()
+                .add(page_tag::Column::SiteId.eq(site_id))
+                .add(page::Column::DeletedAt.is_null()),
+        )
+        .group_by(page_tag::Column::Tag)
+}
+
+#[test]
+fn replace() {
+    macro_rules! check {
+        ($tags:expr, $old:expr, $new:expr, $expected:expr $(,)?) => {{
+            let tags = $tags.iter().map(|s: &&str| str!(s)).collect();
+            let actual = replace_tag(tags, $old, $new);
+            let expected: Vec<&str> = $expected;
+
+            assert_eq!(actual, expected, "Replaced tags didn't match expected");
+        }};
+    }
+
+    check!(["scp", "keter"], "keter", "euclid", vec!["euclid", "scp"]);
+    check!(
+        ["scp", "keter", "euclid"],
+        "keter",
+        "euclid",
+        vec!["euclid", "scp"]
+    );
+    check!(["scp", "safe"], "keter", "euclid", vec!["safe", "scp"]);
+    check!([] as [&str; 0], "keter", "euclid", vec![]);
+}
@@ -0,0 +1,71 @@
+/*
+ * services/tag/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use sea_orm::FromQueryResult;
+
+#[derive(Serialize, FromQueryResult, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TagCount {
+    pub tag: String,
+    pub count: i64,
+}
+
+/// A query for pages based on their tags.
+///
+/// Each of the fields are combined, so a page must satisfy all of them.
+/// Any empty field is ignored.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TagQuery {
+    /// Pages must have every one of these tags.
+    pub all: Vec<String>,
+
+    /// Pages must have at least one of these tags.
+    pub any: Vec<String>,
+
+    /// Pages must have none of these tags.
+    pub none: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameTag {
+    pub old_tag: String,
+    pub new_tag: String,
+    pub user_id: i64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAllowedTags {
+    pub tags: Vec<String>,
+    pub user_id: i64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameTagOutput {
+    /// The IDs of the pages which had a new revision created.
+    pub page_ids: Vec<i64>,
+
+    /// The IDs of the pages left unchanged, because their
+    /// other tags are not in the site's allowed list.
+    pub skipped_page_ids: Vec<i64>,
+}
@@ -27,6 +27,7 @@
 use super::prelude::*;
 use crate::hash::{sha512_hash, Hash, HASH_LENGTH};
 use crate::models::text::{self, Entity as Text};
+use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct TextService;
@@ -55,6 +56,25 @@ impl TextService {
         }
     }
 
+    /// Gets the contents of all the given hashes, keyed by hash.
+    ///
+    /// Hashes which are not present are omitted from the map.
+    pub async fn get_many(
+        ctx: &ServiceContext<'_>,
+        hashes: &[Vec<u8>],
+    ) -> Result<HashMap<Vec<u8>, String>> {
+        let txn = ctx.transaction();
+        let contents = Text::find()
+            .filter(text::Column::Hash.is_in(hashes.iter().cloned()))
+            .all(txn)
+            .await?
+            .into_iter()
+            .map(|model| (model.hash, model.contents))
+            .collect();
+
+        Ok(contents)
+    }
+
     #[inline]
     pub async fn exists(ctx: &ServiceContext<'_>, hash: &[u8]) -> Result<bool> {
         Self::get_optional(ctx, hash)
@@ -62,6 +82,37 @@ impl TextService {
             .map(|text| text.is_some())
     }
 
+    /// Gets text, checking the in-memory cache first.
+    ///
+    /// This is meant for compiled HTML, which is read far more often than
+    /// it changes. Since text is content-addressed, cached entries are never stale.
+    pub async fn get_cached(ctx: &ServiceContext<'_>, hash: &[u8]) -> Result<String> {
+        let key: Hash = hash.try_into().expect("Text hash has incorrect length");
+        let cache = ctx.render_cache();
+
+        if let Some(contents) = cache.get(&key) {
+            return Ok(contents);
+        }
+
+        let contents = Self::get(ctx, hash).await?;
+        cache.insert(key, contents.clone());
+        Ok(contents)
+    }
+
+    /// Like `get_maybe()`, but using the in-memory cache.
+    pub async fn get_cached_maybe(
+        ctx: &ServiceContext<'_>,
+        should_fetch: bool,
+        hash: &[u8],
+    ) -> Result<Option<String>> {
+        if should_fetch {
+            let text = Self::get_cached(ctx, hash).await?;
+            Ok(Some(text))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Possibly retrieve text, if a flag is set.
     ///
     /// This utility conditionally retrieves the
@@ -0,0 +1,38 @@
+/*
+ * services/token/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Single-use tokens sent to users by email.
+//!
+//! These are used to verify a user's email address, to confirm changing it to a
+//! new one, and to reset a forgotten password. Each token is random and signed
+//! with the configured secret, so forged tokens are rejected before touching the
+//! database. Only a hash of the token is stored, and it is marked as used once
+//! consumed. Tokens also expire after a period depending on their purpose.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod service;
+mod structs;
+
+pub use self::service::TokenService;
+pub use self::structs::*;
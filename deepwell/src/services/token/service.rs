@@ -0,0 +1,394 @@
+/*
+ * services/token/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::mail::{is_valid_address, Email};
+use crate::models::user_token::{self, Entity as UserToken, Model as UserTokenModel};
+use crate::models::users::{self, Entity as User};
+use crate::services::password::{Password, PasswordService};
+use crate::services::UserService;
+use hmac::{Hmac, Mac};
+use rand_core::{OsRng, RngCore};
+use sea_orm::sea_query::Expr;
+use sha2::{Digest, Sha256};
+
+/// The number of random bytes in each token.
+const TOKEN_LENGTH: usize = 32;
+
+#[derive(Debug)]
+pub struct TokenService;
+
+impl TokenService {
+    /// Issues a token to verify the user's current email address.
+    ///
+    /// Returns the email containing the token, which the caller must send
+    /// only after the transaction has been committed.
+    pub async fn send_email_verification(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+    ) -> Result<(IssueTokenOutput, Email)> {
+        let user = UserService::get(ctx, Reference::Id(user_id)).await?;
+        if user.email_verified_at.is_some() {
+            tide::log::error!("Email for user ID {user_id} is already verified");
+            return Err(Error::Conflict);
+        }
+
+        let purpose = TokenPurpose::EmailVerification;
+        let (token, model) =
+            Self::issue(ctx, user.id, purpose, Some(&user.email)).await?;
+        let email = Email {
+            to: user.email,
+            subject: str!("Verify your email address"),
+            body: format!(
+                "Hello {},\n\nUse the following code to verify your email address:\n\n{token}\n\nThis code expires in 24 hours.",
+                user.username,
+            ),
+        };
+
+        let output = IssueTokenOutput {
+            expires_at: model.expires_at,
+        };
+
+        Ok((output, email))
+    }
+
+    /// Issues a token to a new email address, to confirm changing to it.
+    ///
+    /// The user's email is not changed until the token is consumed.
+    /// As with `send_email_verification()`, the caller sends the returned email.
+    pub async fn send_email_change(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        RequestEmailChange { email: new_email }: RequestEmailChange,
+    ) -> Result<(IssueTokenOutput, Email)> {
+        if !is_valid_address(&new_email) {
+            tide::log::error!("Requested email address is not valid");
+            return Err(Error::BadRequest);
+        }
+
+        let user = UserService::get(ctx, Reference::Id(user_id)).await?;
+        Self::check_email_available(ctx, &new_email).await?;
+
+        let purpose = TokenPurpose::EmailChange;
+        let (token, model) = Self::issue(ctx, user.id, purpose, Some(&new_email)).await?;
+        let email = Email {
+            to: new_email,
+            subject: str!("Confirm your new email address"),
+            body: format!(
+                "Hello {},\n\nUse the following code to confirm changing your email to this address:\n\n{token}\n\nThis code expires in 24 hours.",
+                user.username,
+            ),
+        };
+
+        let output = IssueTokenOutput {
+            expires_at: model.expires_at,
+        };
+
+        Ok((output, email))
+    }
+
+    /// Issues a password reset token to the user with this email, if any.
+    ///
+    /// This succeeds even if no user has the address,
+    /// so that it cannot be used to discover which emails are registered.
+    /// If there is an email to send, the caller sends it after committing.
+    pub async fn send_password_reset(
+        ctx: &ServiceContext<'_>,
+        RequestPasswordReset { email: address }: RequestPasswordReset,
+    ) -> Result<Option<Email>> {
+        let txn = ctx.transaction();
+        let user = User::find()
+            .filter(
+                Condition::all()
+                    .add(users::Column::Email.eq(address.as_str()))
+                    .add(users::Column::DeletedAt.is_null()),
+            )
+            .one(txn)
+            .await?;
+
+        let user = match user {
+            Some(user) => user,
+            None => {
+                tide::log::warn!("No user with requested email, not sending reset");
+                return Ok(None);
+            }
+        };
+
+        let purpose = TokenPurpose::PasswordReset;
+        let (token, _) = Self::issue(ctx, user.id, purpose, None).await?;
+        let email = Email {
+            to: address,
+            subject: str!("Reset your password"),
+            body: format!(
+                "Hello {},\n\nUse the following code to reset your password:\n\n{token}\n\nThis code expires in 1 hour. If you did not request this, you can ignore this email.",
+                user.username,
+            ),
+        };
+
+        Ok(Some(email))
+    }
+
+    /// Consumes an email verification token, marking the user's email as verified.
+    pub async fn verify_email(ctx: &ServiceContext<'_>, token: &str) -> Result<()> {
+        let txn = ctx.transaction();
+        let model = Self::consume(ctx, TokenPurpose::EmailVerification, token).await?;
+        let user = UserService::get(ctx, Reference::Id(model.user_id)).await?;
+
+        // Don't verify a different address than the one the token was sent to
+        if model.email.as_ref() != Some(&user.email) {
+            tide::log::error!("Email for user ID {} changed since token issued", user.id);
+            return Err(Error::InvalidToken);
+        }
+
+        let user = users::ActiveModel {
+            id: Set(user.id),
+            email_verified_at: Set(Some(Utc::now())),
+            updated_at: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+
+        user.update(txn).await?;
+        Ok(())
+    }
+
+    /// Consumes an email change token, updating the user's email address.
+    pub async fn confirm_email_change(
+        ctx: &ServiceContext<'_>,
+        token: &str,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let model = Self::consume(ctx, TokenPurpose::EmailChange, token).await?;
+        let email = model.email.ok_or(Error::InvalidToken)?;
+
+        // It may have been taken since the token was issued
+        Self::check_email_available(ctx, &email).await?;
+
+        let user = users::ActiveModel {
+            id: Set(model.user_id),
+            email: Set(email),
+            email_verified_at: Set(Some(Utc::now())),
+            updated_at: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+
+        user.update(txn).await?;
+        Ok(())
+    }
+
+    /// Consumes a password reset token, setting the user's new password.
+    pub async fn reset_password(
+        ctx: &ServiceContext<'_>,
+        token: &str,
+        password: &Password,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let model = Self::consume(ctx, TokenPurpose::PasswordReset, token).await?;
+        let hash = PasswordService::hash(ctx, password).await?;
+
+        // Clear the remember token, so existing sessions are logged out
+        let user = users::ActiveModel {
+            id: Set(model.user_id),
+            password: Set(hash),
+            remember_token: Set(None),
+            updated_at: Set(Some(Utc::now())),
+            ..Default::default()
+        };
+
+        user.update(txn).await?;
+        Ok(())
+    }
+
+    /// Creates a new token, replacing any unused ones for the same purpose.
+    ///
+    /// Returns the token to send to the user, and the stored model.
+    async fn issue(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        purpose: TokenPurpose,
+        email: Option<&str>,
+    ) -> Result<(String, UserTokenModel)> {
+        let txn = ctx.transaction();
+        let secret = &ctx.config().token_secret;
+
+        UserToken::delete_many()
+            .filter(
+                Condition::all()
+                    .add(user_token::Column::UserId.eq(user_id))
+                    .add(user_token::Column::Purpose.eq(purpose.name()))
+                    .add(user_token::Column::UsedAt.is_null()),
+            )
+            .exec(txn)
+            .await?;
+
+        let (token, token_hash) = generate_token(secret, purpose);
+        let created_at = now();
+        let model = user_token::ActiveModel {
+            user_id: Set(user_id),
+            purpose: Set(str!(purpose.name())),
+            token_hash: Set(token_hash),
+            email: Set(email.map(String::from)),
+            created_at: Set(created_at),
+            expires_at: Set(created_at + purpose.lifetime()),
+            used_at: Set(None),
+            ..Default::default()
+        };
+
+        let model = model.insert(txn).await?;
+        tide::log::info!(
+            "Issued {} token for user ID {user_id} (expires {})",
+            purpose.name(),
+            model.expires_at,
+        );
+
+        Ok((token, model))
+    }
+
+    /// Marks a token as used, if it is valid, unused, and not expired.
+    ///
+    /// Fails with `Error::InvalidToken` otherwise.
+    async fn consume(
+        ctx: &ServiceContext<'_>,
+        purpose: TokenPurpose,
+        token: &str,
+    ) -> Result<UserTokenModel> {
+        let txn = ctx.transaction();
+        let secret = &ctx.config().token_secret;
+        let token_hash = match check_token(secret, purpose, token) {
+            Some(token_hash) => token_hash,
+            None => {
+                tide::log::warn!("Invalid signature on {} token", purpose.name());
+                return Err(Error::InvalidToken);
+            }
+        };
+
+        // Checked in the same statement, so a token can't be used twice concurrently
+        let now = now();
+        let result = UserToken::update_many()
+            .col_expr(user_token::Column::UsedAt, Expr::value(now))
+            .filter(
+                Condition::all()
+                    .add(user_token::Column::TokenHash.eq(token_hash.as_str()))
+                    .add(user_token::Column::Purpose.eq(purpose.name()))
+                    .add(user_token::Column::UsedAt.is_null())
+                    .add(user_token::Column::ExpiresAt.gt(now)),
+            )
+            .exec(txn)
+            .await?;
+
+        if result.rows_affected == 0 {
+            tide::log::warn!("{} token is used, expired, or missing", purpose.name());
+            return Err(Error::InvalidToken);
+        }
+
+        let model = UserToken::find()
+            .filter(user_token::Column::TokenHash.eq(token_hash))
+            .one(txn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        Ok(model)
+    }
+
+    async fn check_email_available(ctx: &ServiceContext<'_>, email: &str) -> Result<()> {
+        let txn = ctx.transaction();
+        let result = User::find()
+            .filter(
+                Condition::all()
+                    .add(users::Column::Email.eq(email))
+                    .add(users::Column::DeletedAt.is_null()),
+            )
+            .one(txn)
+            .await?;
+
+        if result.is_some() {
+            tide::log::error!("Email is already used by another user");
+            return Err(Error::Conflict);
+        }
+
+        Ok(())
+    }
+}
+
+// Helpers
+
+/// Generates a new signed token.
+///
+/// Returns the token to give to the user, and the hash to store.
+fn generate_token(secret: &str, purpose: TokenPurpose) -> (String, String) {
+    let mut bytes = [0; TOKEN_LENGTH];
+    OsRng.fill_bytes(&mut bytes);
+
+    let value = hex::encode(bytes);
+    let signature = hex::encode(sign(secret, purpose, &value).finalize().into_bytes());
+    let token_hash = hash_token(&value);
+    (format!("{value}.{signature}"), token_hash)
+}
+
+/// Checks the signature on a token, returning the hash to look it up with.
+fn check_token(secret: &str, purpose: TokenPurpose, token: &str) -> Option<String> {
+    let (value, signature) = token.trim().split_once('.')?;
+    let signature = hex::decode(signature).ok()?;
+
+    // Performs a constant-time comparison
+    sign(secret, purpose, value).verify_slice(&signature).ok()?;
+    Some(hash_token(value))
+}
+
+/// Signs the token value, including the purpose so tokens can't be used for another.
+fn sign(secret: &str, purpose: TokenPurpose, value: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+
+    mac.update(purpose.name().as_bytes());
+    mac.update(b":");
+    mac.update(value.as_bytes());
+    mac
+}
+
+fn hash_token(value: &str) -> String {
+    hex::encode(Sha256::digest(value.as_bytes()))
+}
+
+#[test]
+fn tokens() {
+    const SECRET: &str =
+        "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    let purpose = TokenPurpose::PasswordReset;
+    let (token, token_hash) = generate_token(SECRET, purpose);
+    assert!(!token.contains(&token_hash), "Token hash included in token");
+    assert_eq!(check_token(SECRET, purpose, &token), Some(token_hash));
+
+    // Wrong purpose or secret
+    assert_eq!(check_token(SECRET, TokenPurpose::EmailChange, &token), None);
+    assert_eq!(check_token(&SECRET[1..], purpose, &token), None);
+
+    // Tampered tokens
+    let (value, signature) = token.split_once('.').unwrap();
+    let (other, _) = generate_token(SECRET, purpose);
+    let (other_value, _) = other.split_once('.').unwrap();
+    assert_eq!(
+        check_token(SECRET, purpose, &format!("{other_value}.{signature}")),
+        None
+    );
+    assert_eq!(check_token(SECRET, purpose, value), None);
+    assert_eq!(check_token(SECRET, purpose, &format!("{value}.")), None);
+    assert_eq!(check_token(SECRET, purpose, ""), None);
+}
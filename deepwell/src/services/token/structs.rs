@@ -0,0 +1,96 @@
+/*
+ * services/token/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::password::Password;
+use chrono::Duration;
+use sea_orm::entity::prelude::DateTimeWithTimeZone;
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum TokenPurpose {
+    EmailVerification,
+    EmailChange,
+    PasswordReset,
+}
+
+impl TokenPurpose {
+    pub fn name(self) -> &'static str {
+        match self {
+            TokenPurpose::EmailVerification => "email-verification",
+            TokenPurpose::EmailChange => "email-change",
+            TokenPurpose::PasswordReset => "password-reset",
+        }
+    }
+
+    /// How long after being issued the token may be used.
+    pub fn lifetime(self) -> Duration {
+        match self {
+            TokenPurpose::EmailVerification | TokenPurpose::EmailChange => {
+                Duration::hours(24)
+            }
+            TokenPurpose::PasswordReset => Duration::hours(1),
+        }
+    }
+}
+
+impl FromStr for TokenPurpose {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<TokenPurpose> {
+        match value {
+            "email-verification" => Ok(TokenPurpose::EmailVerification),
+            "email-change" => Ok(TokenPurpose::EmailChange),
+            "password-reset" => Ok(TokenPurpose::PasswordReset),
+            _ => Err(Error::InvalidEnumValue),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestEmailChange {
+    pub email: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestPasswordReset {
+    pub email: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsumeToken {
+    pub token: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPassword {
+    pub token: String,
+    pub password: Password,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueTokenOutput {
+    pub expires_at: DateTimeWithTimeZone,
+}
@@ -19,8 +19,13 @@
  */
 
 use super::prelude::*;
+use crate::models::user_alias::{self, Entity as UserAlias, Model as UserAliasModel};
 use crate::models::users::{self, Entity as User, Model as UserModel};
+use crate::services::audit::{AuditAction, AuditTarget, CreateAuditEntry};
+use crate::services::{AuditService, PasswordService};
 use crate::utils::replace_in_place;
+use chrono::Duration;
+use serde_json::json;
 use wikidot_normalize::normalize;
 
 #[derive(Debug)]
@@ -54,15 +59,19 @@ impl UserService {
             return Err(Error::Conflict);
         }
 
+        Self::check_alias_conflicts(ctx, None, &slug).await?;
+        let password = PasswordService::hash(ctx, &input.password).await?;
+
         // Insert new model
         let user = users::ActiveModel {
             username: Set(input.username),
             slug: Set(slug.clone()),
             email: Set(input.email),
             email_verified_at: Set(None),
-            password: Set(input.password),
+            password: Set(password),
             multi_factor_secret: Set(None),
             multi_factor_recovery_codes: Set(None),
+            multi_factor_last_step: Set(None),
             remember_token: Set(None),
             language: Set(input.language),
             karma_points: Set(0),
@@ -101,14 +110,20 @@ impl UserService {
         let user = match reference {
             Reference::Id(id) => User::find_by_id(id).one(txn).await?,
             Reference::Slug(slug) => {
-                User::find()
+                let user = User::find()
                     .filter(
                         Condition::all()
                             .add(users::Column::Slug.eq(slug))
                             .add(users::Column::DeletedAt.is_null()),
                     )
                     .one(txn)
-                    .await?
+                    .await?;
+
+                // If no user has this slug, see if it is a former name
+                match user {
+                    Some(user) => Some(user),
+                    None => Self::get_by_alias(ctx, slug).await?,
+                }
             }
         };
 
@@ -125,6 +140,31 @@ impl UserService {
         }
     }
 
+    /// Finds the user with the given username or email, for logging in.
+    ///
+    /// Unlike `get_optional()`, former usernames are not accepted.
+    pub async fn get_by_name_or_email(
+        ctx: &ServiceContext<'_>,
+        name_or_email: &str,
+    ) -> Result<Option<UserModel>> {
+        let txn = ctx.transaction();
+        let slug = get_user_slug(name_or_email);
+        let user = User::find()
+            .filter(
+                Condition::all()
+                    .add(
+                        Condition::any()
+                            .add(users::Column::Email.eq(name_or_email))
+                            .add(users::Column::Slug.eq(slug)),
+                    )
+                    .add(users::Column::DeletedAt.is_null()),
+            )
+            .one(txn)
+            .await?;
+
+        Ok(user)
+    }
+
     pub async fn update(
         ctx: &ServiceContext<'_>,
         reference: Reference<'_>,
@@ -136,41 +176,52 @@ impl UserService {
 
         // Add each field
         if let ProvidedValue::Set(username) = input.username {
-            // TODO: add old alias
-            // TODO: check for conflicts
-
             let slug = get_user_slug(&username);
+
+            // Changes which only affect capitalization or punctuation
+            // keep the same slug, and are not counted as renames.
+            if slug != model.slug {
+                let changes = Self::get_name_changes(ctx, &model).await?;
+                if changes >= ctx.config().maximum_name_changes {
+                    tide::log::error!(
+                        "User ID {} has no username changes remaining",
+                        model.id,
+                    );
+                    return Err(Error::InsufficientNameChanges);
+                }
+
+                Self::check_conflicts(ctx, model.id, &slug).await?;
+
+                // Keep old name, so links to it still work
+                let alias = user_alias::ActiveModel {
+                    user_id: Set(model.id),
+                    username: Set(model.username.clone()),
+                    slug: Set(model.slug.clone()),
+                    created_at: Set(now()),
+                    ..Default::default()
+                };
+
+                alias.insert(txn).await?;
+                user.username_changes = Set(changes + 1);
+            }
+
             user.username = Set(username);
-            user.username_changes = Set(user.username_changes.unwrap() + 1);
             user.slug = Set(slug);
         }
 
+        // Changing email directly means it is no longer verified,
+        // see TokenService for changes confirmed by the user.
         if let ProvidedValue::Set(email) = input.email {
-            user.email = Set(email);
-        }
-
-        if let ProvidedValue::Set(email_verified) = input.email_verified {
-            let value = if email_verified {
-                Some(Utc::now())
-            } else {
-                None
-            };
+            if email != model.email {
+                user.email_verified_at = Set(None);
+            }
 
-            user.email_verified_at = Set(value);
+            user.email = Set(email);
         }
 
         if let ProvidedValue::Set(password) = input.password {
-            user.password = Set(password);
-        }
-
-        if let ProvidedValue::Set(multi_factor_secret) = input.multi_factor_secret {
-            user.multi_factor_secret = Set(multi_factor_secret);
-        }
-
-        if let ProvidedValue::Set(multi_factor_recovery_codes) =
-            input.multi_factor_recovery_codes
-        {
-            user.multi_factor_recovery_codes = Set(multi_factor_recovery_codes);
+            let hash = PasswordService::hash(ctx, &password).await?;
+            user.password = Set(hash);
         }
 
         if let ProvidedValue::Set(remember_token) = input.remember_token {
@@ -225,6 +276,7 @@ impl UserService {
     pub async fn delete(
         ctx: &ServiceContext<'_>,
         reference: Reference<'_>,
+        DeleteUser { deleted_by }: DeleteUser,
     ) -> Result<UserModel> {
         let txn = ctx.transaction();
         let model = Self::get(ctx, reference).await?;
@@ -236,8 +288,153 @@ impl UserService {
 
         // Update and return
         user.update(txn).await?;
+
+        // Only record identifying fields, the full model includes secrets
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: None,
+                user_id: Some(deleted_by),
+                action: AuditAction::UserDelete,
+                target: AuditTarget::User(model.id),
+                before: Some(json!({
+                    "username": model.username,
+                    "slug": model.slug,
+                })),
+                after: None,
+            },
+        )
+        .await?;
+
         Ok(model)
     }
+
+    /// Gets the former usernames of a user, most recent first.
+    pub async fn get_aliases(
+        ctx: &ServiceContext<'_>,
+        reference: Reference<'_>,
+    ) -> Result<Vec<UserAliasModel>> {
+        let txn = ctx.transaction();
+        let user = Self::get(ctx, reference).await?;
+        let aliases = UserAlias::find()
+            .filter(user_alias::Column::UserId.eq(user.id))
+            .order_by_desc(user_alias::Column::CreatedAt)
+            .all(txn)
+            .await?;
+
+        Ok(aliases)
+    }
+
+    /// Finds the user who most recently had this slug as a former name.
+    async fn get_by_alias(
+        ctx: &ServiceContext<'_>,
+        slug: &str,
+    ) -> Result<Option<UserModel>> {
+        let txn = ctx.transaction();
+        let alias = UserAlias::find()
+            .filter(user_alias::Column::Slug.eq(slug))
+            .order_by_desc(user_alias::Column::CreatedAt)
+            .one(txn)
+            .await?;
+
+        let user = match alias {
+            None => None,
+            Some(alias) => {
+                User::find()
+                    .filter(
+                        Condition::all()
+                            .add(users::Column::Id.eq(alias.user_id))
+                            .add(users::Column::DeletedAt.is_null()),
+                    )
+                    .one(txn)
+                    .await?
+            }
+        };
+
+        Ok(user)
+    }
+
+    /// Gets how many times the user has been renamed in the current period.
+    ///
+    /// The period begins with the first rename made after the previous one ended,
+    /// so if that rename is old enough, the count starts over at zero.
+    async fn get_name_changes(ctx: &ServiceContext<'_>, user: &UserModel) -> Result<i16> {
+        if user.username_changes <= 0 {
+            return Ok(0);
+        }
+
+        let txn = ctx.transaction();
+        let period = Duration::days(ctx.config().name_change_period_days.into());
+        let period_start = UserAlias::find()
+            .filter(user_alias::Column::UserId.eq(user.id))
+            .order_by_desc(user_alias::Column::CreatedAt)
+            .offset((user.username_changes - 1) as u64)
+            .one(txn)
+            .await?;
+
+        match period_start {
+            Some(alias) if alias.created_at > now() - period => Ok(user.username_changes),
+            _ => Ok(0),
+        }
+    }
+
+    /// Checks that no other user has, or recently had, the given slug.
+    ///
+    /// If so, this method fails with `Error::Conflict`.
+    async fn check_conflicts(
+        ctx: &ServiceContext<'_>,
+        user_id: i64,
+        slug: &str,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let result = User::find()
+            .filter(
+                Condition::all()
+                    .add(users::Column::Slug.eq(slug))
+                    .add(users::Column::Id.ne(user_id))
+                    .add(users::Column::DeletedAt.is_null()),
+            )
+            .one(txn)
+            .await?;
+
+        if result.is_some() {
+            tide::log::error!("User with slug '{slug}' already exists, cannot rename");
+            return Err(Error::Conflict);
+        }
+
+        Self::check_alias_conflicts(ctx, Some(user_id), slug).await
+    }
+
+    /// Checks that the slug is not a recently released name of another user.
+    ///
+    /// Users may always take back their own former names.
+    async fn check_alias_conflicts(
+        ctx: &ServiceContext<'_>,
+        user_id: Option<i64>,
+        slug: &str,
+    ) -> Result<()> {
+        let txn = ctx.transaction();
+        let reservation = Duration::days(ctx.config().name_reservation_days.into());
+        let result = UserAlias::find()
+            .filter(
+                Condition::all()
+                    .add(user_alias::Column::Slug.eq(slug))
+                    .add(user_alias::Column::CreatedAt.gt(now() - reservation))
+                    .add_option(user_id.map(|id| user_alias::Column::UserId.ne(id))),
+            )
+            .one(txn)
+            .await?;
+
+        if let Some(alias) = result {
+            tide::log::error!(
+                "Slug '{slug}' is reserved as a former name of user ID {}",
+                alias.user_id,
+            );
+            return Err(Error::Conflict);
+        }
+
+        Ok(())
+    }
 }
 
 // Helpers
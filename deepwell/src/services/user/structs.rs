@@ -20,6 +20,7 @@
 
 use super::prelude::*;
 use crate::models::users::Model as UserModel;
+use crate::services::password::Password;
 use chrono::{DateTime, NaiveDate, Utc};
 use std::collections::HashMap;
 
@@ -28,7 +29,7 @@ use std::collections::HashMap;
 pub struct CreateUser {
     pub username: String,
     pub email: String,
-    pub password: String,
+    pub password: Password,
     pub language: Option<String>,
 }
 
@@ -44,10 +45,7 @@ pub struct CreateUserOutput {
 pub struct UpdateUser {
     pub username: ProvidedValue<String>,
     pub email: ProvidedValue<String>,
-    pub email_verified: ProvidedValue<bool>,
-    pub password: ProvidedValue<String>,
-    pub multi_factor_secret: ProvidedValue<Option<String>>,
-    pub multi_factor_recovery_codes: ProvidedValue<Option<String>>,
+    pub password: ProvidedValue<Password>,
     pub remember_token: ProvidedValue<Option<String>>,
     pub language: ProvidedValue<Option<String>>,
     pub karma_points: ProvidedValue<i32>,
@@ -60,6 +58,13 @@ pub struct UpdateUser {
     pub avatar_path: ProvidedValue<Option<String>>,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteUser {
+    /// The user performing the deletion, recorded in the audit log.
+    pub deleted_by: i64,
+}
+
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserIdentityOutput {
@@ -77,11 +82,20 @@ impl From<&UserModel> for UserIdentityOutput {
             username: user.username.clone(),
             tinyavatar: None, // TODO
             karma: user.karma_level as u8,
-            role: String::new(), // TODO
+            role: String::new(),
         }
     }
 }
 
+impl UserIdentityOutput {
+    /// Sets the slug of the role the user holds in a site.
+    #[inline]
+    pub fn with_role(mut self, role: String) -> Self {
+        self.role = role;
+        self
+    }
+}
+
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserInfoOutput {
@@ -108,6 +122,14 @@ impl From<&UserModel> for UserInfoOutput {
     }
 }
 
+impl UserInfoOutput {
+    #[inline]
+    pub fn with_role(mut self, role: String) -> Self {
+        self.identity = self.identity.with_role(role);
+        self
+    }
+}
+
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserProfileOutput {
@@ -133,3 +155,11 @@ impl From<&UserModel> for UserProfileOutput {
         }
     }
 }
+
+impl UserProfileOutput {
+    #[inline]
+    pub fn with_role(mut self, role: String) -> Self {
+        self.info = self.info.with_role(role);
+        self
+    }
+}
@@ -0,0 +1,216 @@
+/*
+ * services/vote/analytics.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Computation of aggregate statistics from vote history.
+//!
+//! These are computed from the raw `page_vote` rows, including ones
+//! which were deleted or disabled, since we need them to reconstruct
+//! what a score was at a previous point in time.
+
+use super::prelude::*;
+use crate::models::page_vote::Model as PageVoteModel;
+use crate::utils::UTC;
+use chrono::{DateTime, FixedOffset, TimeZone};
+use std::collections::BTreeMap;
+
+/// The maximum number of points which can be produced for a score history.
+///
+/// If a request would exceed this, then a larger time bucket should be used.
+const MAX_SCORE_HISTORY_POINTS: i64 = 10_000;
+
+pub fn build_analytics(
+    votes: &[PageVoteModel],
+    bucket: VoteTimeBucket,
+    now: DateTime<FixedOffset>,
+) -> Result<VoteAnalytics> {
+    let score_history = build_score_history(votes, bucket, now)?;
+    let mut votes_per_day = BTreeMap::new();
+    let mut breakdown = VoteBreakdown::default();
+    let mut disabled = DisabledVoteEffect::default();
+
+    for vote in votes {
+        let date = vote.created_at.naive_utc().date();
+        *votes_per_day.entry(date).or_insert(0) += 1;
+
+        // Only consider votes which haven't been removed or changed
+        if vote.deleted_at.is_some() {
+            continue;
+        }
+
+        let value = i64::from(vote.value);
+        if vote.disabled_at.is_some() {
+            // If this vote were active, it would have contributed its value.
+            // Since it's disabled, the effect on the score is the opposite.
+            disabled.count += 1;
+            disabled.net_effect -= value;
+            continue;
+        }
+
+        if value > 0 {
+            breakdown.upvotes += 1;
+        } else if value < 0 {
+            breakdown.downvotes += 1;
+        }
+
+        *breakdown.by_value.entry(vote.value).or_insert(0) += 1;
+    }
+
+    let votes_per_day = votes_per_day
+        .into_iter()
+        .map(|(date, count)| DailyVoteCount { date, count })
+        .collect();
+
+    Ok(VoteAnalytics {
+        score_history,
+        votes_per_day,
+        breakdown,
+        disabled,
+    })
+}
+
+/// Reconstructs the net score at the end of each time bucket.
+///
+/// A vote is considered to count from its creation until it was
+/// deleted (including being replaced by a changed vote) or disabled,
+/// whichever happened first.
+fn build_score_history(
+    votes: &[PageVoteModel],
+    bucket: VoteTimeBucket,
+    now: DateTime<FixedOffset>,
+) -> Result<Vec<ScorePoint>> {
+    let first_vote_at = match votes.iter().map(|vote| vote.created_at).min() {
+        Some(timestamp) => timestamp.timestamp(),
+        None => return Ok(Vec::new()),
+    };
+
+    let size = bucket.seconds();
+    let start = first_vote_at - first_vote_at.rem_euclid(size);
+    let point_count = (now.timestamp() - start) / size + 1;
+
+    if point_count > MAX_SCORE_HISTORY_POINTS {
+        tide::log::error!(
+            "Score history would have {} points, exceeding the maximum of {}",
+            point_count,
+            MAX_SCORE_HISTORY_POINTS,
+        );
+
+        return Err(Error::BadRequest);
+    }
+
+    // Build list of (timestamp, score change, vote count change)
+    let mut events = Vec::with_capacity(votes.len() * 2);
+    for vote in votes {
+        let value = i64::from(vote.value);
+        events.push((vote.created_at.timestamp(), value, 1));
+
+        let ended_at = match (vote.deleted_at, vote.disabled_at) {
+            (Some(deleted_at), Some(disabled_at)) => Some(deleted_at.min(disabled_at)),
+            (deleted_at, disabled_at) => deleted_at.or(disabled_at),
+        };
+
+        if let Some(ended_at) = ended_at {
+            events.push((ended_at.timestamp(), -value, -1));
+        }
+    }
+
+    events.sort_unstable_by_key(|&(timestamp, _, _)| timestamp);
+
+    // Walk through each bucket, applying all events within it
+    let mut events = events.into_iter().peekable();
+    let mut points = Vec::with_capacity(point_count as usize);
+    let mut score = 0;
+    let mut vote_count = 0;
+
+    for index in 0..point_count {
+        let bucket_start = start + index * size;
+        let bucket_end = bucket_start + size;
+
+        while let Some((_, score_change, count_change)) =
+            events.next_if(|&(timestamp, _, _)| timestamp < bucket_end)
+        {
+            score += score_change;
+            vote_count += count_change;
+        }
+
+        points.push(ScorePoint {
+            time: UTC.timestamp(bucket_start, 0),
+            score,
+            votes: vote_count,
+        });
+    }
+
+    Ok(points)
+}
+
+#[test]
+fn analytics() {
+    use chrono::NaiveDate;
+
+    fn vote(
+        value: i16,
+        created_at: i64,
+        deleted_at: Option<i64>,
+        disabled_at: Option<i64>,
+    ) -> PageVoteModel {
+        let time = |hours: i64| UTC.timestamp(hours * 3600, 0);
+
+        PageVoteModel {
+            page_vote_id: 0,
+            created_at: time(created_at),
+            deleted_at: deleted_at.map(time),
+            disabled_at: disabled_at.map(time),
+            disabled_by: disabled_at.map(|_| 1),
+            page_id: 1,
+            user_id: 1,
+            value,
+        }
+    }
+
+    let votes = [
+        vote(1, 0, None, None),
+        vote(1, 1, Some(2), None), // changed to a downvote
+        vote(-1, 2, None, None),
+        vote(1, 25, None, Some(27)),
+        vote(1, 26, None, None),
+    ];
+
+    let now = UTC.timestamp(49 * 3600, 0);
+    let output = build_analytics(&votes, VoteTimeBucket::Day, now)
+        .expect("Unable to build vote analytics");
+
+    let history: Vec<_> = output
+        .score_history
+        .iter()
+        .map(|point| (point.score, point.votes))
+        .collect();
+
+    assert_eq!(history, [(0, 2), (1, 3), (1, 3)]);
+    assert_eq!(output.votes_per_day.len(), 2);
+    assert_eq!(
+        output.votes_per_day[0].date,
+        NaiveDate::from_ymd(1970, 1, 1)
+    );
+    assert_eq!(output.votes_per_day[0].count, 3);
+    assert_eq!(output.votes_per_day[1].count, 2);
+    assert_eq!(output.breakdown.upvotes, 2);
+    assert_eq!(output.breakdown.downvotes, 1);
+    assert_eq!(output.disabled.count, 1);
+    assert_eq!(output.disabled.net_effect, -1);
+}
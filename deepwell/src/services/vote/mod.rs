@@ -23,6 +23,7 @@ mod prelude {
     pub use super::structs::*;
 }
 
+mod analytics;
 mod service;
 mod structs;
 
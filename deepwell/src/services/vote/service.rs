@@ -18,8 +18,13 @@
  * along with this program. If not, see <http://www.gnu.org/licenses/>.
  */
 
+use super::analytics::build_analytics;
 use super::prelude::*;
 use crate::models::page_vote::{self, Entity as PageVote, Model as PageVoteModel};
+use crate::services::audit::{AuditAction, AuditTarget, CreateAuditEntry};
+use crate::services::permission::{Action, PermissionService};
+use crate::services::score::ScoreSettings;
+use crate::services::{AuditService, PageService, ScoreService};
 use sea_orm::IntoActiveModel;
 
 #[derive(Debug)]
@@ -47,6 +52,41 @@ impl VoteService {
             value,
         );
 
+        // Ensure the user is permitted to vote on this page
+        let page = PageService::get_direct(ctx, page_id).await?;
+        PermissionService::check_category(
+            ctx,
+            page.site_id,
+            page.page_category_id,
+            user_id,
+            Action::Vote,
+        )
+        .await?;
+
+        // Ensure the vote is permitted by the page's settings
+        let ScoreSettings {
+            score_type,
+            vote_type,
+        } = ScoreService::get_settings(ctx, page_id).await?;
+
+        if !ScoreService::scorer_for(score_type).accepts_vote_type(vote_type) {
+            tide::log::error!(
+                "Page ID {} has score type {:?}, which does not accept votes of type {:?}",
+                page_id,
+                score_type,
+                vote_type,
+            );
+
+            return Err(Error::InvalidVote);
+        }
+
+        if !vote_type.accepts_value(value) {
+            tide::log::warn!(
+                "Vote value {value} is not valid for vote type {vote_type:?}"
+            );
+            return Err(Error::InvalidVote);
+        }
+
         // Get previous vote, if any
         let reference = VoteReference::Pair(GetVote { page_id, user_id });
         if let Some(vote) = Self::get_optional(ctx, reference).await? {
@@ -130,8 +170,21 @@ impl VoteService {
         );
 
         let txn = ctx.transaction();
-        let mut vote = Self::get(ctx, reference).await?.into_active_model();
+        let vote = Self::get(ctx, reference).await?;
+
+        // Only moderators may enable or disable votes
+        let page = PageService::get_direct(ctx, vote.page_id).await?;
+        PermissionService::check_category(
+            ctx,
+            page.site_id,
+            page.page_category_id,
+            acting_user_id,
+            Action::Moderate,
+        )
+        .await?;
 
+        let before = serde_json::to_value(&vote)?;
+        let mut vote = vote.into_active_model();
         if enable {
             // Clear "disabled" field.
             vote.disabled_at = Set(None);
@@ -143,6 +196,24 @@ impl VoteService {
         }
 
         let model = vote.update(txn).await?;
+
+        AuditService::record(
+            ctx,
+            CreateAuditEntry {
+                site_id: Some(page.site_id),
+                user_id: Some(acting_user_id),
+                action: if enable {
+                    AuditAction::VoteEnable
+                } else {
+                    AuditAction::VoteDisable
+                },
+                target: AuditTarget::Vote(model.page_vote_id),
+                before: Some(before),
+                after: Some(serde_json::to_value(&model)?),
+            },
+        )
+        .await?;
+
         Ok(model)
     }
 
@@ -208,6 +279,28 @@ impl VoteService {
         Ok(vote_count)
     }
 
+    /// Gets aggregate statistics about votes for either a page or a user.
+    ///
+    /// Unlike `get_history()`, this considers all votes regardless of
+    /// deletion or disablement, since these are needed to reconstruct
+    /// historical scores. See `VoteAnalytics` for the information returned.
+    pub async fn get_analytics(
+        ctx: &ServiceContext<'_>,
+        kind: VoteHistoryKind,
+        bucket: VoteTimeBucket,
+    ) -> Result<VoteAnalytics> {
+        let txn = ctx.transaction();
+        let condition = Self::build_history_condition(kind, 0, None, None);
+
+        let votes = PageVote::find()
+            .filter(condition)
+            .order_by_asc(page_vote::Column::CreatedAt)
+            .all(txn)
+            .await?;
+
+        build_analytics(&votes, bucket, now())
+    }
+
     fn build_history_condition(
         kind: VoteHistoryKind,
         start_id: i64,
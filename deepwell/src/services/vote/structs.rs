@@ -18,6 +18,10 @@
  * along with this program. If not, see <http://www.gnu.org/licenses/>.
  */
 
+use chrono::NaiveDate;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use std::collections::BTreeMap;
+
 pub type VoteValue = i16;
 
 #[derive(Deserialize, Debug, Copy, Clone)]
@@ -66,3 +70,89 @@ pub struct VoteAction {
     pub enable: bool,
     pub acting_user_id: i64,
 }
+
+#[derive(Deserialize, Debug, Copy, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVoteAnalytics {
+    #[serde(flatten)]
+    pub kind: VoteHistoryKind,
+
+    #[serde(default)]
+    pub bucket: VoteTimeBucket,
+}
+
+/// The size of each time period in a score history.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VoteTimeBucket {
+    Hour,
+    Day,
+    Week,
+}
+
+impl VoteTimeBucket {
+    pub fn seconds(self) -> i64 {
+        match self {
+            VoteTimeBucket::Hour => 60 * 60,
+            VoteTimeBucket::Day => 24 * 60 * 60,
+            VoteTimeBucket::Week => 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+impl Default for VoteTimeBucket {
+    #[inline]
+    fn default() -> Self {
+        VoteTimeBucket::Day
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteAnalytics {
+    pub score_history: Vec<ScorePoint>,
+    pub votes_per_day: Vec<DailyVoteCount>,
+    pub breakdown: VoteBreakdown,
+    pub disabled: DisabledVoteEffect,
+}
+
+/// The net score and number of active votes at the end of a time bucket.
+///
+/// The `time` field is the start of the bucket.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScorePoint {
+    pub time: DateTimeWithTimeZone,
+    pub score: i64,
+    pub votes: i64,
+}
+
+/// The number of votes cast on a particular day (in UTC).
+///
+/// Changing a vote counts as casting a new one.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyVoteCount {
+    pub date: NaiveDate,
+    pub count: u64,
+}
+
+/// Counts of currently active votes, by direction and by value.
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteBreakdown {
+    pub upvotes: u64,
+    pub downvotes: u64,
+    pub by_value: BTreeMap<VoteValue, u64>,
+}
+
+/// Information about votes which are currently disabled.
+///
+/// The `net_effect` field is how much the net score has changed
+/// as a result of these votes being disabled.
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DisabledVoteEffect {
+    pub count: u64,
+    pub net_effect: i64,
+}
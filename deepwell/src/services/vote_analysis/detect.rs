@@ -0,0 +1,309 @@
+/*
+ * services/vote_analysis/detect.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::prelude::*;
+use crate::services::vote::VoteValue;
+use chrono::Duration;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// How many days old an account can be and still be considered "new" when voting.
+const NEW_ACCOUNT_DAYS: i64 = 7;
+
+/// The length of time in which new account votes are counted towards a burst.
+const BURST_WINDOW_MINUTES: i64 = 60;
+
+/// The number of new account votes on one page in one window to be a burst.
+const BURST_MIN_VOTES: usize = 5;
+
+/// The number of pages two users must vote the same way on to be considered linked.
+const BLOC_MIN_SHARED_VOTES: usize = 5;
+
+/// The Jaccard similarity two users' votes must have to be considered linked.
+const BLOC_MIN_SIMILARITY: f64 = 0.9;
+
+/// The number of votes a user must have cast to be flagged for only
+/// voting on the pages of a single author.
+const FIXATION_MIN_VOTES: usize = 5;
+
+/// Runs all detectors over the given votes, returning any flags raised.
+pub fn detect(records: &[VoteRecord]) -> Vec<VoteFlag> {
+    let mut flags = Vec::new();
+    detect_bursts(records, &mut flags);
+    detect_blocs(records, &mut flags);
+    detect_author_fixation(records, &mut flags);
+    flags
+}
+
+fn is_new_account(record: &VoteRecord) -> bool {
+    // Accounts without a creation date predate Wikijump, so they're not new.
+    match record.user_created_at {
+        Some(user_created_at) => {
+            let account_age = record.created_at.with_timezone(&Utc) - user_created_at;
+            account_age < Duration::days(NEW_ACCOUNT_DAYS)
+        }
+        None => false,
+    }
+}
+
+/// Finds many votes cast on one page by new accounts in a short window.
+fn detect_bursts(records: &[VoteRecord], flags: &mut Vec<VoteFlag>) {
+    let window = Duration::minutes(BURST_WINDOW_MINUTES);
+    let mut by_page = BTreeMap::<_, Vec<_>>::new();
+
+    for record in records.iter().filter(|record| is_new_account(record)) {
+        by_page.entry(record.page_id).or_default().push(record);
+    }
+
+    for (page_id, mut votes) in by_page {
+        votes.sort_by_key(|record| record.created_at);
+
+        let mut start = 0;
+        while start < votes.len() {
+            let window_end = votes[start].created_at + window;
+            let end = start
+                + votes[start..]
+                    .iter()
+                    .take_while(|record| record.created_at <= window_end)
+                    .count();
+
+            if end - start < BURST_MIN_VOTES {
+                start += 1;
+                continue;
+            }
+
+            let burst = &votes[start..end];
+            flags.push(VoteFlag::NewAccountBurst(NewAccountBurst {
+                page_id,
+                window_start: burst[0].created_at,
+                window_end: burst[burst.len() - 1].created_at,
+                votes: burst
+                    .iter()
+                    .map(|record| FlaggedVote::from(*record))
+                    .collect(),
+            }));
+
+            start = end;
+        }
+    }
+}
+
+/// Finds groups of users who nearly always vote the same way on the same pages.
+///
+/// Pairs of users are linked if the pages they agreed on make up most
+/// of the pages either of them voted on. Linked users are then gathered
+/// into groups, so a ring of several accounts produces only one flag.
+fn detect_blocs(records: &[VoteRecord], flags: &mut Vec<VoteFlag>) {
+    let mut by_user = BTreeMap::<_, BTreeMap<_, _>>::new();
+    for record in records {
+        by_user
+            .entry(record.user_id)
+            .or_default()
+            .insert(record.page_id, record);
+    }
+
+    // Users with few votes can't meet the threshold anyways
+    by_user.retain(|_, votes| votes.len() >= BLOC_MIN_SHARED_VOTES);
+
+    let mut by_page = BTreeMap::<_, Vec<_>>::new();
+    for votes in by_user.values() {
+        for record in votes.values() {
+            by_page.entry(record.page_id).or_default().push(*record);
+        }
+    }
+
+    // Count how many pages each pair of users voted the same way on
+    let mut agreements = HashMap::new();
+    for votes in by_page.values() {
+        for (index, first) in votes.iter().enumerate() {
+            for second in &votes[index + 1..] {
+                if first.value == second.value {
+                    let key = if first.user_id < second.user_id {
+                        (first.user_id, second.user_id)
+                    } else {
+                        (second.user_id, first.user_id)
+                    };
+
+                    *agreements.entry(key).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    // Link users who are similar enough
+    let mut links = BTreeMap::<_, Vec<_>>::new();
+    for ((first, second), shared) in agreements {
+        if shared < BLOC_MIN_SHARED_VOTES {
+            continue;
+        }
+
+        let total = by_user[&first].len() + by_user[&second].len() - shared;
+        let similarity = shared as f64 / total as f64;
+
+        if similarity >= BLOC_MIN_SIMILARITY {
+            links.entry(first).or_default().push((second, similarity));
+            links.entry(second).or_default().push((first, similarity));
+        }
+    }
+
+    // Gather linked users into groups
+    let mut visited = BTreeSet::new();
+    for &start in links.keys() {
+        if !visited.insert(start) {
+            continue;
+        }
+
+        let mut members = BTreeSet::new();
+        let mut similarity = 1.0_f64;
+        let mut pending = vec![start];
+
+        while let Some(user_id) = pending.pop() {
+            members.insert(user_id);
+
+            for &(other_user_id, pair_similarity) in &links[&user_id] {
+                similarity = similarity.min(pair_similarity);
+
+                if visited.insert(other_user_id) {
+                    pending.push(other_user_id);
+                }
+            }
+        }
+
+        // Collect votes on pages where at least two members agreed
+        let mut agreeing = BTreeMap::<(i64, VoteValue), usize>::new();
+        for user_id in &members {
+            for record in by_user[user_id].values() {
+                *agreeing.entry((record.page_id, record.value)).or_default() += 1;
+            }
+        }
+
+        let page_ids = agreeing
+            .iter()
+            .filter(|(_, count)| **count >= 2)
+            .map(|(&(page_id, _), _)| page_id)
+            .collect::<BTreeSet<_>>();
+
+        let votes = members
+            .iter()
+            .flat_map(|user_id| by_user[user_id].values())
+            .filter(|record| agreeing[&(record.page_id, record.value)] >= 2)
+            .map(|record| FlaggedVote::from(*record))
+            .collect();
+
+        flags.push(VoteFlag::VotingBloc(VotingBloc {
+            user_ids: members.into_iter().collect(),
+            page_ids: page_ids.into_iter().collect(),
+            similarity,
+            votes,
+        }));
+    }
+}
+
+/// Finds users who have only voted on pages written by one particular author.
+fn detect_author_fixation(records: &[VoteRecord], flags: &mut Vec<VoteFlag>) {
+    let mut by_user = BTreeMap::<_, Vec<_>>::new();
+    for record in records {
+        by_user.entry(record.user_id).or_default().push(record);
+    }
+
+    for (user_id, votes) in by_user {
+        if votes.len() < FIXATION_MIN_VOTES {
+            continue;
+        }
+
+        let author_id = match votes[0].page_author_id {
+            Some(author_id) if author_id != user_id => author_id,
+            _ => continue,
+        };
+
+        if votes
+            .iter()
+            .all(|record| record.page_author_id == Some(author_id))
+        {
+            flags.push(VoteFlag::AuthorFixation(AuthorFixation {
+                user_id,
+                author_id,
+                votes: votes
+                    .iter()
+                    .map(|record| FlaggedVote::from(*record))
+                    .collect(),
+            }));
+        }
+    }
+}
+
+#[test]
+fn detectors() {
+    use crate::utils::UTC;
+    use chrono::TimeZone;
+
+    let mut records = Vec::new();
+    let mut vote_id = 0;
+    let mut add = |page_id, user_id, minutes, user_created_at: Option<i64>| {
+        vote_id += 1;
+        records.push(VoteRecord {
+            vote_id,
+            page_id,
+            user_id,
+            value: 1,
+            created_at: UTC.timestamp(minutes * 60, 0),
+            user_created_at: user_created_at
+                .map(|minutes| Utc.timestamp(minutes * 60, 0)),
+            page_author_id: Some(page_id % 2),
+        });
+    };
+
+    // Brand new accounts (users 10-15) voting on page 100 in a short period
+    for user_id in 10..16 {
+        add(100, user_id, 10_000 + user_id, Some(9_000));
+    }
+
+    // Users 21 and 22 vote on exactly the same pages (all odd, by author 1)
+    for page_id in (1..12).step_by(2) {
+        add(page_id, 21, page_id, None);
+        add(page_id, 22, page_id, None);
+    }
+
+    let flags = detect(&records);
+    let mut bursts = 0;
+    let mut blocs = 0;
+    let mut fixations = 0;
+
+    for flag in &flags {
+        match flag {
+            VoteFlag::NewAccountBurst(burst) => {
+                assert_eq!(burst.page_id, 100);
+                assert_eq!(burst.votes.len(), 6);
+                bursts += 1;
+            }
+            VoteFlag::VotingBloc(bloc) => {
+                assert_eq!(bloc.user_ids, [21, 22]);
+                assert_eq!(bloc.page_ids.len(), 6);
+                blocs += 1;
+            }
+            VoteFlag::AuthorFixation(fixation) => {
+                assert_eq!(fixation.author_id, 1);
+                assert!(fixation.user_id == 21 || fixation.user_id == 22);
+                fixations += 1;
+            }
+        }
+    }
+
+    assert_eq!((bursts, blocs, fixations), (1, 1, 2));
+}
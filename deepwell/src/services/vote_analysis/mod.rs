@@ -0,0 +1,41 @@
+/*
+ * services/vote_analysis/mod.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Detection of suspicious voting patterns.
+//!
+//! This scans recent votes on a site for patterns which suggest
+//! sockpuppet accounts or organized brigading, and produces a report
+//! listing each finding alongside the votes which triggered it.
+//! Staff can then review the evidence and disable votes as needed
+//! using `VoteService::action()`.
+//!
+//! Analysis is performed in the background by the `JobRunner`.
+
+mod prelude {
+    pub use super::super::prelude::*;
+    pub use super::structs::*;
+}
+
+mod detect;
+mod service;
+mod structs;
+
+pub use self::service::VoteAnalysisService;
+pub use self::structs::*;
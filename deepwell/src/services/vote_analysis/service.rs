@@ -0,0 +1,238 @@
+/*
+ * services/vote_analysis/service.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::detect::detect;
+use super::prelude::*;
+use crate::models::page;
+use crate::models::page_revision::{self, Entity as PageRevision};
+use crate::models::page_vote::{self, Entity as PageVote};
+use crate::models::users::{self, Entity as Users};
+use crate::models::vote_analysis_report::{
+    self, Entity as VoteAnalysisReport, Model as VoteAnalysisReportModel,
+};
+use crate::services::permission::{Action, PermissionService};
+use chrono::Duration;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use std::collections::{BTreeSet, HashMap};
+
+/// How far back to look for votes, if not specified.
+const DEFAULT_ANALYSIS_DAYS: i64 = 30;
+
+#[derive(Debug)]
+pub struct VoteAnalysisService;
+
+impl VoteAnalysisService {
+    /// Creates a new, pending report for the given site.
+    ///
+    /// The analysis itself is not performed here, the caller should
+    /// queue the report with `JobService::queue_vote_analysis()` after
+    /// the transaction has been committed.
+    pub async fn create(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        CreateVoteAnalysis { user_id, since }: CreateVoteAnalysis,
+    ) -> Result<CreateVoteAnalysisOutput> {
+        let txn = ctx.transaction();
+        let since =
+            since.unwrap_or_else(|| now() - Duration::days(DEFAULT_ANALYSIS_DAYS));
+
+        tide::log::info!(
+            "Creating vote analysis report for site ID {} on votes since {} (requested by {})",
+            site_id,
+            since,
+            user_id,
+        );
+
+        PermissionService::check_site(ctx, site_id, user_id, Action::Moderate).await?;
+
+        let model = vote_analysis_report::ActiveModel {
+            site_id: Set(site_id),
+            user_id: Set(user_id),
+            since: Set(since),
+            ..Default::default()
+        };
+
+        let VoteAnalysisReportModel { report_id, .. } = model.insert(txn).await?;
+        Ok(CreateVoteAnalysisOutput { report_id })
+    }
+
+    /// Performs the analysis for a pending report, storing the results.
+    ///
+    /// This is invoked by the `JobRunner`.
+    pub async fn run(ctx: &ServiceContext<'_>, report_id: i64) -> Result<()> {
+        let txn = ctx.transaction();
+        let report = Self::get(ctx, report_id).await?;
+
+        if report.completed_at.is_some() {
+            tide::log::warn!("Vote analysis report ID {report_id} already completed");
+            return Ok(());
+        }
+
+        let records = Self::collect_records(ctx, report.site_id, report.since).await?;
+        tide::log::info!(
+            "Analyzing {} votes for report ID {}",
+            records.len(),
+            report_id,
+        );
+
+        let flags = detect(&records);
+        tide::log::info!(
+            "Vote analysis report ID {} raised {} flags",
+            report_id,
+            flags.len(),
+        );
+
+        let model = vote_analysis_report::ActiveModel {
+            report_id: Set(report_id),
+            completed_at: Set(Some(now())),
+            flags: Set(Some(serde_json::to_value(&flags)?)),
+            ..Default::default()
+        };
+
+        model.update(txn).await?;
+        Ok(())
+    }
+
+    pub async fn get_optional(
+        ctx: &ServiceContext<'_>,
+        report_id: i64,
+    ) -> Result<Option<VoteAnalysisReportModel>> {
+        let txn = ctx.transaction();
+        let report = VoteAnalysisReport::find_by_id(report_id).one(txn).await?;
+        Ok(report)
+    }
+
+    pub async fn get(
+        ctx: &ServiceContext<'_>,
+        report_id: i64,
+    ) -> Result<VoteAnalysisReportModel> {
+        match Self::get_optional(ctx, report_id).await? {
+            Some(report) => Ok(report),
+            None => Err(Error::NotFound),
+        }
+    }
+
+    /// Gets the IDs of all reports which have not been completed.
+    ///
+    /// These may have been left pending by a restart or a failed analysis.
+    pub async fn get_pending_ids(ctx: &ServiceContext<'_>) -> Result<Vec<i64>> {
+        let txn = ctx.transaction();
+        let report_ids = VoteAnalysisReport::find()
+            .filter(vote_analysis_report::Column::CompletedAt.is_null())
+            .order_by_asc(vote_analysis_report::Column::ReportId)
+            .all(txn)
+            .await?
+            .into_iter()
+            .map(|report| report.report_id)
+            .collect();
+
+        Ok(report_ids)
+    }
+
+    /// Gets the most recent reports for a site, newest first.
+    pub async fn get_all(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        limit: u64,
+    ) -> Result<Vec<VoteAnalysisReportModel>> {
+        let txn = ctx.transaction();
+        let reports = VoteAnalysisReport::find()
+            .filter(vote_analysis_report::Column::SiteId.eq(site_id))
+            .order_by_desc(vote_analysis_report::Column::ReportId)
+            .limit(limit)
+            .all(txn)
+            .await?;
+
+        Ok(reports)
+    }
+
+    /// Gathers all active votes on this site since the given time,
+    /// along with the voter's account age and the page's author.
+    async fn collect_records(
+        ctx: &ServiceContext<'_>,
+        site_id: i64,
+        since: DateTimeWithTimeZone,
+    ) -> Result<Vec<VoteRecord>> {
+        let txn = ctx.transaction();
+
+        // Already-disabled votes have been dealt with, so they're excluded.
+        let votes = PageVote::find()
+            .join(JoinType::InnerJoin, page_vote::Relation::Page.def())
+            .filter(
+                Condition::all()
+                    .add(page::Column::SiteId.eq(site_id))
+                    .add(page_vote::Column::CreatedAt.gte(since))
+                    .add(page_vote::Column::DeletedAt.is_null())
+                    .add(page_vote::Column::DisabledAt.is_null()),
+            )
+            .all(txn)
+            .await?;
+
+        let user_ids = votes
+            .iter()
+            .map(|vote| vote.user_id)
+            .collect::<BTreeSet<_>>();
+
+        let page_ids = votes
+            .iter()
+            .map(|vote| vote.page_id)
+            .collect::<BTreeSet<_>>();
+
+        // Get account creation dates for voters,
+        // and the author (creator) of each page.
+        let (users, first_revisions) = try_join!(
+            Users::find()
+                .filter(users::Column::Id.is_in(user_ids))
+                .all(txn),
+            PageRevision::find()
+                .filter(
+                    Condition::all()
+                        .add(page_revision::Column::PageId.is_in(page_ids))
+                        .add(page_revision::Column::RevisionNumber.eq(0)),
+                )
+                .all(txn),
+        )?;
+
+        let user_created_at = users
+            .into_iter()
+            .map(|user| (user.id, user.created_at))
+            .collect::<HashMap<_, _>>();
+
+        let page_authors = first_revisions
+            .into_iter()
+            .map(|revision| (revision.page_id, revision.user_id))
+            .collect::<HashMap<_, _>>();
+
+        let records = votes
+            .into_iter()
+            .map(|vote| VoteRecord {
+                vote_id: vote.page_vote_id,
+                page_id: vote.page_id,
+                user_id: vote.user_id,
+                value: vote.value,
+                created_at: vote.created_at,
+                user_created_at: user_created_at.get(&vote.user_id).copied().flatten(),
+                page_author_id: page_authors.get(&vote.page_id).copied(),
+            })
+            .collect();
+
+        Ok(records)
+    }
+}
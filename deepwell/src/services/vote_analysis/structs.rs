@@ -0,0 +1,112 @@
+/*
+ * services/vote_analysis/structs.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::services::vote::VoteValue;
+use chrono::{DateTime, Utc};
+use sea_orm::prelude::DateTimeWithTimeZone;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVoteAnalysis {
+    pub user_id: i64,
+    pub since: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVoteAnalysisOutput {
+    pub report_id: i64,
+}
+
+/// A vote, along with the information needed to analyze it.
+#[derive(Debug, Clone)]
+pub struct VoteRecord {
+    pub vote_id: i64,
+    pub page_id: i64,
+    pub user_id: i64,
+    pub value: VoteValue,
+    pub created_at: DateTimeWithTimeZone,
+    pub user_created_at: Option<DateTime<Utc>>,
+    pub page_author_id: Option<i64>,
+}
+
+/// A vote cited as evidence for a flag.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlaggedVote {
+    pub vote_id: i64,
+    pub page_id: i64,
+    pub user_id: i64,
+    pub value: VoteValue,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl From<&VoteRecord> for FlaggedVote {
+    fn from(record: &VoteRecord) -> FlaggedVote {
+        FlaggedVote {
+            vote_id: record.vote_id,
+            page_id: record.page_id,
+            user_id: record.user_id,
+            value: record.value,
+            created_at: record.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum VoteFlag {
+    /// Many votes were cast on one page by new accounts in a short period.
+    NewAccountBurst(NewAccountBurst),
+
+    /// A group of users consistently vote the same way on the same pages.
+    VotingBloc(VotingBloc),
+
+    /// A user's votes have all been on pages by one author.
+    AuthorFixation(AuthorFixation),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewAccountBurst {
+    pub page_id: i64,
+    pub window_start: DateTimeWithTimeZone,
+    pub window_end: DateTimeWithTimeZone,
+    pub votes: Vec<FlaggedVote>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VotingBloc {
+    pub user_ids: Vec<i64>,
+    pub page_ids: Vec<i64>,
+
+    /// The lowest Jaccard similarity of any linked pair of users in the group.
+    pub similarity: f64,
+    pub votes: Vec<FlaggedVote>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorFixation {
+    pub user_id: i64,
+    pub author_id: i64,
+    pub votes: Vec<FlaggedVote>,
+}
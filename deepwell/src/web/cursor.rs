@@ -0,0 +1,173 @@
+/*
+ * web/cursor.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Cursor-based pagination, as used by the public API.
+//!
+//! Clients receive a `nextCursor` with each page of results, and pass it back
+//! as the `cursor` query parameter to fetch the following page. The cursor is
+//! opaque to clients, but internally is the key of the last item returned.
+
+use super::FetchLimit;
+use data_encoding::BASE64URL_NOPAD;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::str::FromStr;
+use tide::{Error, StatusCode};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor(String);
+
+impl Cursor {
+    #[inline]
+    pub fn new<T: ToString>(key: T) -> Self {
+        Cursor(key.to_string())
+    }
+
+    #[inline]
+    pub fn key(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Serialize for Cursor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let encoded = BASE64URL_NOPAD.encode(self.0.as_bytes());
+        serializer.serialize_str(&encoded)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cursor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let encoded = String::deserialize(deserializer)?;
+        let bytes = BASE64URL_NOPAD
+            .decode(encoded.as_bytes())
+            .map_err(|_| de::Error::custom("invalid cursor"))?;
+
+        let key =
+            String::from_utf8(bytes).map_err(|_| de::Error::custom("invalid cursor"))?;
+
+        Ok(Cursor(key))
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CursorQuery {
+    /// How many items to pull in this query.
+    pub limit: FetchLimit,
+
+    /// Where to continue from, as given by a previous response.
+    pub cursor: Option<Cursor>,
+}
+
+impl CursorQuery {
+    /// Parses the key of the cursor, if one was given.
+    pub fn key<T: FromStr>(&self) -> Result<Option<T>, Error> {
+        match self.cursor {
+            None => Ok(None),
+            Some(ref cursor) => match cursor.key().parse() {
+                Ok(key) => Ok(Some(key)),
+                Err(_) => Err(Error::from_str(StatusCode::BadRequest, "Invalid cursor")),
+            },
+        }
+    }
+
+    /// How many items to request from the database.
+    ///
+    /// This is one more than the limit, so `CursorPage` can tell
+    /// whether there are any further items.
+    #[inline]
+    pub fn fetch_limit(&self) -> u64 {
+        u64::from(self.limit) + 1
+    }
+}
+
+/// One page of results from a paginated listing.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorPage<T> {
+    pub data: Vec<T>,
+    pub next_cursor: Option<Cursor>,
+}
+
+impl<T> CursorPage<T> {
+    /// Builds a page from items fetched with `CursorQuery::fetch_limit()`.
+    ///
+    /// The `key` function gets the value a subsequent query should start after.
+    pub fn new<F, K>(mut data: Vec<T>, query: &CursorQuery, key: F) -> Self
+    where
+        F: FnOnce(&T) -> K,
+        K: ToString,
+    {
+        let limit = usize::from(u16::from(query.limit));
+        let next_cursor = if data.len() > limit {
+            data.truncate(limit);
+            data.last().map(|item| Cursor::new(key(item)))
+        } else {
+            None
+        };
+
+        CursorPage { data, next_cursor }
+    }
+
+    /// Converts each item in the page, keeping the cursor.
+    pub fn map<U, F>(self, f: F) -> CursorPage<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        CursorPage {
+            data: self.data.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[test]
+fn cursor_page() {
+    fn query(limit: u16, cursor: Option<&str>) -> CursorQuery {
+        serde_json::from_value(serde_json::json!({
+            "limit": limit,
+            "cursor": cursor,
+        }))
+        .expect("Unable to deserialize query")
+    }
+
+    // More items than the limit
+    let page = CursorPage::new(vec![1, 2, 3, 4], &query(3, None), |n| *n);
+    assert_eq!(page.data, vec![1, 2, 3]);
+    assert_eq!(page.next_cursor, Some(Cursor::new(3)));
+
+    // Exactly the limit, which is the last page
+    let page = CursorPage::new(vec![1, 2, 3], &query(3, None), |n| *n);
+    assert_eq!(page.data, vec![1, 2, 3]);
+    assert_eq!(page.next_cursor, None);
+
+    // Round trip through serialization
+    let encoded = serde_json::to_value(Cursor::new(3)).unwrap();
+    let query = query(3, Some(encoded.as_str().unwrap()));
+    assert_eq!(query.key::<i64>().unwrap(), Some(3));
+    assert_eq!(query.fetch_limit(), 4);
+}
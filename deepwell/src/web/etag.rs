@@ -0,0 +1,110 @@
+/*
+ * web/etag.rs
+ *
+ * DEEPWELL - Wikijump API provider and database manager
+ * Copyright (C) 2019-2022 Wikijump Team
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Helpers for entity tags and conditional `GET` requests.
+
+use crate::api::ApiRequest;
+use crate::hash::sha512_hash;
+use serde::Serialize;
+use tide::http::headers::{ETAG, IF_NONE_MATCH};
+use tide::http::mime;
+use tide::{Body, Response, StatusCode};
+
+/// Forms a strong entity tag from a SHA-512 hash.
+///
+/// Only the first half of the hash is used, which is plenty
+/// to distinguish versions of the same resource.
+pub fn etag_from_hash(hash: &[u8]) -> String {
+    format!("\"{}\"", hex::encode(&hash[..32]))
+}
+
+/// Forms a strong entity tag from the values a response is built from.
+///
+/// This lets a handler answer a conditional request before doing the
+/// expensive part of building the body, such as fetching text or blobs.
+pub fn etag_from_parts<T: Serialize>(parts: &T) -> serde_json::Result<String> {
+    let bytes = serde_json::to_vec(parts)?;
+    Ok(etag_from_hash(&sha512_hash(&bytes)))
+}
+
+/// Determines if the client's `If-None-Match` header matches the given entity tag.
+pub fn etag_matches(req: &ApiRequest, etag: &str) -> bool {
+    let values = match req.header(IF_NONE_MATCH) {
+        Some(values) => values,
+        None => return false,
+    };
+
+    values
+        .iter()
+        .flat_map(|value| value.as_str().split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// Builds a `304 Not Modified` response, if the client already has this version.
+pub fn not_modified(
+    req: &ApiRequest,
+    etag: &str,
+    status: StatusCode,
+) -> Option<Response> {
+    if status != StatusCode::Ok || !etag_matches(req, etag) {
+        return None;
+    }
+
+    let mut response = Response::new(StatusCode::NotModified);
+    response.insert_header(ETAG, etag);
+    Some(response)
+}
+
+/// Builds a response with an `ETag` header.
+///
+/// If the client already has this version, then a `304 Not Modified`
+/// is returned instead of the body.
+pub fn conditional_response(
+    req: &ApiRequest,
+    etag: String,
+    status: StatusCode,
+    body: Body,
+) -> Response {
+    if let Some(response) = not_modified(req, &etag, status) {
+        return response;
+    }
+
+    let mut response = Response::builder(status).body(body).build();
+    response.insert_header(ETAG, etag);
+    response
+}
+
+/// Serializes the output as JSON, tagged with the given entity tag.
+///
+/// The tag should come from `etag_from_parts()`, so that handlers
+/// can check for `not_modified()` before producing the output.
+pub fn json_response<T: Serialize>(
+    req: &ApiRequest,
+    status: StatusCode,
+    etag: String,
+    output: &T,
+) -> serde_json::Result<Response> {
+    let bytes = serde_json::to_vec(output)?;
+    let mut body = Body::from_bytes(bytes);
+    body.set_mime(mime::JSON);
+
+    Ok(conditional_response(req, etag, status, body))
+}
@@ -54,6 +54,16 @@ pub struct FetchLimitQuery {
     pub limit: FetchLimit,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OffsetLimitQuery {
+    /// How many items to pull in this query.
+    pub limit: FetchLimit,
+
+    /// How many items to skip before the first one returned.
+    pub offset: u64,
+}
+
 // NOTE: #[serde(flatten)] on PageDetailsQuery as a field
 //       doesn't seem to work here, so we're just pasting it in.
 
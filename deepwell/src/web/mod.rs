@@ -20,6 +20,8 @@
 
 mod category;
 mod connection_type;
+mod cursor;
+mod etag;
 mod fetch_direction;
 mod fetch_limit;
 mod file_details;
@@ -34,9 +36,13 @@ pub mod utils;
 
 pub use self::category::*;
 pub use self::connection_type::ConnectionType;
+pub use self::cursor::{CursorPage, CursorQuery};
+pub use self::etag::{
+    conditional_response, etag_from_hash, etag_from_parts, json_response, not_modified,
+};
 pub use self::fetch_direction::FetchDirection;
 pub use self::fetch_limit::{
-    FetchLimit, FetchLimitQuery, FileLimitQuery, PageLimitQuery,
+    FetchLimit, FetchLimitQuery, FileLimitQuery, OffsetLimitQuery, PageLimitQuery,
 };
 pub use self::file_details::FileDetailsQuery;
 pub use self::page_details::PageDetailsQuery;
@@ -85,9 +85,14 @@ impl Default for UserDetails {
 }
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct UserDetailsQuery {
     #[serde(default)]
     pub detail: UserDetails,
+
+    /// The site to report the user's role in, if any.
+    #[serde(default)]
+    pub site_id: Option<i64>,
 }
 
 /// Ensure `UserDetails::name()` produces the same output as serde.
@@ -22,13 +22,15 @@ use super::{modules::*, ModuleRule};
 use std::collections::HashMap;
 use unicase::UniCase;
 
-pub const MODULE_RULES: [ModuleRule; 6] = [
+pub const MODULE_RULES: [ModuleRule; 8] = [
     MODULE_BACKLINKS,
     MODULE_CATEGORIES,
+    MODULE_COMMENTS,
     MODULE_CSS,
     MODULE_JOIN,
     MODULE_PAGE_TREE,
     MODULE_RATE,
+    MODULE_SEARCH,
 ];
 
 pub type ModuleRuleMap = HashMap<UniCase<&'static str>, &'static ModuleRule>;
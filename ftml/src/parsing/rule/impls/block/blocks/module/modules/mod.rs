@@ -30,14 +30,18 @@ mod prelude {
 
 mod backlinks;
 mod categories;
+mod comments;
 mod css;
 mod join;
 mod page_tree;
 mod rate;
+mod search;
 
 pub use self::backlinks::MODULE_BACKLINKS;
 pub use self::categories::MODULE_CATEGORIES;
+pub use self::comments::MODULE_COMMENTS;
 pub use self::css::MODULE_CSS;
 pub use self::join::MODULE_JOIN;
 pub use self::page_tree::MODULE_PAGE_TREE;
 pub use self::rate::MODULE_RATE;
+pub use self::search::MODULE_SEARCH;
@@ -88,6 +88,9 @@ fn arb_module() -> impl Strategy<Value = Element<'static>> {
         },
     );
 
+    let comments = (arb_optional_str(), any::<bool>())
+        .prop_map(|(title, hide)| Module::Comments { title, hide });
+
     let page_tree = (
         arb_optional_str(),
         any::<bool>(),
@@ -99,12 +102,22 @@ fn arb_module() -> impl Strategy<Value = Element<'static>> {
             depth,
         });
 
+    let search = (arb_optional_str(), arb_optional_str(), arb_optional_str()).prop_map(
+        |(query, category, tags)| Module::Search {
+            query,
+            category,
+            tags,
+        },
+    );
+
     prop_oneof![
         Just(Module::Rate),
         arb_optional_str().prop_map(|page| Module::Backlinks { page }),
         any::<bool>().prop_map(|include_hidden| Module::Categories { include_hidden }),
+        comments,
         join,
         page_tree,
+        search,
     ]
     .prop_map(Element::Module)
 }
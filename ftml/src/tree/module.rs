@@ -38,6 +38,14 @@ pub enum Module<'t> {
     #[serde(rename_all = "kebab-case")]
     Categories { include_hidden: bool },
 
+    /// Shows the discussion thread for the current page.
+    ///
+    /// If `hide` is set, the comments are collapsed until expanded by the reader.
+    Comments {
+        title: Option<Cow<'t, str>>,
+        hide: bool,
+    },
+
     /// Allows a user to join a site.
     #[serde(rename_all = "kebab-case")]
     Join {
@@ -58,6 +66,15 @@ pub enum Module<'t> {
 
     /// A rating module, which can be used to vote on the page.
     Rate,
+
+    /// A search box for finding pages on the site.
+    ///
+    /// The category and tags restrict which pages are searched.
+    Search {
+        query: Option<Cow<'t, str>>,
+        category: Option<Cow<'t, str>>,
+        tags: Option<Cow<'t, str>>,
+    },
 }
 
 impl Module<'_> {
@@ -74,6 +91,10 @@ impl Module<'_> {
             Module::Categories { include_hidden } => Module::Categories {
                 include_hidden: *include_hidden,
             },
+            Module::Comments { title, hide } => Module::Comments {
+                title: option_string_to_owned(title),
+                hide: *hide,
+            },
             Module::Join {
                 button_text,
                 attributes,
@@ -91,6 +112,15 @@ impl Module<'_> {
                 depth: *depth,
             },
             Module::Rate => Module::Rate,
+            Module::Search {
+                query,
+                category,
+                tags,
+            } => Module::Search {
+                query: option_string_to_owned(query),
+                category: option_string_to_owned(category),
+                tags: option_string_to_owned(tags),
+            },
         }
     }
 }
@@ -0,0 +1,73 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+
+class ScoreSettings extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Adds settings to determine which kind of voting and scoring
+        // are used. The site provides the defaults, which each category
+        // can then override.
+
+        DB::statement("
+            CREATE TYPE score_type AS ENUM (
+                'null',
+                'sum',
+                'mean',
+                'median',
+                'percent',
+                'wilson'
+            )
+        ");
+
+        DB::statement("
+            CREATE TYPE vote_type AS ENUM (
+                'ups_downs',
+                'five_star'
+            )
+        ");
+
+        DB::statement("
+            ALTER TABLE site
+                ADD COLUMN score_type score_type NOT NULL DEFAULT 'sum',
+                ADD COLUMN vote_type vote_type NOT NULL DEFAULT 'ups_downs'
+        ");
+
+        // NULL means to use the site's setting
+        DB::statement("
+            ALTER TABLE page_category
+                ADD COLUMN score_type score_type,
+                ADD COLUMN vote_type vote_type
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        DB::statement("
+            ALTER TABLE page_category
+                DROP COLUMN score_type,
+                DROP COLUMN vote_type
+        ");
+
+        DB::statement("
+            ALTER TABLE site
+                DROP COLUMN score_type,
+                DROP COLUMN vote_type
+        ");
+
+        DB::statement('DROP TYPE score_type');
+        DB::statement('DROP TYPE vote_type');
+    }
+}
@@ -0,0 +1,42 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Support\Facades\Schema;
+
+class VoteAnalysisReport extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Reports produced by DEEPWELL's suspicious voting detection.
+        // The 'flags' column is NULL until the analysis job has finished.
+        DB::statement("
+            CREATE TABLE vote_analysis_report (
+                report_id BIGSERIAL PRIMARY KEY,
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+                completed_at TIMESTAMP WITH TIME ZONE,
+                site_id BIGINT NOT NULL REFERENCES site(site_id),
+                user_id BIGINT NOT NULL REFERENCES users(id),
+                since TIMESTAMP WITH TIME ZONE NOT NULL,
+                flags JSON,
+
+                CHECK ((completed_at IS NULL) = (flags IS NULL))
+            )
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        Schema::drop('vote_analysis_report');
+    }
+}
@@ -0,0 +1,51 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+
+class PageRevisionCompiledExtras extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Rendering produces more than the HTML body, these store the
+        // stylesheets from [[css]] blocks and any <meta> tags for the page.
+        //
+        // Like compiled_hash, these are updated when a revision is rerendered.
+        DB::statement("
+            ALTER TABLE page_revision
+                ADD COLUMN compiled_styles JSON NOT NULL DEFAULT '[]',
+                ADD COLUMN compiled_meta JSON NOT NULL DEFAULT '[]'
+        ");
+
+        // Existing pages were rendered before these were stored, so mark their
+        // latest revisions as stale. DEEPWELL's rerender sweep will then fill them in.
+        DB::statement("
+            UPDATE page_revision
+            SET compiled_generator = 'stale'
+            WHERE revision_id IN (
+                SELECT DISTINCT ON (page_id) revision_id
+                FROM page_revision
+                ORDER BY page_id, revision_number DESC
+            )
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        DB::statement("
+            ALTER TABLE page_revision
+                DROP COLUMN compiled_styles,
+                DROP COLUMN compiled_meta
+        ");
+    }
+}
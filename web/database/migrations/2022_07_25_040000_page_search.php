@@ -0,0 +1,58 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Support\Facades\Schema;
+
+class PageSearch extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Full-text search index for pages, maintained by DEEPWELL.
+        // There is one row per page, reflecting its latest revision.
+        //
+        // 'body' is the plain text output of the page, which is kept
+        // so that highlighted snippets can be produced for results.
+        DB::statement("
+            CREATE TABLE page_search (
+                page_id BIGINT PRIMARY KEY REFERENCES page(page_id),
+                updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+                title TEXT NOT NULL,
+                alt_title TEXT,
+                tags JSONB NOT NULL,
+                body TEXT NOT NULL,
+                search_vector TSVECTOR NOT NULL
+            )
+        ");
+
+        DB::statement('CREATE INDEX page_search_vector_idx ON page_search USING GIN (search_vector)');
+        DB::statement('CREATE INDEX page_search_tags_idx ON page_search USING GIN (tags)');
+
+        // Pages are indexed when rendered, so mark the latest revisions of existing
+        // pages as stale. DEEPWELL's rerender sweep will then index them.
+        DB::statement("
+            UPDATE page_revision
+            SET compiled_generator = 'stale'
+            WHERE revision_id IN (
+                SELECT DISTINCT ON (page_id) revision_id
+                FROM page_revision
+                ORDER BY page_id, revision_number DESC
+            )
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        Schema::drop('page_search');
+    }
+}
@@ -0,0 +1,63 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Support\Facades\Schema;
+
+class PageTag extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Normalized copy of the tags on each page's latest revision,
+        // so that pages can be efficiently queried by tag.
+        // The revision's 'tags' column remains the source of truth.
+        DB::statement("
+            CREATE TABLE page_tag (
+                page_id BIGINT NOT NULL REFERENCES page(page_id),
+                site_id BIGINT NOT NULL REFERENCES site(site_id),
+                tag TEXT NOT NULL,
+
+                PRIMARY KEY (page_id, tag)
+            )
+        ");
+
+        DB::statement('CREATE INDEX page_tag_site_tag_idx ON page_tag (site_id, tag)');
+
+        DB::statement("
+            INSERT INTO page_tag (page_id, site_id, tag)
+            SELECT DISTINCT page_id, site_id, json_array_elements_text(tags)
+            FROM page_revision
+            WHERE revision_number = (
+                SELECT MAX(latest.revision_number)
+                FROM page_revision AS latest
+                WHERE latest.page_id = page_revision.page_id
+            )
+        ");
+
+        // If a site has any rows here, then page tags are restricted to this list.
+        DB::statement("
+            CREATE TABLE site_allowed_tag (
+                site_id BIGINT NOT NULL REFERENCES site(site_id),
+                tag TEXT NOT NULL,
+
+                PRIMARY KEY (site_id, tag)
+            )
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        Schema::drop('site_allowed_tag');
+        Schema::drop('page_tag');
+    }
+}
@@ -0,0 +1,45 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Support\Facades\Schema;
+
+class PageRedirect extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Slugs which lead to a page other than by its own slug.
+        //
+        // These are created automatically when a page is moved (is_alias = false),
+        // or added explicitly by users (is_alias = true). Since they point to a
+        // page ID rather than a slug, moving a page again never creates a chain.
+        DB::statement("
+            CREATE TABLE page_redirect (
+                site_id BIGINT NOT NULL REFERENCES site(site_id),
+                slug TEXT NOT NULL,
+                page_id BIGINT NOT NULL REFERENCES page(page_id),
+                is_alias BOOLEAN NOT NULL,
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+
+                PRIMARY KEY (site_id, slug)
+            )
+        ");
+
+        DB::statement('CREATE INDEX page_redirect_page_idx ON page_redirect (page_id)');
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        Schema::drop('page_redirect');
+    }
+}
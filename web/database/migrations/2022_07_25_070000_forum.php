@@ -0,0 +1,108 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+
+class Forum extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Widen the legacy ID columns to match the tables they refer to.
+        DB::statement('ALTER TABLE forum_group ALTER COLUMN site_id TYPE BIGINT');
+
+        DB::statement("
+            ALTER TABLE forum_category
+                ALTER COLUMN group_id TYPE BIGINT,
+                ALTER COLUMN last_post_id TYPE BIGINT,
+                ALTER COLUMN site_id TYPE BIGINT
+        ");
+
+        DB::statement("
+            ALTER TABLE forum_thread
+                ALTER COLUMN user_id TYPE BIGINT,
+                ALTER COLUMN category_id TYPE BIGINT,
+                ALTER COLUMN site_id TYPE BIGINT,
+                ALTER COLUMN last_post_id TYPE BIGINT,
+                ALTER COLUMN page_id TYPE BIGINT,
+                ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE
+        ");
+
+        // Post bodies are rendered by DEEPWELL, with the HTML stored
+        // in the text table like it is for page revisions.
+        DB::statement("
+            ALTER TABLE forum_post
+                ALTER COLUMN thread_id TYPE BIGINT,
+                ALTER COLUMN parent_id TYPE BIGINT,
+                ALTER COLUMN user_id TYPE BIGINT,
+                ALTER COLUMN site_id TYPE BIGINT,
+                ALTER COLUMN revision_id TYPE BIGINT,
+                ALTER COLUMN edited_user_id TYPE BIGINT,
+                ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE,
+                ADD COLUMN compiled_hash BYTEA REFERENCES text(hash),
+                ADD COLUMN compiled_at TIMESTAMP WITH TIME ZONE,
+                ADD COLUMN compiled_generator TEXT
+        ");
+
+        DB::statement("
+            ALTER TABLE forum_post_revision
+                ALTER COLUMN post_id TYPE BIGINT,
+                ALTER COLUMN user_id TYPE BIGINT
+        ");
+
+        DB::statement('CREATE INDEX forum_post_parent_idx ON forum_post (parent_id)');
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        DB::statement('DROP INDEX forum_post_parent_idx');
+
+        DB::statement("
+            ALTER TABLE forum_post_revision
+                ALTER COLUMN post_id TYPE INTEGER,
+                ALTER COLUMN user_id TYPE INTEGER
+        ");
+
+        DB::statement("
+            ALTER TABLE forum_post
+                DROP COLUMN compiled_generator,
+                DROP COLUMN compiled_at,
+                DROP COLUMN compiled_hash,
+                DROP COLUMN deleted_at,
+                ALTER COLUMN thread_id TYPE INTEGER,
+                ALTER COLUMN parent_id TYPE INTEGER,
+                ALTER COLUMN user_id TYPE INTEGER,
+                ALTER COLUMN site_id TYPE INTEGER,
+                ALTER COLUMN revision_id TYPE INTEGER,
+                ALTER COLUMN edited_user_id TYPE INTEGER
+        ");
+
+        DB::statement("
+            ALTER TABLE forum_thread
+                DROP COLUMN deleted_at,
+                ALTER COLUMN user_id TYPE INTEGER,
+                ALTER COLUMN category_id TYPE INTEGER,
+                ALTER COLUMN site_id TYPE INTEGER,
+                ALTER COLUMN last_post_id TYPE INTEGER,
+                ALTER COLUMN page_id TYPE INTEGER
+        ");
+
+        DB::statement("
+            ALTER TABLE forum_category
+                ALTER COLUMN group_id TYPE INTEGER,
+                ALTER COLUMN last_post_id TYPE INTEGER,
+                ALTER COLUMN site_id TYPE INTEGER
+        ");
+
+        DB::statement('ALTER TABLE forum_group ALTER COLUMN site_id TYPE INTEGER');
+    }
+}
@@ -0,0 +1,43 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+
+class UserMessageRendering extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Messages are now sent by DEEPWELL, which has the database
+        // generate their IDs, and stores the rendered message body
+        // alongside the wikitext.
+
+        DB::statement("
+            ALTER TABLE user_messages
+                ALTER COLUMN id SET DEFAULT gen_random_uuid(),
+                ADD COLUMN compiled_hash BYTEA REFERENCES text(hash),
+                ADD COLUMN compiled_at TIMESTAMP WITH TIME ZONE,
+                ADD COLUMN compiled_generator TEXT
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        DB::statement("
+            ALTER TABLE user_messages
+                ALTER COLUMN id DROP DEFAULT,
+                DROP COLUMN compiled_hash,
+                DROP COLUMN compiled_at,
+                DROP COLUMN compiled_generator
+        ");
+    }
+}
@@ -0,0 +1,90 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+
+class UserBlock extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Extends user_block to hold both site bans and user-to-user blocks.
+        //
+        // Site bans have site_id set, and blocks between users have
+        // blocker_user_id set. Lifting a ban or block marks it as revoked
+        // instead of deleting it, so there is a history of past bans.
+
+        DB::statement('DELETE FROM user_block WHERE user_id IS NULL OR site_id IS NULL');
+
+        DB::statement("
+            ALTER TABLE user_block
+                DROP CONSTRAINT user_block_site_id_user_id_unique,
+                ALTER COLUMN site_id TYPE BIGINT,
+                ALTER COLUMN user_id TYPE BIGINT,
+                ALTER COLUMN user_id SET NOT NULL,
+                ALTER COLUMN date_blocked SET DEFAULT now(),
+                ADD COLUMN blocker_user_id BIGINT REFERENCES users(id),
+                ADD COLUMN created_by BIGINT REFERENCES users(id),
+                ADD COLUMN expires_at TIMESTAMP WITH TIME ZONE,
+                ADD COLUMN revoked_at TIMESTAMP WITH TIME ZONE,
+                ADD COLUMN revoked_by BIGINT REFERENCES users(id),
+                ADD CONSTRAINT user_block_user_fk FOREIGN KEY (user_id) REFERENCES users(id),
+                ADD CONSTRAINT user_block_target_check
+                    CHECK ((site_id IS NULL) != (blocker_user_id IS NULL))
+        ");
+
+        // Only one unrevoked ban or block for each pair
+        DB::statement("
+            CREATE UNIQUE INDEX user_block_site_idx
+                ON user_block (site_id, user_id)
+                WHERE revoked_at IS NULL
+        ");
+
+        DB::statement("
+            CREATE UNIQUE INDEX user_block_blocker_idx
+                ON user_block (blocker_user_id, user_id)
+                WHERE revoked_at IS NULL
+        ");
+
+        // Copy over blocks made through the web application
+        DB::statement("
+            INSERT INTO user_block (user_id, blocker_user_id, created_by, date_blocked)
+            SELECT target_id, setter_id, setter_id, created_at
+            FROM interactions
+            WHERE interaction_type = 5
+                AND setter_type = 'Wikijump\Models\User'
+                AND target_type = 'Wikijump\Models\User'
+            ON CONFLICT DO NOTHING
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        DB::statement('DELETE FROM user_block WHERE site_id IS NULL OR revoked_at IS NOT NULL');
+        DB::statement('DROP INDEX user_block_site_idx');
+        DB::statement('DROP INDEX user_block_blocker_idx');
+
+        DB::statement("
+            ALTER TABLE user_block
+                DROP CONSTRAINT user_block_target_check,
+                DROP CONSTRAINT user_block_user_fk,
+                DROP COLUMN blocker_user_id,
+                DROP COLUMN created_by,
+                DROP COLUMN expires_at,
+                DROP COLUMN revoked_at,
+                DROP COLUMN revoked_by,
+                ALTER COLUMN date_blocked DROP DEFAULT,
+                ALTER COLUMN user_id DROP NOT NULL,
+                ADD CONSTRAINT user_block_site_id_user_id_unique UNIQUE (site_id, user_id)
+        ");
+    }
+}
@@ -0,0 +1,43 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Support\Facades\Schema;
+
+class UserAlias extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Past usernames, recorded whenever a user is renamed.
+        //
+        // Old slugs still lead to the user, so existing links keep working,
+        // and are reserved for a time so nobody else can take them over.
+        DB::statement("
+            CREATE TABLE user_alias (
+                alias_id BIGSERIAL PRIMARY KEY,
+                user_id BIGINT NOT NULL REFERENCES users(id),
+                username TEXT NOT NULL,
+                slug TEXT NOT NULL,
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+            )
+        ");
+
+        DB::statement('CREATE INDEX user_alias_slug_idx ON user_alias (slug)');
+        DB::statement('CREATE INDEX user_alias_user_idx ON user_alias (user_id, created_at)');
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        Schema::drop('user_alias');
+    }
+}
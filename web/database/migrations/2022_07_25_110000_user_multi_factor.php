@@ -0,0 +1,31 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+
+class UserMultiFactor extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Records the most recent TOTP time step accepted for each user.
+        //
+        // A code is only accepted if its time step is later than this one,
+        // so an observed code cannot be used a second time.
+        DB::statement('ALTER TABLE users ADD COLUMN multi_factor_last_step BIGINT');
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        DB::statement('ALTER TABLE users DROP COLUMN multi_factor_last_step');
+    }
+}
@@ -0,0 +1,49 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Support\Facades\Schema;
+
+class UserToken extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Single-use tokens sent to users by email.
+        //
+        // Only a hash of each token is stored. Email tokens record the address
+        // they were sent to, which for email changes is the new address to use
+        // once the token is confirmed.
+        DB::statement("
+            CREATE TABLE user_token (
+                token_id BIGSERIAL PRIMARY KEY,
+                user_id BIGINT NOT NULL REFERENCES users(id),
+                purpose TEXT NOT NULL,
+                token_hash TEXT NOT NULL UNIQUE,
+                email TEXT,
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+                expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+                used_at TIMESTAMP WITH TIME ZONE,
+
+                CHECK (purpose IN ('email-verification', 'email-change', 'password-reset')),
+                CHECK ((purpose = 'password-reset') = (email IS NULL))
+            )
+        ");
+
+        DB::statement('CREATE INDEX user_token_user_idx ON user_token (user_id, purpose)');
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        Schema::drop('user_token');
+    }
+}
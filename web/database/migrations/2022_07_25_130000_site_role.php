@@ -0,0 +1,131 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Support\Facades\Schema;
+
+class SiteRole extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Roles within a site, and the actions each is permitted to do.
+        //
+        // Every site has the built-in roles guest, member, moderator, and admin,
+        // and may define its own custom roles. Users without an assigned role
+        // on a site are treated as guests.
+        DB::statement("
+            CREATE TABLE site_role (
+                role_id BIGSERIAL PRIMARY KEY,
+                site_id BIGINT NOT NULL REFERENCES site(site_id),
+                slug TEXT NOT NULL,
+                name TEXT NOT NULL,
+                builtin BOOLEAN NOT NULL DEFAULT false,
+                can_create BOOLEAN NOT NULL DEFAULT false,
+                can_edit BOOLEAN NOT NULL DEFAULT false,
+                can_move BOOLEAN NOT NULL DEFAULT false,
+                can_delete BOOLEAN NOT NULL DEFAULT false,
+                can_vote BOOLEAN NOT NULL DEFAULT false,
+                can_upload BOOLEAN NOT NULL DEFAULT false,
+                can_post BOOLEAN NOT NULL DEFAULT false,
+                can_moderate BOOLEAN NOT NULL DEFAULT false,
+                can_manage BOOLEAN NOT NULL DEFAULT false,
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+                updated_at TIMESTAMP WITH TIME ZONE,
+
+                UNIQUE (site_id, slug)
+            )
+        ");
+
+        DB::statement("
+            CREATE TABLE site_user_role (
+                site_id BIGINT NOT NULL REFERENCES site(site_id),
+                user_id BIGINT NOT NULL REFERENCES users(id),
+                role_id BIGINT NOT NULL REFERENCES site_role(role_id),
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+                created_by BIGINT REFERENCES users(id),
+
+                PRIMARY KEY (site_id, user_id)
+            )
+        ");
+
+        // Per-category changes to what a role may do with pages.
+        //
+        // A NULL column means the role's site-wide permission applies.
+        DB::statement("
+            CREATE TABLE page_category_permission (
+                category_id BIGINT NOT NULL REFERENCES page_category(category_id),
+                role_id BIGINT NOT NULL REFERENCES site_role(role_id),
+                can_create BOOLEAN,
+                can_edit BOOLEAN,
+                can_move BOOLEAN,
+                can_delete BOOLEAN,
+                can_vote BOOLEAN,
+                can_upload BOOLEAN,
+
+                PRIMARY KEY (category_id, role_id)
+            )
+        ");
+
+        // Built-in roles for existing sites
+        DB::statement("
+            INSERT INTO site_role (
+                site_id, slug, name, builtin,
+                can_create, can_edit, can_move, can_delete, can_vote,
+                can_upload, can_post, can_moderate, can_manage
+            )
+            SELECT site.site_id, role.*
+            FROM site
+            CROSS JOIN (VALUES
+                ('guest', 'Guest', true,
+                    false, false, false, false, false, false, true, false, false),
+                ('member', 'Member', true,
+                    true, true, true, false, true, true, true, false, false),
+                ('moderator', 'Moderator', true,
+                    true, true, true, true, true, true, true, true, false),
+                ('admin', 'Administrator', true,
+                    true, true, true, true, true, true, true, true, true)
+            ) AS role (
+                slug, name, builtin,
+                can_create, can_edit, can_move, can_delete, can_vote,
+                can_upload, can_post, can_moderate, can_manage
+            )
+        ");
+
+        // Carry over Wikidot members, moderators, and admins,
+        // using the highest role where a user appears more than once.
+        DB::statement("
+            INSERT INTO site_user_role (site_id, user_id, role_id)
+            SELECT DISTINCT ON (legacy.site_id, legacy.user_id)
+                legacy.site_id, legacy.user_id, site_role.role_id
+            FROM (
+                SELECT site_id, user_id, 'admin' AS slug, 3 AS rank FROM admin
+                UNION ALL
+                SELECT site_id, user_id, 'moderator', 2 FROM moderator
+                UNION ALL
+                SELECT site_id, user_id, 'member', 1 FROM member
+            ) AS legacy
+            JOIN site_role
+                ON site_role.site_id = legacy.site_id
+                AND site_role.slug = legacy.slug
+            JOIN users ON users.id = legacy.user_id
+            ORDER BY legacy.site_id, legacy.user_id, legacy.rank DESC
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        Schema::drop('page_category_permission');
+        Schema::drop('site_user_role');
+        Schema::drop('site_role');
+    }
+}
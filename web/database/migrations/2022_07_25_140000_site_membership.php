@@ -0,0 +1,81 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Support\Facades\Schema;
+
+class SiteMembership extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Applications by users to become members of a site.
+        //
+        // These are reviewed by site staff, who accept or reject them,
+        // optionally with a reply. A user may only have one pending
+        // application per site at a time.
+        DB::statement("
+            CREATE TABLE site_application (
+                application_id BIGSERIAL PRIMARY KEY,
+                site_id BIGINT NOT NULL REFERENCES site(site_id),
+                user_id BIGINT NOT NULL REFERENCES users(id),
+                message TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                reply TEXT,
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+                reviewed_at TIMESTAMP WITH TIME ZONE,
+                reviewed_by BIGINT REFERENCES users(id),
+
+                CHECK (status IN ('pending', 'accepted', 'rejected')),
+                CHECK ((status = 'pending') = (reviewed_at IS NULL))
+            )
+        ");
+
+        DB::statement("
+            CREATE UNIQUE INDEX site_application_pending_idx
+            ON site_application (site_id, user_id)
+            WHERE status = 'pending'
+        ");
+
+        // Invitations for users to become members of a site.
+        //
+        // The invited user may accept or decline. Accepting one
+        // makes them a member without needing an application.
+        DB::statement("
+            CREATE TABLE site_invitation (
+                invitation_id BIGSERIAL PRIMARY KEY,
+                site_id BIGINT NOT NULL REFERENCES site(site_id),
+                user_id BIGINT NOT NULL REFERENCES users(id),
+                invited_by BIGINT NOT NULL REFERENCES users(id),
+                message TEXT,
+                status TEXT NOT NULL DEFAULT 'pending',
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+                responded_at TIMESTAMP WITH TIME ZONE,
+
+                CHECK (status IN ('pending', 'accepted', 'declined')),
+                CHECK ((status = 'pending') = (responded_at IS NULL))
+            )
+        ");
+
+        DB::statement("
+            CREATE UNIQUE INDEX site_invitation_pending_idx
+            ON site_invitation (site_id, user_id)
+            WHERE status = 'pending'
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        Schema::drop('site_invitation');
+        Schema::drop('site_application');
+    }
+}
@@ -0,0 +1,67 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Support\Facades\Schema;
+
+class AuditLog extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Record of moderation and administrative actions.
+        //
+        // Each row captures who did what to which object, the value before
+        // and after the change, and where the request came from. The actor
+        // is nullable for actions performed by the system itself.
+        DB::statement("
+            CREATE TABLE audit_log (
+                audit_id BIGSERIAL PRIMARY KEY,
+                site_id BIGINT REFERENCES site(site_id),
+                user_id BIGINT REFERENCES users(id),
+                action TEXT NOT NULL,
+                target_type TEXT NOT NULL,
+                target_id TEXT NOT NULL,
+                value_before JSONB,
+                value_after JSONB,
+                ip_address TEXT,
+                user_agent TEXT,
+                created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+            )
+        ");
+
+        DB::statement('CREATE INDEX audit_log_site_idx ON audit_log (site_id, audit_id)');
+        DB::statement('CREATE INDEX audit_log_user_idx ON audit_log (user_id)');
+        DB::statement('CREATE INDEX audit_log_target_idx ON audit_log (target_type, target_id)');
+
+        // The audit log is append-only, reject any modification of existing rows.
+        DB::statement("
+            CREATE FUNCTION audit_log_append_only() RETURNS TRIGGER AS \$\$
+            BEGIN
+                RAISE EXCEPTION 'audit_log is append-only';
+            END;
+            \$\$ LANGUAGE plpgsql
+        ");
+
+        DB::statement("
+            CREATE TRIGGER audit_log_append_only_trigger
+            BEFORE UPDATE OR DELETE ON audit_log
+            FOR EACH ROW EXECUTE PROCEDURE audit_log_append_only()
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        Schema::drop('audit_log');
+        DB::statement('DROP FUNCTION audit_log_append_only()');
+    }
+}
@@ -0,0 +1,34 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+
+class ForumThreadArchived extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Discussion threads are closed while their page is deleted.
+        //
+        // This is kept apart from blocked, which is a moderator lock,
+        // so that restoring the page does not lift a lock set by staff.
+        DB::statement("
+            ALTER TABLE forum_thread
+                ADD COLUMN archived BOOLEAN NOT NULL DEFAULT false
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        DB::statement('ALTER TABLE forum_thread DROP COLUMN archived');
+    }
+}
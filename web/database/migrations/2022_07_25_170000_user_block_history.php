@@ -0,0 +1,72 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+
+class UserBlockHistory extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Expired bans and blocks are no longer in effect, but are never
+        // revoked, so a new one must be able to sit alongside them.
+        //
+        // An index predicate cannot depend on the current time, so only
+        // one active ban or block for each pair is ensured by DEEPWELL.
+        DB::statement('DROP INDEX user_block_site_idx');
+        DB::statement('DROP INDEX user_block_blocker_idx');
+
+        DB::statement("
+            CREATE INDEX user_block_site_idx
+                ON user_block (site_id, user_id)
+                WHERE revoked_at IS NULL
+        ");
+
+        DB::statement("
+            CREATE INDEX user_block_blocker_idx
+                ON user_block (blocker_user_id, user_id)
+                WHERE revoked_at IS NULL
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        // Revoke all but the latest unrevoked row for each pair,
+        // so the unique indexes can be restored.
+        DB::statement("
+            UPDATE user_block
+            SET revoked_at = COALESCE(expires_at, now())
+            WHERE revoked_at IS NULL
+                AND block_id NOT IN (
+                    SELECT MAX(block_id)
+                    FROM user_block
+                    WHERE revoked_at IS NULL
+                    GROUP BY site_id, blocker_user_id, user_id
+                )
+        ");
+
+        DB::statement('DROP INDEX user_block_site_idx');
+        DB::statement('DROP INDEX user_block_blocker_idx');
+
+        DB::statement("
+            CREATE UNIQUE INDEX user_block_site_idx
+                ON user_block (site_id, user_id)
+                WHERE revoked_at IS NULL
+        ");
+
+        DB::statement("
+            CREATE UNIQUE INDEX user_block_blocker_idx
+                ON user_block (blocker_user_id, user_id)
+                WHERE revoked_at IS NULL
+        ");
+    }
+}
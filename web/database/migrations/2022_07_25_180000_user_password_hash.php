@@ -0,0 +1,37 @@
+<?php
+declare(strict_types=1);
+
+use Illuminate\Database\Migrations\Migration;
+
+class UserPasswordHash extends Migration
+{
+    /**
+     * Run the migrations.
+     *
+     * @return void
+     */
+    public function up()
+    {
+        // Some older rows store the password itself rather than a hash.
+        //
+        // DEEPWELL rejects these, so hash them with bcrypt here, which
+        // is accepted on login and then upgraded to Argon2id.
+        DB::statement('CREATE EXTENSION IF NOT EXISTS pgcrypto');
+
+        DB::statement("
+            UPDATE users
+            SET password = crypt(password, gen_salt('bf'))
+            WHERE password NOT LIKE '\$%'
+        ");
+    }
+
+    /**
+     * Reverse the migrations.
+     *
+     * @return void
+     */
+    public function down()
+    {
+        // The original values cannot be recovered from the hashes.
+    }
+}